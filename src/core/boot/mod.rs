@@ -0,0 +1,230 @@
+// src/core/boot/mod.rs
+// Declarative startup: named init steps ordered by their dependencies
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::infrastructure::web::logging::BuildMetrics;
+
+type StepFn<C> = Box<dyn FnOnce(&mut C) -> Result<(), String>>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BootError {
+    DuplicateStep(String),
+    UnknownDependency { step: String, dependency: String },
+    Cycle(Vec<String>),
+    StepFailed { step: String, message: String },
+}
+
+impl fmt::Display for BootError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BootError::DuplicateStep(name) => write!(f, "Boot step '{}' is defined twice", name),
+            BootError::UnknownDependency { step, dependency } => write!(
+                f,
+                "Boot step '{}' depends on unknown step '{}'",
+                step, dependency
+            ),
+            BootError::Cycle(steps) => {
+                write!(f, "Boot steps form a dependency cycle: {}", steps.join(", "))
+            }
+            BootError::StepFailed { step, message } => {
+                write!(f, "Boot step '{}' failed: {}", step, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BootError {}
+
+struct BootStep<C> {
+    name: String,
+    depends_on: Vec<String>,
+    run: StepFn<C>,
+}
+
+/// Init steps run against a shared context `C`. Each step runs after every
+/// step it depends on; otherwise steps keep the order they were added in.
+pub struct BootSequence<C> {
+    steps: Vec<BootStep<C>>,
+}
+
+impl<C> BootSequence<C> {
+    pub fn new() -> Self {
+        Self { steps: Vec::new() }
+    }
+
+    pub fn step<F>(mut self, name: &str, depends_on: &[&str], run: F) -> Self
+    where
+        F: FnOnce(&mut C) -> Result<(), String> + 'static,
+    {
+        self.steps.push(BootStep {
+            name: name.to_string(),
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+            run: Box::new(run),
+        });
+        self
+    }
+
+    /// Adds extra dependencies to existing steps, e.g. from `[boot] after`.
+    pub fn with_dependencies(mut self, extra: &HashMap<String, Vec<String>>) -> Self {
+        for step in &mut self.steps {
+            if let Some(deps) = extra.get(&step.name) {
+                for dep in deps {
+                    if !step.depends_on.contains(dep) {
+                        step.depends_on.push(dep.clone());
+                    }
+                }
+            }
+        }
+        self
+    }
+
+    /// Resolves the execution order without running anything.
+    pub fn order(&self) -> Result<Vec<String>, BootError> {
+        self.resolve()
+            .map(|indices| indices.into_iter().map(|i| self.steps[i].name.clone()).collect())
+    }
+
+    fn resolve(&self) -> Result<Vec<usize>, BootError> {
+        let mut index = HashMap::new();
+        for (i, step) in self.steps.iter().enumerate() {
+            if index.insert(step.name.as_str(), i).is_some() {
+                return Err(BootError::DuplicateStep(step.name.clone()));
+            }
+        }
+
+        for step in &self.steps {
+            for dep in &step.depends_on {
+                if !index.contains_key(dep.as_str()) {
+                    return Err(BootError::UnknownDependency {
+                        step: step.name.clone(),
+                        dependency: dep.clone(),
+                    });
+                }
+            }
+        }
+
+        let mut done: HashSet<usize> = HashSet::new();
+        let mut ordered = Vec::with_capacity(self.steps.len());
+        while ordered.len() < self.steps.len() {
+            let next = (0..self.steps.len()).find(|i| {
+                !done.contains(i)
+                    && self.steps[*i]
+                        .depends_on
+                        .iter()
+                        .all(|d| done.contains(&index[d.as_str()]))
+            });
+
+            match next {
+                Some(i) => {
+                    done.insert(i);
+                    ordered.push(i);
+                }
+                None => {
+                    let stuck = (0..self.steps.len())
+                        .filter(|i| !done.contains(i))
+                        .map(|i| self.steps[i].name.clone())
+                        .collect();
+                    return Err(BootError::Cycle(stuck));
+                }
+            }
+        }
+
+        Ok(ordered)
+    }
+
+    /// Runs every step in dependency order, recording each one as a phase in
+    /// `metrics`. Stops at the first failing step.
+    pub fn run(self, ctx: &mut C, metrics: &mut BuildMetrics) -> Result<(), BootError> {
+        let order = self.resolve()?;
+        let mut steps: Vec<Option<BootStep<C>>> = self.steps.into_iter().map(Some).collect();
+
+        for i in order {
+            let step = steps[i].take().expect("boot step resolved twice");
+            metrics.start_phase(&step.name);
+            match (step.run)(ctx) {
+                Ok(()) => metrics.end_phase(&step.name, true, None),
+                Err(message) => {
+                    metrics.end_phase(&step.name, false, Some(&message));
+                    return Err(BootError::StepFailed {
+                        step: step.name,
+                        message,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<C> Default for BootSequence<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(name: &'static str) -> impl FnOnce(&mut Vec<String>) -> Result<(), String> {
+        move |ran: &mut Vec<String>| {
+            ran.push(name.to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn steps_run_after_their_dependencies() {
+        let boot = BootSequence::new()
+            .step("modules", &["plugins", "services"], record("modules"))
+            .step("services", &["config"], record("services"))
+            .step("plugins", &[], record("plugins"))
+            .step("config", &[], record("config"));
+
+        let mut ran = Vec::new();
+        boot.run(&mut ran, &mut BuildMetrics::new()).unwrap();
+
+        assert_eq!(ran, ["plugins", "config", "services", "modules"]);
+    }
+
+    #[test]
+    fn a_cycle_is_rejected_before_any_step_runs() {
+        let extra = HashMap::from([("config".to_string(), vec!["services".to_string()])]);
+        let boot = BootSequence::new()
+            .step("config", &[], record("config"))
+            .step("services", &["config"], record("services"))
+            .step("plugins", &[], record("plugins"))
+            .with_dependencies(&extra);
+
+        let mut ran = Vec::new();
+        let result = boot.run(&mut ran, &mut BuildMetrics::new());
+
+        assert_eq!(
+            result,
+            Err(BootError::Cycle(vec!["config".to_string(), "services".to_string()]))
+        );
+        assert!(ran.is_empty());
+    }
+
+    #[test]
+    fn a_failing_step_stops_the_sequence() {
+        let boot = BootSequence::new()
+            .step("config", &[], |_: &mut Vec<String>| Err("missing file".to_string()))
+            .step("services", &["config"], record("services"));
+
+        let mut ran = Vec::new();
+        let result = boot.run(&mut ran, &mut BuildMetrics::new());
+
+        assert_eq!(
+            result,
+            Err(BootError::StepFailed {
+                step: "config".to_string(),
+                message: "missing file".to_string(),
+            })
+        );
+        assert!(ran.is_empty());
+    }
+}
@@ -1,24 +1,96 @@
 // infrastructure/config/mod.rs
 use serde::Deserialize;
+use crate::core::logging::LogFormat;
+use crate::core::serialization::Format;
+use crate::model::entities::user::UniqueBy;
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr};
+
+pub mod validation;
+pub mod watcher;
+
+pub use validation::ConfigError;
+pub use watcher::ConfigWatcher;
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct AppConfig {
     pub app: AppSettings,
     pub database: DatabaseSettings,
+    /// Additional named databases, e.g. a plugin's own SQLite file.
+    #[serde(default)]
+    pub databases: Vec<NamedDatabaseSettings>,
     pub window: WindowSettings,
     pub logging: LoggingSettings,
+    #[serde(default)]
+    pub event_store: EventStoreSettings,
+    #[serde(default)]
+    pub features: FeatureSettings,
+    #[serde(default)]
+    pub users: UserSettings,
+    #[serde(default)]
+    pub concurrency: ConcurrencySettings,
+    #[serde(default)]
+    pub plugins: PluginSettings,
+    #[serde(default)]
+    pub boot: BootSettings,
+    #[serde(default)]
+    pub shutdown: ShutdownSettings,
+    #[serde(default)]
+    pub monitoring: MonitoringSettings,
+    /// Alternative command names mapped to their canonical command.
+    #[serde(default)]
+    pub command_aliases: HashMap<String, String>,
+    /// Role -> user fields visible in query responses; replaces that role's
+    /// built-in default. `"*"` shows everything.
+    #[serde(default)]
+    pub field_access: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub webhooks: WebhookSettings,
+    #[serde(default)]
+    pub compression: CompressionSettings,
+    #[serde(default)]
+    pub serialization: SerializationSettings,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct AppSettings {
     pub name: String,
     pub version: String,
+    /// IANA zone name (e.g. "Europe/Berlin") used to display timestamps.
+    pub timezone: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct DatabaseSettings {
     pub path: String,
     pub create_sample_data: Option<bool>,
+    /// Size of the r2d2 pool of read-only connections.
+    pub read_pool_size: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct NamedDatabaseSettings {
+    pub name: String,
+    pub path: String,
+    pub read_pool_size: Option<usize>,
+    /// Directory of `*.sql` files applied to this database only.
+    pub migrations_dir: Option<String>,
+}
+
+/// Pooled read connections when `read_pool_size` is unset. Zero would route
+/// every read through the single writer connection.
+pub const DEFAULT_READ_POOL_SIZE: usize = 4;
+
+impl DatabaseSettings {
+    pub fn read_pool_size(&self) -> usize {
+        self.read_pool_size.unwrap_or(DEFAULT_READ_POOL_SIZE)
+    }
+}
+
+impl NamedDatabaseSettings {
+    pub fn read_pool_size(&self) -> usize {
+        self.read_pool_size.unwrap_or(DEFAULT_READ_POOL_SIZE)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -26,6 +98,114 @@ pub struct WindowSettings {
     pub title: String,
     pub width: Option<u32>,
     pub height: Option<u32>,
+    /// Address the WebUI server listens on; loopback unless set. WebUI can
+    /// only listen on loopback or on every interface, so the only accepted
+    /// non-loopback values are `0.0.0.0` and `::`.
+    pub bind_address: Option<String>,
+    /// Must be true for a non-loopback `bind_address` to be accepted.
+    #[serde(default)]
+    pub allow_external: bool,
+    /// Exact port WebUI must listen on, e.g. behind a reverse proxy.
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// Ports WebUI may listen on; an ephemeral port when unset.
+    #[serde(default)]
+    pub port_range: Option<PortRange>,
+}
+
+/// Inclusive range of candidate ports.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct PortRange {
+    pub min: u16,
+    pub max: u16,
+}
+
+impl PortRange {
+    pub fn candidates(&self) -> std::ops::RangeInclusive<u16> {
+        self.min..=self.max
+    }
+}
+
+/// How the WebUI server port is chosen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortStrategy {
+    /// Use exactly this port or fail.
+    Fixed(u16),
+    /// Try each port of the range in order.
+    Range(PortRange),
+    /// Let the OS pick, retrying a few times.
+    Ephemeral,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindAddress {
+    Loopback(IpAddr),
+    External(IpAddr),
+}
+
+impl BindAddress {
+    pub fn ip(&self) -> IpAddr {
+        match self {
+            BindAddress::Loopback(ip) | BindAddress::External(ip) => *ip,
+        }
+    }
+
+    pub fn is_external(&self) -> bool {
+        matches!(self, BindAddress::External(_))
+    }
+}
+
+impl WindowSettings {
+    pub const MIN_DIMENSION: u32 = 200;
+    pub const MAX_DIMENSION: u32 = 16384;
+
+    /// Configured `(width, height)`, or `None` when either is unset so WebUI
+    /// keeps its default size. `AppConfig::validate` rejects sizes outside
+    /// `MIN_DIMENSION..=MAX_DIMENSION` before the window is created.
+    pub fn window_size(&self) -> Option<(u32, u32)> {
+        Some((self.width?, self.height?))
+    }
+
+    /// `port` wins over `port_range`; validation rejects setting both.
+    pub fn port_strategy(&self) -> PortStrategy {
+        match (self.port, self.port_range) {
+            (Some(port), _) => PortStrategy::Fixed(port),
+            (None, Some(range)) => PortStrategy::Range(range),
+            (None, None) => PortStrategy::Ephemeral,
+        }
+    }
+
+    /// Loopback by default. Listening on all interfaces (`0.0.0.0` or `::`)
+    /// requires `allow_external`; a specific non-loopback address is
+    /// rejected because WebUI cannot bind to one.
+    pub fn resolve_bind_address(&self) -> Result<BindAddress, String> {
+        let Some(raw) = self.bind_address.as_deref().map(str::trim).filter(|s| !s.is_empty()) else {
+            return Ok(BindAddress::Loopback(IpAddr::V4(Ipv4Addr::LOCALHOST)));
+        };
+
+        let ip: IpAddr = if raw.eq_ignore_ascii_case("localhost") {
+            IpAddr::V4(Ipv4Addr::LOCALHOST)
+        } else {
+            raw.parse()
+                .map_err(|_| format!("window.bind_address '{}' is not an IP address", raw))?
+        };
+
+        if ip.is_loopback() {
+            Ok(BindAddress::Loopback(ip))
+        } else if !ip.is_unspecified() {
+            Err(format!(
+                "window.bind_address '{}' is not supported; WebUI listens on loopback or on all interfaces (0.0.0.0 or ::)",
+                raw
+            ))
+        } else if self.allow_external {
+            Ok(BindAddress::External(ip))
+        } else {
+            Err(format!(
+                "window.bind_address '{}' is not loopback; set window.allow_external = true to expose it",
+                raw
+            ))
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -33,6 +213,223 @@ pub struct LoggingSettings {
     pub level: String,
     pub file: String,
     pub append: Option<bool>,
+    /// Used when the platform data directory cannot be determined.
+    pub fallback_dir: Option<String>,
+    /// Module prefix -> level, e.g. `"core::event_bus" = "debug"`.
+    #[serde(default)]
+    pub modules: HashMap<String, String>,
+    /// `"text"` (default) or `"json"` lines.
+    #[serde(default)]
+    pub format: LogFormat,
+    /// Rotate the log file once it reaches this size.
+    #[serde(default = "default_max_file_size_mb")]
+    pub max_file_size_mb: u64,
+    /// Rotated files kept alongside the live one.
+    #[serde(default = "default_max_files")]
+    pub max_files: usize,
+}
+
+fn default_max_file_size_mb() -> u64 {
+    10
+}
+
+fn default_max_files() -> usize {
+    5
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct EventStoreSettings {
+    /// Acknowledged events older than this are pruned.
+    pub max_age_days: Option<u32>,
+    /// Oldest acknowledged events are pruned beyond this many rows.
+    pub max_rows: Option<usize>,
+    pub prune_interval_secs: u64,
+}
+
+impl Default for EventStoreSettings {
+    fn default() -> Self {
+        Self {
+            max_age_days: Some(30),
+            max_rows: Some(100_000),
+            prune_interval_secs: 3600,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct FeatureSettings {
+    /// Feature module name -> enabled. Modules not listed are enabled.
+    #[serde(default)]
+    pub modules: HashMap<String, bool>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct UserSettings {
+    /// Email domains accepted for new or updated users; empty allows all.
+    #[serde(default)]
+    pub allowed_email_domains: Vec<String>,
+    /// `"email"`, `"external_id"` or `"both"`.
+    #[serde(default)]
+    pub unique_by: UniqueBy,
+    /// Role the local session acts as until a sign-in sets one; unset means
+    /// guest, which may only query.
+    #[serde(default)]
+    pub actor_role: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct BootSettings {
+    /// Extra startup ordering, e.g. `plugins = ["services"]` runs plugins after DI.
+    #[serde(default)]
+    pub after: HashMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct ShutdownSettings {
+    /// How long background tasks get to finish before they are aborted.
+    pub grace_period_secs: u64,
+}
+
+impl Default for ShutdownSettings {
+    fn default() -> Self {
+        Self {
+            grace_period_secs: 10,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OverflowMode {
+    /// Wait up to `wait_timeout_ms` for a slot, then report busy.
+    Wait,
+    /// Report busy immediately.
+    Reject,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct ConcurrencySettings {
+    pub mode: OverflowMode,
+    pub wait_timeout_ms: u64,
+    /// Maximum concurrent executions per command; unlisted commands are unlimited.
+    pub limits: HashMap<String, usize>,
+}
+
+impl Default for ConcurrencySettings {
+    fn default() -> Self {
+        Self {
+            mode: OverflowMode::Wait,
+            wait_timeout_ms: 2000,
+            limits: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct PluginSettings {
+    /// Longest a single plugin command or query may run before it is abandoned.
+    pub call_timeout_ms: u64,
+    /// Shared libraries in this directory are loaded as plugins at startup.
+    pub directory: String,
+}
+
+impl Default for PluginSettings {
+    fn default() -> Self {
+        Self {
+            call_timeout_ms: 5000,
+            directory: String::from("plugins"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct MonitoringSettings {
+    pub sample_interval_ms: u64,
+    /// Per-metric alert thresholds, in units per second.
+    pub rate_thresholds: HashMap<String, f64>,
+}
+
+impl Default for MonitoringSettings {
+    fn default() -> Self {
+        Self {
+            sample_interval_ms: 1000,
+            rate_thresholds: HashMap::from([(String::from("events_failed"), 10.0)]),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct WebhookSettings {
+    /// HMAC-SHA256 key used to sign request bodies; unsigned when absent.
+    pub secret: Option<String>,
+    pub queue_size: usize,
+    pub max_retries: u32,
+    pub endpoints: Vec<WebhookEndpoint>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct WebhookEndpoint {
+    pub url: String,
+    pub events: Vec<String>,
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        Self {
+            secret: None,
+            queue_size: 256,
+            max_retries: 3,
+            endpoints: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionAlgorithm {
+    Gzip,
+    Deflate,
+}
+
+impl CompressionAlgorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompressionAlgorithm::Gzip => "gzip",
+            CompressionAlgorithm::Deflate => "deflate",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct CompressionSettings {
+    pub enabled: bool,
+    pub algorithm: CompressionAlgorithm,
+    /// Responses smaller than this many bytes are always sent as plain JSON.
+    pub threshold_bytes: usize,
+}
+
+impl Default for CompressionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            algorithm: CompressionAlgorithm::Gzip,
+            threshold_bytes: 16 * 1024,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct SerializationSettings {
+    /// Encoding of handler responses and exported event payloads.
+    #[serde(default)]
+    pub format: Format,
 }
 
 impl Default for AppConfig {
@@ -41,36 +438,225 @@ impl Default for AppConfig {
             app: AppSettings {
                 name: String::from("Rust WebUI Application"),
                 version: String::from("1.0.0"),
+                timezone: None,
             },
             database: DatabaseSettings {
                 path: String::from("app.db"),
                 create_sample_data: Some(true),
+                read_pool_size: Some(DEFAULT_READ_POOL_SIZE),
             },
+            databases: Vec::new(),
             window: WindowSettings {
                 title: String::from("Rust WebUI Application"),
                 width: Some(1200),
                 height: Some(800),
+                bind_address: None,
+                allow_external: false,
+                port: None,
+                port_range: None,
             },
             logging: LoggingSettings {
                 level: String::from("info"),
                 file: String::from("application.log"),
                 append: Some(true),
+                fallback_dir: None,
+                modules: HashMap::new(),
+                format: LogFormat::Text,
+                max_file_size_mb: default_max_file_size_mb(),
+                max_files: default_max_files(),
             },
+            event_store: EventStoreSettings::default(),
+            features: FeatureSettings::default(),
+            users: UserSettings::default(),
+            concurrency: ConcurrencySettings::default(),
+            plugins: PluginSettings::default(),
+            boot: BootSettings::default(),
+            shutdown: ShutdownSettings::default(),
+            monitoring: MonitoringSettings::default(),
+            command_aliases: HashMap::new(),
+            field_access: HashMap::new(),
+            webhooks: WebhookSettings::default(),
+            compression: CompressionSettings::default(),
+            serialization: SerializationSettings::default(),
         }
     }
 }
 
 impl AppConfig {
-    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
-        let config_paths = ["app.config.toml", "./app.config.toml"];
+    pub const CONFIG_PATH_ENV: &'static str = "APP_CONFIG_PATH";
+
+    /// Candidate files in priority order; TOML wins when several exist.
+    const CONFIG_PATHS: [&'static str; 4] = [
+        "app.config.toml",
+        "app.config.json",
+        "app.config.yaml",
+        "app.config.yml",
+    ];
 
-        for path in &config_paths {
-            if std::path::Path::new(path).exists() {
-                let content = std::fs::read_to_string(path)?;
-                return Ok(toml::from_str(&content)?);
+    /// Path given with `--config <path>` / `--config=<path>`, else `APP_CONFIG_PATH`.
+    pub fn explicit_path(args: &[String], env_path: Option<String>) -> Option<std::path::PathBuf> {
+        let from_args = args.iter().enumerate().find_map(|(i, arg)| {
+            if arg == "--config" {
+                args.get(i + 1).cloned()
+            } else {
+                arg.strip_prefix("--config=").map(str::to_string)
             }
+        });
+        from_args
+            .or(env_path)
+            .filter(|p| !p.trim().is_empty())
+            .map(std::path::PathBuf::from)
+    }
+
+    /// Config files that exist, in priority order. `load` uses the first.
+    pub fn existing_config_files() -> Vec<std::path::PathBuf> {
+        Self::CONFIG_PATHS
+            .iter()
+            .map(std::path::PathBuf::from)
+            .filter(|path| path.exists())
+            .collect()
+    }
+
+    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
+        match Self::existing_config_files().first() {
+            Some(path) => Self::load_from(path),
+            None => Ok(AppConfig::default()),
         }
+    }
+
+    /// Parses a config file, choosing the format from its extension.
+    pub fn load_from(path: &std::path::Path) -> Result<Self, Box<dyn std::error::Error>> {
+        if !path.is_file() {
+            return Err(format!("Config file {} not found", path.display()).into());
+        }
+        let content = std::fs::read_to_string(path)?;
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase);
+
+        let parsed: Result<Self, Box<dyn std::error::Error>> = match extension.as_deref() {
+            Some("toml") => toml::from_str(&content).map_err(Into::into),
+            Some("json") => serde_json::from_str(&content).map_err(Into::into),
+            Some("yaml") | Some("yml") => serde_yaml::from_str(&content).map_err(Into::into),
+            _ => Err(format!("Unsupported config format: {}", path.display()).into()),
+        };
+        parsed.map_err(|e| format!("Failed to parse {}: {}", path.display(), e).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(extra: &str) -> WindowSettings {
+        toml::from_str(&format!("title = \"test\"\n{}", extra)).unwrap()
+    }
+
+    #[test]
+    fn bind_address_defaults_to_loopback() {
+        let loopback = BindAddress::Loopback(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        assert_eq!(window("").resolve_bind_address(), Ok(loopback));
+        assert_eq!(window("bind_address = \"localhost\"").resolve_bind_address(), Ok(loopback));
+        assert_eq!(
+            window("bind_address = \"::1\"").resolve_bind_address(),
+            Ok(BindAddress::Loopback("::1".parse().unwrap()))
+        );
+    }
+
+    #[test]
+    fn all_interfaces_require_allow_external() {
+        assert!(window("bind_address = \"0.0.0.0\"").resolve_bind_address().is_err());
+
+        let allowed = window("bind_address = \"0.0.0.0\"\nallow_external = true");
+        assert_eq!(
+            allowed.resolve_bind_address(),
+            Ok(BindAddress::External(IpAddr::V4(Ipv4Addr::UNSPECIFIED)))
+        );
+        let allowed_v6 = window("bind_address = \"::\"\nallow_external = true");
+        assert!(allowed_v6.resolve_bind_address().unwrap().is_external());
+    }
+
+    #[test]
+    fn a_specific_external_address_is_rejected_even_when_allowed() {
+        let specific = window("bind_address = \"192.168.1.20\"\nallow_external = true");
+        let error = specific.resolve_bind_address().unwrap_err();
+        assert!(error.contains("all interfaces"), "{}", error);
+        assert!(window("bind_address = \"not-an-ip\"").resolve_bind_address().is_err());
+    }
+
+    const REQUIRED: &str = r#"
+        [app]
+        name = "test"
+        version = "1.0.0"
+
+        [database]
+        path = ":memory:"
+
+        [window]
+        title = "test"
+
+        [logging]
+        level = "info"
+        file = "test.log"
+    "#;
+
+    #[test]
+    fn partial_tables_fall_back_to_defaults() {
+        let content = format!(
+            "{}\n{}",
+            REQUIRED,
+            r#"
+            [concurrency]
+            limits = { create_user = 2 }
+
+            [plugins]
+            directory = "extensions"
+
+            [monitoring]
+            sample_interval_ms = 500
+
+            [webhooks]
+            secret = "s3cret"
+
+            [event_store]
+            max_rows = 10
+
+            [shutdown]
+
+            [compression]
+            enabled = true
+            "#
+        );
+        let config: AppConfig = toml::from_str(&content).unwrap();
+
+        assert_eq!(config.concurrency.limits.get("create_user"), Some(&2));
+        assert_eq!(config.concurrency.mode, OverflowMode::Wait);
+        assert_eq!(config.concurrency.wait_timeout_ms, 2000);
+        assert_eq!(config.plugins.directory, "extensions");
+        assert_eq!(config.plugins.call_timeout_ms, 5000);
+        assert_eq!(config.monitoring.rate_thresholds.get("events_failed"), Some(&10.0));
+        assert_eq!(config.webhooks.queue_size, 256);
+        assert!(config.webhooks.endpoints.is_empty());
+        assert_eq!(config.event_store.max_rows, Some(10));
+        assert_eq!(config.event_store.prune_interval_secs, 3600);
+        assert_eq!(config.shutdown.grace_period_secs, 10);
+        assert_eq!(config.compression.threshold_bytes, 16 * 1024);
+    }
+
+    #[test]
+    fn port_strategy_prefers_a_fixed_port_then_a_range() {
+        assert_eq!(window("").port_strategy(), PortStrategy::Ephemeral);
 
-        Ok(AppConfig::default())
+        let range = PortRange { min: 8080, max: 8090 };
+        assert_eq!(
+            window("port_range = { min = 8080, max = 8090 }").port_strategy(),
+            PortStrategy::Range(range)
+        );
+        assert_eq!(window("port = 9000").port_strategy(), PortStrategy::Fixed(9000));
+        assert_eq!(
+            window("port = 9000\nport_range = { min = 8080, max = 8090 }").port_strategy(),
+            PortStrategy::Fixed(9000)
+        );
     }
 }
@@ -0,0 +1,159 @@
+// infrastructure/config/validation.rs
+// Semantic checks on a parsed AppConfig
+
+use super::{AppConfig, WindowSettings};
+
+pub const KNOWN_LOG_LEVELS: &[&str] = &["trace", "debug", "info", "warn", "warning", "error"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    EmptyField(&'static str),
+    UnknownLogLevel(String),
+    DimensionOutOfRange { field: &'static str, value: u32 },
+    InvalidPortRange { min: u16, max: u16 },
+    InvalidPort(u16),
+    ConflictingPortSettings,
+    /// Every problem found when there is more than one.
+    Multiple(Vec<ConfigError>),
+}
+
+impl ConfigError {
+    /// The individual problems, flattening `Multiple`.
+    pub fn problems(&self) -> Vec<&ConfigError> {
+        match self {
+            ConfigError::Multiple(errors) => errors.iter().flat_map(|e| e.problems()).collect(),
+            other => vec![other],
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::EmptyField(field) => write!(f, "{} must not be empty", field),
+            ConfigError::UnknownLogLevel(level) => write!(
+                f,
+                "logging.level '{}' is not one of {}",
+                level,
+                KNOWN_LOG_LEVELS.join(", ")
+            ),
+            ConfigError::DimensionOutOfRange { field, value } => write!(
+                f,
+                "{} = {} is outside {}..={}",
+                field,
+                value,
+                WindowSettings::MIN_DIMENSION,
+                WindowSettings::MAX_DIMENSION
+            ),
+            ConfigError::InvalidPortRange { min, max } => write!(
+                f,
+                "window.port_range {}..={} must be non-empty and exclude port 0",
+                min, max
+            ),
+            ConfigError::InvalidPort(port) => write!(f, "window.port {} is not a usable port", port),
+            ConfigError::ConflictingPortSettings => {
+                write!(f, "window.port and window.port_range are mutually exclusive")
+            }
+            ConfigError::Multiple(errors) => {
+                write!(f, "{} configuration problems:", errors.len())?;
+                for error in errors {
+                    write!(f, "\n  - {}", error)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl AppConfig {
+    /// Checks values that parse fine but make no sense, reporting all of them.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut errors = Vec::new();
+
+        if self.app.name.trim().is_empty() {
+            errors.push(ConfigError::EmptyField("app.name"));
+        }
+        if self.database.path.trim().is_empty() {
+            errors.push(ConfigError::EmptyField("database.path"));
+        }
+
+        let level = self.logging.level.to_lowercase();
+        if !KNOWN_LOG_LEVELS.contains(&level.as_str()) {
+            errors.push(ConfigError::UnknownLogLevel(self.logging.level.clone()));
+        }
+
+        let range = WindowSettings::MIN_DIMENSION..=WindowSettings::MAX_DIMENSION;
+        for (field, value) in [("window.width", self.window.width), ("window.height", self.window.height)] {
+            if let Some(value) = value.filter(|v| !range.contains(v)) {
+                errors.push(ConfigError::DimensionOutOfRange { field, value });
+            }
+        }
+
+        if self.window.port == Some(0) {
+            errors.push(ConfigError::InvalidPort(0));
+        }
+        if self.window.port.is_some() && self.window.port_range.is_some() {
+            errors.push(ConfigError::ConflictingPortSettings);
+        }
+        if let Some(range) = self
+            .window
+            .port_range
+            .filter(|range| range.min == 0 || range.min > range.max)
+        {
+            errors.push(ConfigError::InvalidPortRange {
+                min: range.min,
+                max: range.max,
+            });
+        }
+
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            _ => Err(ConfigError::Multiple(errors)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_with_size(width: Option<u32>, height: Option<u32>) -> AppConfig {
+        let mut config = AppConfig::default();
+        config.window.width = width;
+        config.window.height = height;
+        config
+    }
+
+    #[test]
+    fn window_dimensions_outside_the_bounds_are_rejected() {
+        assert_eq!(
+            config_with_size(Some(0), Some(600)).validate(),
+            Err(ConfigError::DimensionOutOfRange { field: "window.width", value: 0 })
+        );
+        assert_eq!(
+            config_with_size(Some(800), Some(16385)).validate(),
+            Err(ConfigError::DimensionOutOfRange { field: "window.height", value: 16385 })
+        );
+
+        let both = config_with_size(Some(0), Some(20000)).validate().unwrap_err();
+        assert_eq!(
+            both.problems(),
+            [
+                &ConfigError::DimensionOutOfRange { field: "window.width", value: 0 },
+                &ConfigError::DimensionOutOfRange { field: "window.height", value: 20000 },
+            ]
+        );
+    }
+
+    #[test]
+    fn window_dimensions_at_the_bounds_or_unset_are_accepted() {
+        let (min, max) = (WindowSettings::MIN_DIMENSION, WindowSettings::MAX_DIMENSION);
+
+        assert_eq!(config_with_size(Some(min), Some(max)).validate(), Ok(()));
+        assert_eq!(config_with_size(None, None).validate(), Ok(()));
+        assert_eq!(config_with_size(Some(min), Some(max)).window.window_size(), Some((min, max)));
+    }
+}
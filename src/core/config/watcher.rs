@@ -0,0 +1,196 @@
+// infrastructure/config/watcher.rs
+// Reloads AppConfig when its file changes on disk
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, RwLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+use super::AppConfig;
+
+/// Editors often write a file in several steps; changes this close together
+/// are reloaded once.
+const DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// A file that keeps changing is still reloaded this long after the first change.
+const MAX_SETTLE: Duration = Duration::from_secs(2);
+
+/// Watches a config file on a background thread. The callback only sees
+/// configs that parsed; a bad edit keeps the last good config and logs a warning.
+pub struct ConfigWatcher {
+    watcher: Option<RecommendedWatcher>,
+    thread: Option<JoinHandle<()>>,
+    current: Arc<RwLock<AppConfig>>,
+}
+
+impl ConfigWatcher {
+    pub fn watch<F>(path: &Path, initial: AppConfig, on_change: F) -> Result<Self, Box<dyn std::error::Error>>
+    where
+        F: Fn(&AppConfig) + Send + 'static,
+    {
+        let path = path.canonicalize()?;
+        // Watch the directory so atomic save-by-rename is still seen.
+        let dir = path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        let (tx, rx) = mpsc::channel::<notify::Result<notify::Event>>();
+        let mut watcher = notify::recommended_watcher(tx)?;
+        watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+
+        let current = Arc::new(RwLock::new(initial));
+        let thread = {
+            let current = current.clone();
+            std::thread::Builder::new()
+                .name("config-watcher".to_string())
+                .spawn(move || {
+                    // Ends when the watcher, and with it the sender, is dropped.
+                    while let Ok(event) = rx.recv() {
+                        if !touches(&event, &path) {
+                            continue;
+                        }
+                        if !settle(&rx, &path) {
+                            break;
+                        }
+
+                        let reloaded = AppConfig::load_from(&path).and_then(|config| {
+                            config.validate()?;
+                            Ok(config)
+                        });
+                        match reloaded {
+                            Ok(config) => {
+                                info!("Config reloaded from {}", path.display());
+                                on_change(&config);
+                                *current.write().unwrap() = config;
+                            }
+                            Err(e) => warn!("Keeping previous config; reload failed: {}", e),
+                        }
+                    }
+                })?
+        };
+
+        Ok(Self {
+            watcher: Some(watcher),
+            thread: Some(thread),
+            current,
+        })
+    }
+
+    /// The most recent config that parsed successfully.
+    pub fn current(&self) -> AppConfig {
+        self.current.read().unwrap().clone()
+    }
+
+    /// Stops watching and waits for the background thread to exit.
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.watcher.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for ConfigWatcher {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Waits until the config file has been quiet for `DEBOUNCE`, or `MAX_SETTLE`
+/// has passed. Changes to other files in the directory are ignored. Returns
+/// false once the watcher is gone.
+fn settle(rx: &Receiver<notify::Result<notify::Event>>, path: &Path) -> bool {
+    let deadline = Instant::now() + MAX_SETTLE;
+    let mut quiet_until = Instant::now() + DEBOUNCE;
+    loop {
+        let wait_until = quiet_until.min(deadline);
+        let Some(remaining) = wait_until.checked_duration_since(Instant::now()) else {
+            return true;
+        };
+        match rx.recv_timeout(remaining) {
+            Ok(event) if touches(&event, path) => quiet_until = Instant::now() + DEBOUNCE,
+            Ok(_) => {}
+            Err(RecvTimeoutError::Timeout) => return true,
+            Err(RecvTimeoutError::Disconnected) => return false,
+        }
+    }
+}
+
+fn touches(event: &notify::Result<notify::Event>, path: &Path) -> bool {
+    match event {
+        Ok(event) => {
+            (event.kind.is_modify() || event.kind.is_create())
+                && event.paths.iter().any(|p| p.file_name() == path.file_name())
+        }
+        Err(e) => {
+            warn!("Config watcher error: {}", e);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{EventKind, ModifyKind};
+
+    fn modified(path: &str) -> notify::Result<notify::Event> {
+        Ok(notify::Event::new(EventKind::Modify(ModifyKind::Any)).add_path(PathBuf::from(path)))
+    }
+
+    #[test]
+    fn sibling_changes_do_not_delay_the_reload() {
+        let (tx, rx) = mpsc::channel();
+        let writer = std::thread::spawn(move || {
+            for _ in 0..100 {
+                if tx.send(modified("/app/app.db-wal")).is_err() {
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(20));
+            }
+        });
+
+        let started = Instant::now();
+        assert!(settle(&rx, Path::new("/app/app.config.toml")));
+        assert!(started.elapsed() < DEBOUNCE * 2);
+
+        drop(rx);
+        writer.join().unwrap();
+    }
+
+    #[test]
+    fn a_constantly_changing_config_still_reloads() {
+        let (tx, rx) = mpsc::channel();
+        let writer = std::thread::spawn(move || {
+            for _ in 0..200 {
+                if tx.send(modified("/app/app.config.toml")).is_err() {
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(20));
+            }
+        });
+
+        let started = Instant::now();
+        assert!(settle(&rx, Path::new("/app/app.config.toml")));
+        assert!(started.elapsed() < MAX_SETTLE + DEBOUNCE);
+
+        drop(rx);
+        writer.join().unwrap();
+    }
+
+    #[test]
+    fn settle_reports_a_dropped_watcher() {
+        let (tx, rx) = mpsc::channel::<notify::Result<notify::Event>>();
+        drop(tx);
+        assert!(!settle(&rx, Path::new("/app/app.config.toml")));
+    }
+}
@@ -1,4 +1,135 @@
 // infrastructure/event_bus/async_event_bus.rs
-pub use super::in_memory::InMemoryEventBus;
+use crate::core::domain::errors::DomainError;
+use crate::core::domain::events::{DomainEvent, Envelope, EventEnvelope, EventMetadata, EventRecord};
+use crate::core::ports::event_bus::EventBus;
+use log::warn;
+use tokio::sync::broadcast;
 
-pub type AsyncEventBus = InMemoryEventBus;
+pub const DEFAULT_CAPACITY: usize = 1024;
+
+/// Multi-consumer bus: every `subscribe()` gets its own receiver and sees
+/// every event published after it subscribed, independently of the others.
+///
+/// `ServiceProvider` does not create one; the application's bus is
+/// `InMemoryEventBus`. Construct this where several consumers each need
+/// their own ordered stream, such as background tasks on the shared runtime,
+/// and hand each of them a `subscribe()` receiver.
+#[derive(Clone)]
+pub struct AsyncEventBus {
+    tx: broadcast::Sender<EventEnvelope>,
+    source: String,
+}
+
+impl AsyncEventBus {
+    pub fn new(capacity: usize, source: &str) -> Self {
+        let (tx, _) = broadcast::channel(capacity.max(1));
+        Self {
+            tx,
+            source: source.to_string(),
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<EventEnvelope> {
+        self.tx.subscribe()
+    }
+
+    pub fn receiver_count(&self) -> usize {
+        self.tx.receiver_count()
+    }
+
+    /// Sends an already-built envelope; returns how many receivers got it.
+    pub fn publish_envelope(&self, envelope: EventEnvelope) -> usize {
+        self.tx.send(envelope).unwrap_or(0)
+    }
+
+    /// Next envelope for `receiver`. A receiver that fell behind skips what it
+    /// missed with a warning; `None` once the bus is gone.
+    pub async fn recv(receiver: &mut broadcast::Receiver<EventEnvelope>) -> Option<EventEnvelope> {
+        loop {
+            match receiver.recv().await {
+                Ok(envelope) => return Some(envelope),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Async event receiver lagged; skipped {} event(s)", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+impl Default for AsyncEventBus {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY, "async_event_bus")
+    }
+}
+
+impl EventBus for AsyncEventBus {
+    fn publish(&self, event: Box<dyn DomainEvent>) -> Result<(), DomainError> {
+        let metadata = EventMetadata::current(&self.source);
+        self.publish_with_metadata(event, metadata)
+    }
+
+    fn publish_with_metadata(
+        &self,
+        event: Box<dyn DomainEvent>,
+        metadata: EventMetadata,
+    ) -> Result<(), DomainError> {
+        let envelope = Envelope {
+            metadata,
+            payload: EventRecord::from_event(event.as_ref()),
+        };
+        // No receivers is not an error: nobody is listening yet.
+        self.publish_envelope(envelope);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::domain::events::UserCreatedEvent;
+    use crate::core::domain::user::UserId;
+    use crate::core::runtime;
+
+    fn user_created(id: i64) -> Box<dyn DomainEvent> {
+        Box::new(UserCreatedEvent::new(UserId(id), "Ada".into(), "ada@example.com".into()))
+    }
+
+    #[test]
+    fn every_receiver_sees_a_published_event() {
+        let bus = AsyncEventBus::new(8, "test");
+        let (mut first, mut second) = (bus.subscribe(), bus.subscribe());
+        assert_eq!(bus.receiver_count(), 2);
+
+        bus.publish(user_created(1)).unwrap();
+
+        runtime::shared().block_on(async {
+            for receiver in [&mut first, &mut second] {
+                let envelope = AsyncEventBus::recv(receiver).await.unwrap();
+                assert_eq!(envelope.payload.event_type, "user.created");
+                assert_eq!(envelope.payload.aggregate_id, "1");
+                assert_eq!(envelope.metadata.source, "test");
+            }
+        });
+    }
+
+    #[test]
+    fn lagged_receiver_skips_to_the_oldest_retained_event() {
+        let bus = AsyncEventBus::new(2, "test");
+        let mut receiver = bus.subscribe();
+
+        for id in 1..=5 {
+            bus.publish(user_created(id)).unwrap();
+        }
+
+        runtime::shared().block_on(async {
+            let ids: Vec<String> = vec![
+                AsyncEventBus::recv(&mut receiver).await.unwrap().payload.aggregate_id,
+                AsyncEventBus::recv(&mut receiver).await.unwrap().payload.aggregate_id,
+            ];
+            assert_eq!(ids, ["4", "5"]);
+        });
+        drop(bus);
+        assert!(runtime::shared().block_on(AsyncEventBus::recv(&mut receiver)).is_none());
+    }
+}
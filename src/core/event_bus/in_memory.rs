@@ -1,42 +1,111 @@
 // infrastructure/event_bus/in_memory.rs
-use crate::core::domain::events::DomainEvent;
+use crate::core::domain::events::{DomainEvent, EventMetadata};
 use crate::core::domain::errors::DomainError;
-use crate::core::ports::event_bus::{EventBus, EventHandler};
+use crate::core::ports::event_bus::{EventBus, EventHandler, EventJournal};
 use crate::core::ports::logger::Logger;
-use crate::infrastructure::event_bus::{EventBusConfig, EventBusMetrics, Subscription};
+use crate::core::runtime;
+use crate::core::trace;
+use crate::infrastructure::event_bus::{
+    event_type_matches, AsyncEventHandlerFn, EventBusConfig, EventBusMetrics, Subscription,
+};
 use crate::infrastructure::logging::StructuredLogger;
+use futures::future::BoxFuture;
+use futures::Stream;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, TryLockError};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
 use tokio::sync::Mutex;
 
+const MAX_STREAM_BUFFER: usize = 4096;
+const PROBE_INTERVAL: Duration = Duration::from_millis(5);
+
+type SyncHandlerFn = Arc<dyn Fn(&EventMetadata, String, serde_json::Value) + Send + Sync>;
+
+/// Handlers by subscription key, each kept with its subscription id so
+/// `unsubscribe` can drop it.
+type HandlerMap<H> = HashMap<String, Vec<(String, H)>>;
+
+/// Subscription keys that receive `event_type`: the exact key first, then
+/// wildcard patterns (`user.*`, `*`) in lexical order.
+fn matching_keys<V>(handlers: &HashMap<String, V>, event_type: &str) -> Vec<String> {
+    let mut patterns: Vec<String> = handlers
+        .keys()
+        .filter(|key| key.as_str() != event_type && key.ends_with('*'))
+        .filter(|key| event_type_matches(key, event_type))
+        .cloned()
+        .collect();
+    patterns.sort();
+
+    let mut keys = Vec::with_capacity(patterns.len() + 1);
+    if handlers.contains_key(event_type) {
+        keys.push(event_type.to_string());
+    }
+    keys.extend(patterns);
+    keys
+}
+
 pub struct InMemoryEventBus {
-    handlers: Arc<Mutex<HashMap<String, Vec<Box<dyn Fn(String, serde_json::Value) + Send + Sync>>>>>,
+    // Std locks: they are only held to clone handler `Arc`s or bump counters,
+    // never across an await, so publishing never enters an executor and may
+    // itself run inside `block_on`.
+    handlers: Arc<std::sync::Mutex<HandlerMap<SyncHandlerFn>>>,
+    async_handlers: Arc<std::sync::RwLock<HandlerMap<AsyncEventHandlerFn>>>,
     subscriptions: Arc<Mutex<HashMap<String, Vec<Subscription>>>>,
     config: EventBusConfig,
-    metrics: Arc<Mutex<EventBusMetrics>>,
+    metrics: Arc<std::sync::Mutex<EventBusMetrics>>,
     logger: StructuredLogger,
+    stream_tx: broadcast::Sender<(EventMetadata, serde_json::Value)>,
+    journal: Arc<std::sync::RwLock<Option<Arc<dyn EventJournal>>>>,
 }
 
 impl InMemoryEventBus {
     pub fn new(config: EventBusConfig, logger: StructuredLogger) -> Self {
+        let (stream_tx, _) = broadcast::channel(config.max_queue_size.clamp(1, MAX_STREAM_BUFFER));
         Self {
-            handlers: Arc::new(Mutex::new(HashMap::new())),
+            handlers: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            async_handlers: Arc::new(std::sync::RwLock::new(HashMap::new())),
             subscriptions: Arc::new(Mutex::new(HashMap::new())),
             config,
-            metrics: Arc::new(Mutex::new(EventBusMetrics::new())),
+            metrics: Arc::new(std::sync::Mutex::new(EventBusMetrics::new())),
             logger,
+            stream_tx,
+            journal: Arc::new(std::sync::RwLock::new(None)),
         }
     }
 
+    /// Records every event published from now on in `journal`.
+    pub fn set_journal(&self, journal: Arc<dyn EventJournal>) {
+        *self.journal.write().unwrap_or_else(|e| e.into_inner()) = Some(journal);
+    }
+
+    /// Subscribes to an exact event type, a prefix pattern such as `user.*`,
+    /// or `*` for every event. On publish, exact subscribers run before
+    /// wildcard ones.
     pub async fn subscribe<F>(&self, event_type: &str, handler: F) -> String 
     where 
         F: Fn(String, serde_json::Value) + Send + Sync + 'static,
+    {
+        self.subscribe_with_metadata(event_type, move |_, event_type, payload| {
+            handler(event_type, payload)
+        })
+        .await
+    }
+
+    /// Like `subscribe`, but the handler also receives the event's metadata:
+    /// event id, source, and correlation/causation ids.
+    pub async fn subscribe_with_metadata<F>(&self, event_type: &str, handler: F) -> String
+    where
+        F: Fn(&EventMetadata, String, serde_json::Value) + Send + Sync + 'static,
     {
         let subscription_id = uuid::Uuid::new_v4().to_string();
 
-        let mut handlers = self.handlers.lock().await;
-        let handlers_for_type = handlers.entry(event_type.to_string()).or_insert_with(Vec::new);
-        handlers_for_type.push(Box::new(handler));
+        self.handlers
+            .lock()
+            .unwrap()
+            .entry(event_type.to_string())
+            .or_default()
+            .push((subscription_id.clone(), Arc::new(handler)));
 
         let mut subscriptions = self.subscriptions.lock().await;
         subscriptions
@@ -55,24 +124,140 @@ impl InMemoryEventBus {
         subscription_id
     }
 
-    pub async fn unsubscribe(&self, subscription_id: &str) -> Result<(), DomainError> {
+    /// Registers a handler whose work is asynchronous. Each publish spawns the
+    /// returned future on the shared runtime, so the publisher never waits on it
+    /// and several async handlers for one event run concurrently; an `Err` from
+    /// the future counts towards `events_failed`. Patterns work as in `subscribe`.
+    pub async fn subscribe_async<F>(&self, event_type: &str, handler: F) -> String
+    where
+        F: Fn(String, serde_json::Value) -> BoxFuture<'static, Result<(), DomainError>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        let subscription_id = uuid::Uuid::new_v4().to_string();
+
+        self.async_handlers
+            .write()
+            .unwrap()
+            .entry(event_type.to_string())
+            .or_default()
+            .push((subscription_id.clone(), Arc::new(handler)));
+
         let mut subscriptions = self.subscriptions.lock().await;
+        subscriptions
+            .entry(event_type.to_string())
+            .or_insert_with(Vec::new)
+            .push(Subscription {
+                id: subscription_id.clone(),
+                event_type: event_type.to_string(),
+            });
 
-        for (event_type, subs) in subscriptions.iter_mut() {
-            if let Some(pos) = subs.iter().position(|s| s.id == subscription_id) {
-                subs.remove(pos);
-                self.logger.info(&format!(
-                    "Unsubscribed handler from event: {} (id: {})",
-                    event_type, subscription_id
-                ));
-                return Ok(());
+        self.logger.info(&format!(
+            "Subscribed async handler to event: {} (id: {})",
+            event_type, subscription_id
+        ));
+
+        subscription_id
+    }
+
+    fn spawn_async_handlers(&self, event_type: &str, payload: &serde_json::Value) -> bool {
+        let async_handlers: Vec<AsyncEventHandlerFn> = {
+            let registered = self.async_handlers.read().unwrap();
+            matching_keys(&registered, event_type)
+                .iter()
+                .flat_map(|key| registered[key].iter().map(|(_, h)| h.clone()))
+                .collect()
+        };
+
+        for handler in &async_handlers {
+            let future = handler(event_type.to_string(), payload.clone());
+            let metrics = self.metrics.clone();
+            let logger = self.logger.clone();
+            let event_type = event_type.to_string();
+
+            runtime::shared().spawn(async move {
+                let result = future.await;
+                let mut metrics = metrics.lock().unwrap();
+                match result {
+                    Ok(()) => metrics.events_handled += 1,
+                    Err(e) => {
+                        metrics.events_failed += 1;
+                        logger.error(&format!(
+                            "Async handler for event {} failed: {}",
+                            event_type, e
+                        ));
+                    }
+                }
+            });
+        }
+
+        !async_handlers.is_empty()
+    }
+
+    /// Streams every event published from now on whose type matches `filter`
+    /// (see `event_type_matches`). Each item's value carries `event_type`,
+    /// `aggregate_id`, `occurred_at` and `payload`. A consumer that falls
+    /// behind skips the events it missed and keeps going.
+    pub fn event_stream(
+        &self,
+        filter: &str,
+    ) -> impl Stream<Item = (EventMetadata, serde_json::Value)> + Send + 'static {
+        let filter = filter.to_string();
+        let logger = self.logger.clone();
+        let receiver = self.stream_tx.subscribe();
+
+        futures::stream::unfold(receiver, move |mut receiver| {
+            let filter = filter.clone();
+            let logger = logger.clone();
+            async move {
+                loop {
+                    match receiver.recv().await {
+                        Ok((metadata, event)) => {
+                            let event_type = event["event_type"].as_str().unwrap_or_default();
+                            if event_type_matches(&filter, event_type) {
+                                return Some(((metadata, event), receiver));
+                            }
+                        }
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            logger.warn(&format!(
+                                "Event stream '{}' lagged; skipped {} event(s)",
+                                filter, skipped
+                            ));
+                        }
+                        Err(broadcast::error::RecvError::Closed) => return None,
+                    }
+                }
             }
+        })
+    }
+
+    pub async fn unsubscribe(&self, subscription_id: &str) -> Result<(), DomainError> {
+        let mut subscriptions = self.subscriptions.lock().await;
+
+        let Some(event_type) = subscriptions.iter_mut().find_map(|(event_type, subs)| {
+            let pos = subs.iter().position(|s| s.id == subscription_id)?;
+            subs.remove(pos);
+            Some(event_type.clone())
+        }) else {
+            return Err(DomainError::NotFound(format!(
+                "Subscription {} not found",
+                subscription_id
+            )));
+        };
+
+        if let Some(list) = self.handlers.lock().unwrap().get_mut(&event_type) {
+            list.retain(|(id, _)| id != subscription_id);
+        }
+        if let Some(list) = self.async_handlers.write().unwrap().get_mut(&event_type) {
+            list.retain(|(id, _)| id != subscription_id);
         }
 
-        Err(DomainError::NotFound(format!(
-            "Subscription {} not found",
-            subscription_id
-        )))
+        self.logger.info(&format!(
+            "Unsubscribed handler from event: {} (id: {})",
+            event_type, subscription_id
+        ));
+        Ok(())
     }
 
     pub async fn get_subscriptions(&self) -> Vec<Subscription> {
@@ -81,7 +266,7 @@ impl InMemoryEventBus {
     }
 
     pub async fn get_metrics(&self) -> EventBusMetrics {
-        let metrics = self.metrics.lock().await;
+        let metrics = self.metrics.lock().unwrap();
         EventBusMetrics {
             events_published: metrics.events_published,
             events_handled: metrics.events_handled,
@@ -90,12 +275,44 @@ impl InMemoryEventBus {
         }
     }
 
+    /// Whether the bus can take work right now: every lock it needs comes
+    /// free within `timeout` and none was poisoned by a panic. Returns the
+    /// number of subscriptions.
+    pub fn probe(&self, timeout: Duration) -> Result<usize, DomainError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let handlers = self.handlers.try_lock();
+            let async_handlers = self.async_handlers.try_read();
+            let metrics = self.metrics.try_lock();
+            let poisoned = matches!(handlers, Err(TryLockError::Poisoned(_)))
+                || matches!(async_handlers, Err(TryLockError::Poisoned(_)))
+                || matches!(metrics, Err(TryLockError::Poisoned(_)));
+            if poisoned {
+                return Err(DomainError::InvalidOperation(
+                    "a handler panicked while holding event bus state".to_string(),
+                ));
+            }
+            if let (Ok(_), Ok(_), Ok(_), Ok(subscriptions)) =
+                (handlers, async_handlers, metrics, self.subscriptions.try_lock())
+            {
+                return Ok(subscriptions.values().map(Vec::len).sum());
+            }
+            if Instant::now() >= deadline {
+                return Err(DomainError::InvalidOperation(format!(
+                    "event bus did not respond within {} ms",
+                    timeout.as_millis()
+                )));
+            }
+            std::thread::sleep(PROBE_INTERVAL);
+        }
+    }
+
     pub async fn clear(&self) {
-        let mut handlers = self.handlers.lock().await;
-        handlers.clear();
+        self.handlers.lock().unwrap().clear();
+        self.async_handlers.write().unwrap().clear();
         let mut subscriptions = self.subscriptions.lock().await;
         subscriptions.clear();
-        let mut metrics = self.metrics.lock().await;
+        let mut metrics = self.metrics.lock().unwrap();
         *metrics = EventBusMetrics::new();
         self.logger.info("Event bus cleared");
     }
@@ -105,42 +322,114 @@ impl Clone for InMemoryEventBus {
     fn clone(&self) -> Self {
         Self {
             handlers: self.handlers.clone(),
+            async_handlers: self.async_handlers.clone(),
             subscriptions: self.subscriptions.clone(),
             config: self.config.clone(),
             metrics: self.metrics.clone(),
             logger: self.logger.clone(),
+            stream_tx: self.stream_tx.clone(),
+            journal: self.journal.clone(),
         }
     }
 }
 
 impl EventBus for InMemoryEventBus {
     fn publish(&self, event: Box<dyn DomainEvent>) -> Result<(), DomainError> {
+        self.publish_with_metadata(event, EventMetadata::current("event_bus"))
+    }
+
+    fn publish_with_metadata(
+        &self,
+        event: Box<dyn DomainEvent>,
+        metadata: EventMetadata,
+    ) -> Result<(), DomainError> {
+        let _span = trace::enter("event.publish");
         let event_type = event.event_type();
         let payload = event.payload();
 
         if self.config.enable_logging {
             self.logger.info(&format!(
-                "Publishing event: {} (aggregate: {})",
+                "Publishing event: {} (aggregate: {}, id: {})",
                 event_type,
-                event.aggregate_id()
+                event.aggregate_id(),
+                metadata.event_id
             ));
         }
 
-        let handled_any = futures::executor::block_on(async {
-            let handlers = self.handlers.lock().await;
-            if let Some(handler_list) = handlers.get(event_type) {
-                for handler in handler_list {
-                    handler(event_type.to_string(), payload.clone());
+        let journal = self.journal.read().unwrap_or_else(|e| e.into_inner()).clone();
+        let journal_id = journal.as_ref().and_then(|journal| {
+            journal
+                .append(event.as_ref())
+                .map_err(|e| {
+                    self.logger
+                        .warn(&format!("Failed to journal event {}: {}", event_type, e))
+                })
+                .ok()
+        });
+
+        // Handlers run after the lock is released, so they may publish or
+        // subscribe themselves.
+        let sync_handlers: Vec<SyncHandlerFn> = {
+            let handlers = self.handlers.lock().unwrap();
+            matching_keys(&handlers, event_type)
+                .iter()
+                .flat_map(|key| handlers[key].iter().map(|(_, h)| h.clone()))
+                .collect()
+        };
+        let handled_any = !sync_handlers.is_empty();
+
+        let (mut handled, mut failed) = (0, 0);
+        for handler in &sync_handlers {
+            // Handlers can't return errors, so a panic is the failure signal.
+            let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                handler(&metadata, event_type.to_string(), payload.clone())
+            }));
+            match outcome {
+                Ok(()) => handled += 1,
+                Err(_) => {
+                    failed += 1;
+                    self.logger.error(&format!(
+                        "Event handler panicked while handling {}",
+                        event_type
+                    ));
                 }
-                true
-            } else {
-                false
             }
-        });
+        }
+
+        // An event a handler failed on stays unacknowledged, so it is kept
+        // through pruning.
+        let acknowledged = match (&journal, journal_id) {
+            (Some(journal), Some(id)) if failed == 0 => journal.acknowledge(id),
+            _ => Ok(false),
+        };
+        if let Err(e) = acknowledged {
+            self.logger
+                .warn(&format!("Failed to acknowledge event {}: {}", event_type, e));
+        }
+
+        if handled + failed > 0 {
+            let mut metrics = self.metrics.lock().unwrap();
+            metrics.events_handled += handled;
+            metrics.events_failed += failed;
+        }
 
-        if handled_any {
+        let handled_async = self.spawn_async_handlers(event_type, &payload);
+
+        if self.stream_tx.receiver_count() > 0 {
+            let _ = self.stream_tx.send((
+                metadata.clone(),
+                serde_json::json!({
+                    "event_type": event_type,
+                    "aggregate_id": event.aggregate_id(),
+                    "occurred_at": event.occurred_at().to_rfc3339(),
+                    "payload": payload.clone(),
+                }),
+            ));
+        }
+
+        if handled_any || handled_async {
             {
-                let mut metrics = futures::executor::block_on(async { self.metrics.lock().await });
+                let mut metrics = self.metrics.lock().unwrap();
                 metrics.events_published += 1;
                 metrics.last_event_type = Some(event_type.to_string());
             }
@@ -184,3 +473,221 @@ impl Clone for EventBusConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use crate::core::domain::events::{UserCreatedEvent, UserDeletedEvent};
+    use crate::core::domain::user::UserId;
+    use crate::core::logging::correlation;
+    use crate::core::ports::logger::LogLevel;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn bus() -> InMemoryEventBus {
+        InMemoryEventBus::new(
+            EventBusConfig::new().with_logging(false),
+            StructuredLogger::new(LogLevel::Error, "event_bus_test"),
+        )
+    }
+
+    #[test]
+    fn event_stream_yields_only_matching_events() {
+        let bus = bus();
+        let stream = bus.event_stream("user.created");
+
+        let created = |id: i64| {
+            Box::new(UserCreatedEvent::new(UserId(id), format!("u{}", id), format!("u{}@example.com", id)))
+        };
+        bus.publish(created(1)).unwrap();
+        bus.publish(Box::new(UserDeletedEvent::new(UserId(1)))).unwrap();
+        bus.publish(created(2)).unwrap();
+        drop(bus);
+
+        let events: Vec<_> = futures::executor::block_on(stream.collect());
+        let types: Vec<_> = events.iter().map(|(_, event)| event["event_type"].clone()).collect();
+        let ids: Vec<_> = events.iter().map(|(_, event)| event["aggregate_id"].clone()).collect();
+        assert_eq!(types, vec!["user.created", "user.created"]);
+        assert_eq!(ids, vec!["1", "2"]);
+    }
+
+    fn counter(bus: &InMemoryEventBus, event_type: &str) -> (Arc<AtomicUsize>, String) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let seen = calls.clone();
+        let id = futures::executor::block_on(bus.subscribe(event_type, move |_, _| {
+            seen.fetch_add(1, Ordering::SeqCst);
+        }));
+        (calls, id)
+    }
+
+    #[test]
+    fn probe_reports_a_stuck_or_poisoned_bus() {
+        let bus = Arc::new(bus());
+        counter(&bus, "user.deleted");
+        assert_eq!(bus.probe(Duration::from_millis(50)).unwrap(), 1);
+
+        let held = bus.handlers.lock().unwrap();
+        assert!(bus.probe(Duration::from_millis(20)).is_err());
+        drop(held);
+
+        let poisoner = bus.clone();
+        let _ = std::thread::spawn(move || {
+            let _held = poisoner.handlers.lock().unwrap();
+            panic!("handler panicked");
+        })
+        .join();
+        let error = bus.probe(Duration::from_millis(20)).unwrap_err();
+        assert!(error.to_string().contains("panicked"));
+    }
+
+    #[test]
+    fn unsubscribed_handlers_stop_running() {
+        let bus = bus();
+        let (kept, _) = counter(&bus, "user.deleted");
+        let (dropped, dropped_id) = counter(&bus, "user.deleted");
+
+        futures::executor::block_on(bus.unsubscribe(&dropped_id)).unwrap();
+        bus.publish(Box::new(UserDeletedEvent::new(UserId(1)))).unwrap();
+
+        assert_eq!(kept.load(Ordering::SeqCst), 1);
+        assert_eq!(dropped.load(Ordering::SeqCst), 0);
+    }
+
+    /// Subscribes a handler that appends `label` to `log` each time it runs.
+    fn labelled(
+        bus: &InMemoryEventBus,
+        pattern: &str,
+        label: &'static str,
+        log: &Arc<std::sync::Mutex<Vec<&'static str>>>,
+    ) -> String {
+        let log = log.clone();
+        futures::executor::block_on(bus.subscribe(pattern, move |_, _| log.lock().unwrap().push(label)))
+    }
+
+    fn created(id: i64) -> Box<UserCreatedEvent> {
+        Box::new(UserCreatedEvent::new(UserId(id), format!("u{}", id), format!("u{}@example.com", id)))
+    }
+
+    #[test]
+    fn prefix_and_catch_all_patterns_receive_matching_events() {
+        let bus = bus();
+        let (user_events, _) = counter(&bus, "user.*");
+        let (every_event, _) = counter(&bus, "*");
+        let (other_prefix, _) = counter(&bus, "order.*");
+
+        bus.publish(created(1)).unwrap();
+        bus.publish(Box::new(UserDeletedEvent::new(UserId(1)))).unwrap();
+
+        assert_eq!(user_events.load(Ordering::SeqCst), 2);
+        assert_eq!(every_event.load(Ordering::SeqCst), 2);
+        assert_eq!(other_prefix.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn exact_subscribers_run_before_wildcard_ones() {
+        let bus = bus();
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+        labelled(&bus, "user.*", "prefix", &log);
+        labelled(&bus, "*", "all", &log);
+        labelled(&bus, "user.created", "exact", &log);
+
+        bus.publish(created(1)).unwrap();
+
+        assert_eq!(*log.lock().unwrap(), ["exact", "all", "prefix"]);
+    }
+
+    #[test]
+    fn unsubscribed_wildcard_handlers_stop_running() {
+        let bus = bus();
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let prefix_id = labelled(&bus, "user.*", "prefix", &log);
+        labelled(&bus, "user.created", "exact", &log);
+
+        futures::executor::block_on(bus.unsubscribe(&prefix_id)).unwrap();
+        bus.publish(created(1)).unwrap();
+
+        assert_eq!(*log.lock().unwrap(), ["exact"]);
+        assert!(futures::executor::block_on(bus.unsubscribe(&prefix_id)).is_err());
+    }
+
+    #[test]
+    fn sync_handlers_may_publish() {
+        let bus = Arc::new(bus());
+        let (nested, _) = counter(&bus, "user.created");
+        let inner = bus.clone();
+        futures::executor::block_on(bus.subscribe("user.deleted", move |_, _| {
+            let event = UserCreatedEvent::new(UserId(2), "b".to_string(), "b@example.com".to_string());
+            inner.publish(Box::new(event)).unwrap();
+        }));
+
+        bus.publish(Box::new(UserDeletedEvent::new(UserId(1)))).unwrap();
+
+        assert_eq!(nested.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn async_handlers_run_concurrently_without_blocking_the_publisher() {
+        let bus = bus();
+        // Both handlers and the test must reach the barrier, so it only opens
+        // if the handlers are waiting at the same time and publish returned.
+        let barrier = Arc::new(tokio::sync::Barrier::new(3));
+        for _ in 0..2 {
+            let barrier = barrier.clone();
+            futures::executor::block_on(bus.subscribe_async("user.deleted", move |_, _| {
+                let barrier = barrier.clone();
+                Box::pin(async move {
+                    barrier.wait().await;
+                    Ok(())
+                })
+            }));
+        }
+
+        futures::executor::block_on(bus.subscribe_async("user.deleted", |_, _| {
+            Box::pin(async { Err(DomainError::InvalidOperation("downstream unavailable".to_string())) })
+        }));
+
+        bus.publish(Box::new(UserDeletedEvent::new(UserId(1)))).unwrap();
+
+        let opened = runtime::shared().block_on(async {
+            tokio::time::timeout(std::time::Duration::from_secs(5), barrier.wait()).await
+        });
+        assert!(opened.is_ok(), "async handlers did not run concurrently");
+
+        // Metrics are recorded once each spawned future has finished.
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let metrics = loop {
+            let metrics = futures::executor::block_on(bus.get_metrics());
+            if metrics.events_handled + metrics.events_failed >= 3 || Instant::now() >= deadline {
+                break metrics;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        };
+        assert_eq!(metrics.events_handled, 2);
+        assert_eq!(metrics.events_failed, 1);
+        assert_eq!(metrics.events_published, 1);
+    }
+
+    #[test]
+    fn metadata_handlers_receive_the_published_metadata() {
+        let bus = bus();
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = seen.clone();
+        futures::executor::block_on(bus.subscribe_with_metadata("user.deleted", move |metadata, _, _| {
+            recorded.lock().unwrap().push(metadata.clone());
+        }));
+
+        let metadata = EventMetadata::new("test").with_correlation("req-1".to_string());
+        bus.publish_with_metadata(Box::new(UserDeletedEvent::new(UserId(1))), metadata.clone())
+            .unwrap();
+        correlation::scope(Some("req-2".to_string()), || {
+            bus.publish(Box::new(UserDeletedEvent::new(UserId(2)))).unwrap();
+        });
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen[0].event_id, metadata.event_id);
+        assert_eq!(seen[0].source, "test");
+        assert_eq!(seen[0].correlation_id.as_deref(), Some("req-1"));
+        assert_eq!(seen[1].correlation_id.as_deref(), Some("req-2"));
+        assert_ne!(seen[1].event_id, metadata.event_id);
+    }
+}
@@ -1,4 +1,5 @@
 // infrastructure/event_bus/mod.rs
+use crate::core::domain::errors::DomainError;
 use crate::core::domain::events::{DomainEvent, Envelope, EventMetadata};
 use crate::core::ports::event_bus::{EventBus, EventHandler};
 use crate::core::ports::logger::Logger;
@@ -11,12 +12,33 @@ use std::sync::Mutex;
 
 pub mod in_memory;
 pub mod async_event_bus;
+pub mod webhook;
 
 pub use in_memory::InMemoryEventBus;
 pub use async_event_bus::AsyncEventBus;
+pub use webhook::WebhookSubscriber;
 
 pub type EventHandlerFn<E> = Box<dyn Fn(E) + Send + Sync>;
 
+pub type AsyncEventHandlerFn = Arc<
+    dyn Fn(String, serde_json::Value) -> futures::future::BoxFuture<'static, Result<(), DomainError>>
+        + Send
+        + Sync,
+>;
+
+/// Matches an event type against a subscription pattern: `*` matches
+/// everything, `user.*` matches any type starting with `user.`, anything
+/// else must match exactly.
+pub fn event_type_matches(pattern: &str, event_type: &str) -> bool {
+    if pattern == "*" {
+        return true;
+    }
+    match pattern.strip_suffix('*') {
+        Some(prefix) => event_type.starts_with(prefix),
+        None => pattern == event_type,
+    }
+}
+
 #[derive(Clone)]
 pub struct Subscription {
     pub id: String,
@@ -0,0 +1,345 @@
+// infrastructure/event_bus/webhook.rs
+use crate::core::config::{WebhookEndpoint, WebhookSettings};
+use crate::core::domain::events::EventMetadata;
+use crate::core::event_bus::InMemoryEventBus;
+use crate::core::runtime;
+use crate::core::shutdown::Shutdown;
+use hmac::{Hmac, Mac};
+use log::{info, warn};
+use sha2::Sha256;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+
+/// The first retry waits this long; each later one doubles it.
+const BASE_BACKOFF: Duration = Duration::from_millis(200);
+/// Retries never wait longer than this, however many `max_retries` allows.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+struct WebhookDelivery {
+    url: String,
+    event_type: String,
+    body: String,
+}
+
+/// Forwards selected bus events to HTTP endpoints.
+///
+/// Bus handlers only enqueue; a single background worker performs the POSTs
+/// with exponential backoff capped at a minute, so a slow endpoint never
+/// blocks `publish`. When the queue is full the delivery is dropped and
+/// counted. The worker stops once `shutdown` is triggered.
+pub struct WebhookSubscriber {
+    subscription_ids: Vec<String>,
+    dropped: Arc<AtomicUsize>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl WebhookSubscriber {
+    pub async fn attach(
+        bus: &InMemoryEventBus,
+        settings: &WebhookSettings,
+        shutdown: Shutdown,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel(settings.queue_size.max(1));
+        let dropped = Arc::new(AtomicUsize::new(0));
+
+        let worker = runtime::shared().spawn(Self::run_worker(
+            receiver,
+            settings.secret.clone(),
+            settings.max_retries,
+            shutdown,
+        ));
+
+        let mut subscription_ids = Vec::new();
+        for endpoint in &settings.endpoints {
+            for event_type in &endpoint.events {
+                let id = bus
+                    .subscribe_with_metadata(
+                        event_type,
+                        Self::enqueue_handler(endpoint, sender.clone(), dropped.clone()),
+                    )
+                    .await;
+                subscription_ids.push(id);
+            }
+        }
+
+        info!(
+            "Webhook subscriber attached: {} endpoint(s), {} subscription(s)",
+            settings.endpoints.len(),
+            subscription_ids.len()
+        );
+
+        Self {
+            subscription_ids,
+            dropped,
+            worker: Some(worker),
+        }
+    }
+
+    /// The delivery worker's handle, for the shutdown coordinator to track.
+    pub fn take_worker(&mut self) -> Option<JoinHandle<()>> {
+        self.worker.take()
+    }
+
+    pub fn subscription_ids(&self) -> &[String] {
+        &self.subscription_ids
+    }
+
+    pub fn dropped_deliveries(&self) -> usize {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    fn enqueue_handler(
+        endpoint: &WebhookEndpoint,
+        sender: mpsc::Sender<WebhookDelivery>,
+        dropped: Arc<AtomicUsize>,
+    ) -> impl Fn(&EventMetadata, String, serde_json::Value) + Send + Sync + 'static {
+        let url = endpoint.url.clone();
+        move |metadata, event_type, payload| {
+            // The published envelope's metadata, so receivers see the same
+            // event id and correlation as every other consumer.
+            let body = serde_json::json!({
+                "event_type": event_type,
+                "metadata": metadata,
+                "payload": payload,
+            })
+            .to_string();
+
+            let delivery = WebhookDelivery {
+                url: url.clone(),
+                event_type,
+                body,
+            };
+
+            if let Err(e) = sender.try_send(delivery) {
+                let total = dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                warn!(
+                    "Webhook queue full, dropping delivery to {} ({} dropped so far): {}",
+                    url, total, e
+                );
+            }
+        }
+    }
+
+    async fn run_worker(
+        mut receiver: mpsc::Receiver<WebhookDelivery>,
+        secret: Option<String>,
+        max_retries: u32,
+        shutdown: Shutdown,
+    ) {
+        let client = reqwest::Client::new();
+
+        loop {
+            let delivery = tokio::select! {
+                delivery = receiver.recv() => delivery,
+                _ = shutdown.cancelled() => None,
+            };
+            let Some(delivery) = delivery else {
+                break;
+            };
+            Self::deliver(&client, &delivery, secret.as_deref(), max_retries).await;
+        }
+    }
+
+    async fn deliver(
+        client: &reqwest::Client,
+        delivery: &WebhookDelivery,
+        secret: Option<&str>,
+        max_retries: u32,
+    ) {
+        let mut request = client
+            .post(&delivery.url)
+            .header("Content-Type", "application/json")
+            .body(delivery.body.clone());
+        if let Some(secret) = secret {
+            request = request.header(SIGNATURE_HEADER, sign(secret, &delivery.body));
+        }
+
+        let mut attempt = 0;
+        loop {
+            let outcome = match request.try_clone() {
+                Some(req) => req.send().await.and_then(|r| r.error_for_status()),
+                None => break,
+            };
+
+            match outcome {
+                Ok(_) => break,
+                Err(e) if attempt < max_retries => {
+                    let backoff = backoff_for(attempt);
+                    warn!(
+                        "Webhook delivery of {} to {} failed (attempt {}), retrying in {:?}: {}",
+                        delivery.event_type,
+                        delivery.url,
+                        attempt + 1,
+                        backoff,
+                        e
+                    );
+                    attempt += 1;
+                    tokio::time::sleep(backoff).await;
+                }
+                Err(e) => {
+                    warn!(
+                        "Webhook delivery of {} to {} gave up after {} attempts: {}",
+                        delivery.event_type,
+                        delivery.url,
+                        attempt + 1,
+                        e
+                    );
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// How long to wait before retrying after the given failed attempt.
+fn backoff_for(attempt: u32) -> Duration {
+    let millis = 2u64
+        .saturating_pow(attempt)
+        .saturating_mul(BASE_BACKOFF.as_millis() as u64);
+    Duration::from_millis(millis).min(MAX_BACKOFF)
+}
+
+/// Hex-encoded HMAC-SHA256 of the body, sent as `sha256=<hex>`.
+pub fn sign(secret: &str, body: &str) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::domain::events::UserCreatedEvent;
+    use crate::core::domain::user::UserId;
+    use crate::core::ports::event_bus::EventBus;
+    use crate::core::ports::logger::LogLevel;
+    use crate::core::event_bus::EventBusConfig;
+    use crate::core::logging::StructuredLogger;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc as std_mpsc;
+
+    struct Received {
+        signature: Option<String>,
+        body: String,
+    }
+
+    /// Answers each POST with the next status in `statuses`, then 200, and
+    /// reports what it received.
+    fn mock_server(statuses: Vec<u16>) -> (String, std_mpsc::Receiver<Received>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let (tx, rx) = std_mpsc::channel();
+
+        std::thread::spawn(move || {
+            let mut statuses = statuses.into_iter();
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { break };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let (mut length, mut signature) = (0, None);
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line = line.trim_end();
+                    if line.is_empty() {
+                        break;
+                    }
+                    let (name, value) = line.split_once(':').unwrap_or((line, ""));
+                    if name.eq_ignore_ascii_case("content-length") {
+                        length = value.trim().parse().unwrap();
+                    } else if name.eq_ignore_ascii_case(SIGNATURE_HEADER) {
+                        signature = Some(value.trim().to_string());
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+
+                let status = statuses.next().unwrap_or(200);
+                write!(stream, "HTTP/1.1 {} X\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status)
+                    .unwrap();
+                let body = String::from_utf8(body).unwrap();
+                if tx.send(Received { signature, body }).is_err() {
+                    break;
+                }
+            }
+        });
+
+        (url, rx)
+    }
+
+    #[test]
+    fn publish_results_in_a_signed_post_retried_after_a_failure() {
+        let (url, received) = mock_server(vec![500]);
+        let settings = WebhookSettings {
+            secret: Some("s3cret".to_string()),
+            queue_size: 8,
+            max_retries: 2,
+            endpoints: vec![WebhookEndpoint { url, events: vec!["user.created".to_string()] }],
+        };
+        let bus = InMemoryEventBus::new(
+            EventBusConfig::new().with_logging(false),
+            StructuredLogger::new(LogLevel::Error, "webhook_test"),
+        );
+        futures::executor::block_on(WebhookSubscriber::attach(&bus, &settings, Shutdown::new()));
+
+        let metadata = EventMetadata::new("user_service").with_correlation("corr-1".to_string());
+        let event_id = metadata.event_id.clone();
+        bus.publish_with_metadata(
+            Box::new(UserCreatedEvent::new(UserId(7), "Ada".into(), "ada@example.com".into())),
+            metadata,
+        )
+        .unwrap();
+
+        let wait = Duration::from_secs(10);
+        let failed = received.recv_timeout(wait).expect("first delivery");
+        let retried = received.recv_timeout(wait).expect("retried delivery");
+        assert_eq!(failed.body, retried.body);
+        assert_eq!(retried.signature.as_deref(), Some(sign("s3cret", &retried.body).as_str()));
+
+        let body: serde_json::Value = serde_json::from_str(&retried.body).unwrap();
+        assert_eq!(body["event_type"], "user.created");
+        assert_eq!(body["metadata"]["event_id"], event_id.as_str());
+        assert_eq!(body["metadata"]["source"], "user_service");
+        assert_eq!(body["metadata"]["correlation_id"], "corr-1");
+        assert_eq!(body["payload"]["user_id"], 7);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap_without_overflowing() {
+        assert_eq!(backoff_for(0), Duration::from_millis(200));
+        assert_eq!(backoff_for(3), Duration::from_millis(1600));
+        for attempt in [9, 57, 64, u32::MAX] {
+            assert_eq!(backoff_for(attempt), MAX_BACKOFF);
+        }
+    }
+
+    #[test]
+    fn worker_stops_once_shutdown_is_triggered() {
+        let bus = InMemoryEventBus::new(
+            EventBusConfig::new().with_logging(false),
+            StructuredLogger::new(LogLevel::Error, "webhook_test"),
+        );
+        let shutdown = Shutdown::new();
+        let mut subscriber = futures::executor::block_on(WebhookSubscriber::attach(
+            &bus,
+            &WebhookSettings::default(),
+            shutdown.clone(),
+        ));
+        let worker = subscriber.take_worker().expect("worker handle");
+        assert!(subscriber.take_worker().is_none());
+
+        shutdown.trigger("test");
+        runtime::shared()
+            .block_on(async { tokio::time::timeout(Duration::from_secs(5), worker).await })
+            .expect("worker stopped")
+            .unwrap();
+    }
+}
@@ -0,0 +1,243 @@
+// src/core/health/mod.rs
+// Liveness and readiness probes
+
+use serde::Serialize;
+use std::time::Duration;
+
+use crate::core::domain::errors::DomainError;
+use crate::core::event_bus::InMemoryEventBus;
+use crate::core::ports::repository::UserRepository;
+use crate::model::repositories::sqlite::connection::SqliteDatabase;
+use crate::model::repositories::sqlite::migrations::pending_migrations;
+use crate::plugins::{PluginHealth, PluginRegistry};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProbeCheck {
+    pub name: String,
+    pub ok: bool,
+    pub detail: Option<String>,
+}
+
+impl ProbeCheck {
+    fn pass(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ok: true,
+            detail: None,
+        }
+    }
+
+    fn fail(name: &str, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            ok: false,
+            detail: Some(detail.into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProbeReport {
+    pub ok: bool,
+    pub checks: Vec<ProbeCheck>,
+}
+
+impl ProbeReport {
+    fn from_checks(checks: Vec<ProbeCheck>) -> Self {
+        Self {
+            ok: checks.iter().all(|c| c.ok),
+            checks,
+        }
+    }
+}
+
+/// The process is up and able to answer. Deliberately does no I/O.
+pub fn liveness() -> ProbeReport {
+    ProbeReport::from_checks(vec![ProbeCheck::pass("process")])
+}
+
+/// The application can serve traffic: every built-in migration applied,
+/// database answering, and plugin startup finished. `database` is the error
+/// when it could not be opened.
+pub fn readiness(
+    database: Result<&SqliteDatabase, &DomainError>,
+    plugins: &PluginRegistry,
+) -> ProbeReport {
+    let mut checks = Vec::new();
+
+    match database {
+        Ok(database) => {
+            checks.push(match pending_migrations(&database.reader()) {
+                Ok(pending) if pending.is_empty() => ProbeCheck::pass("migrations"),
+                Ok(pending) => {
+                    ProbeCheck::fail("migrations", format!("pending: {}", pending.join(", ")))
+                }
+                Err(e) => ProbeCheck::fail("migrations", e.to_string()),
+            });
+
+            checks.push(match database.ping() {
+                Ok(()) => ProbeCheck::pass("database"),
+                Err(e) => ProbeCheck::fail("database", e.to_string()),
+            });
+        }
+        Err(e) => {
+            checks.push(ProbeCheck::fail("migrations", e.to_string()));
+            checks.push(ProbeCheck::fail("database", e.to_string()));
+        }
+    }
+
+    checks.push(if plugins.is_initialized() {
+        ProbeCheck::pass("plugins")
+    } else {
+        ProbeCheck::fail("plugins", "plugin startup not finished")
+    });
+
+    ProbeReport::from_checks(checks)
+}
+
+/// How long `system_health` waits for a busy event bus before reporting it
+/// as not running.
+const EVENT_BUS_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Overall application health for the UI and monitoring wrappers. A failing
+/// check marks the report `"degraded"` instead of failing the whole report:
+///
+/// `{ "status": "ok" | "degraded", "checks": { "database", "event_bus", "plugins" } }`
+pub fn system_health(
+    users: Result<&dyn UserRepository, &DomainError>,
+    event_bus: &InMemoryEventBus,
+    plugins: &PluginRegistry,
+) -> serde_json::Value {
+    let count = match users {
+        Ok(users) => futures::executor::block_on(users.count()),
+        Err(e) => Err(e.clone()),
+    };
+    let database = match count {
+        Ok(count) => serde_json::json!({ "ok": true, "users": count }),
+        Err(e) => serde_json::json!({ "ok": false, "error": e.to_string() }),
+    };
+
+    let event_bus = match event_bus.probe(EVENT_BUS_TIMEOUT) {
+        Ok(subscriptions) => {
+            serde_json::json!({ "ok": true, "running": true, "subscriptions": subscriptions })
+        }
+        Err(e) => serde_json::json!({ "ok": false, "running": false, "error": e.to_string() }),
+    };
+
+    let unhealthy: Vec<_> = plugins
+        .health_report()
+        .into_iter()
+        .filter(|(_, health)| *health != PluginHealth::Ok)
+        .map(|(name, health)| serde_json::json!({ "name": name, "health": health }))
+        .collect();
+    let plugins = serde_json::json!({
+        "ok": unhealthy.is_empty(),
+        "count": plugins.len(),
+        "unhealthy": unhealthy,
+    });
+
+    let healthy = [&database, &event_bus, &plugins]
+        .iter()
+        .all(|check| check["ok"].as_bool().unwrap_or(false));
+
+    serde_json::json!({
+        "status": if healthy { "ok" } else { "degraded" },
+        "checks": {
+            "database": database,
+            "event_bus": event_bus,
+            "plugins": plugins,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ports::logger::LogLevel;
+    use crate::core::event_bus::EventBusConfig;
+    use crate::core::logging::StructuredLogger;
+    use crate::model::repositories::sqlite::migrations::MIGRATIONS;
+    use crate::model::repositories::sqlite::user_repository::SqliteUserRepository;
+    use std::sync::Arc;
+
+    fn bus() -> InMemoryEventBus {
+        InMemoryEventBus::new(
+            EventBusConfig::new().with_logging(false),
+            StructuredLogger::new(LogLevel::Error, "health_test"),
+        )
+    }
+
+    fn users() -> SqliteUserRepository {
+        let db = Arc::new(SqliteDatabase::open(":memory:", 0).unwrap());
+        let users = SqliteUserRepository::with_database(db);
+        users.init_schema().unwrap();
+        users
+    }
+
+    #[test]
+    fn liveness_is_always_ok() {
+        assert!(liveness().ok);
+    }
+
+    #[test]
+    fn readiness_waits_for_migrations_and_plugins() {
+        let db = Arc::new(SqliteDatabase::open(":memory:", 0).unwrap());
+        let plugins = PluginRegistry::new();
+        plugins.mark_initialized();
+
+        let before = readiness(Ok(&db), &plugins);
+        assert!(!before.ok);
+        assert!(before.checks.iter().any(|c| c.name == "migrations" && !c.ok));
+
+        SqliteUserRepository::with_database(db.clone()).init_schema().unwrap();
+        assert!(readiness(Ok(&db), &plugins).ok);
+        assert!(!readiness(Ok(&db), &PluginRegistry::new()).ok);
+    }
+
+    #[test]
+    fn readiness_fails_while_a_migration_is_unrecorded() {
+        let db = Arc::new(SqliteDatabase::open(":memory:", 0).unwrap());
+        SqliteUserRepository::with_database(db.clone()).init_schema().unwrap();
+        let plugins = PluginRegistry::new();
+        plugins.mark_initialized();
+
+        let (latest, _) = MIGRATIONS.last().unwrap();
+        db.writer()
+            .execute("DELETE FROM schema_migrations WHERE version = ?1", [latest])
+            .unwrap();
+
+        let report = readiness(Ok(&db), &plugins);
+        assert!(!report.ok);
+        let migrations = report.checks.iter().find(|c| c.name == "migrations").unwrap();
+        assert_eq!(migrations.detail.as_deref(), Some(format!("pending: {}", latest).as_str()));
+    }
+
+    #[test]
+    fn readiness_fails_when_the_database_cannot_open() {
+        let error = DomainError::InvalidOperation("unable to open database file".to_string());
+        let report = readiness(Err(&error), &PluginRegistry::new());
+        assert!(!report.ok);
+        assert!(report.checks.iter().filter(|c| c.name != "plugins").all(|c| !c.ok));
+    }
+
+    #[test]
+    fn healthy_checks_report_ok() {
+        let users = users();
+        let report = system_health(Ok(&users), &bus(), &PluginRegistry::new());
+
+        assert_eq!(report["status"], "ok");
+        assert_eq!(report["checks"]["database"]["users"], 0);
+        assert_eq!(report["checks"]["event_bus"]["running"], true);
+    }
+
+    #[test]
+    fn database_failure_degrades_the_report() {
+        let error = DomainError::InvalidOperation("database is locked".to_string());
+        let report = system_health(Err(&error), &bus(), &PluginRegistry::new());
+
+        assert_eq!(report["status"], "degraded");
+        assert_eq!(report["checks"]["database"]["ok"], false);
+        assert_eq!(report["checks"]["event_bus"]["ok"], true);
+        assert_eq!(report["checks"]["plugins"]["ok"], true);
+    }
+}
@@ -0,0 +1,48 @@
+// src/core/logging/correlation.rs
+// Correlation id of the frontend request being handled on this thread
+//
+// Bindings run synchronously on WebUI threads, so like `core::trace` the id
+// lives in a thread-local for the duration of `scope`. Log lines and
+// response envelopes emitted inside the scope carry it.
+
+use std::cell::RefCell;
+
+thread_local! {
+    static CURRENT: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Restores the enclosing id when the scope ends, even on panic.
+struct ScopeGuard(Option<String>);
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        CURRENT.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+pub fn current() -> Option<String> {
+    CURRENT.with(|current| current.borrow().clone())
+}
+
+/// Runs `work` with `id` as the current correlation id. `None` leaves the
+/// enclosing id in place.
+pub fn scope<T>(id: Option<String>, work: impl FnOnce() -> T) -> T {
+    let Some(id) = id else {
+        return work();
+    };
+    let previous = CURRENT.with(|current| current.borrow_mut().replace(id));
+    let _guard = ScopeGuard(previous);
+    work()
+}
+
+/// The `correlation_id` string of a JSON object payload, if any.
+pub fn from_payload(raw: &str) -> Option<String> {
+    let payload: serde_json::Value = serde_json::from_str(raw).ok()?;
+    payload
+        .get("correlation_id")?
+        .as_str()
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+}
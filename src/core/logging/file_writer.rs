@@ -0,0 +1,185 @@
+// Core logging - background log file writer with size-based rotation
+
+use log::warn;
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+
+pub const DEFAULT_QUEUE_CAPACITY: usize = 8192;
+
+#[derive(Debug, Clone, Copy)]
+pub struct RotationPolicy {
+    pub max_file_size: u64,
+    pub max_files: usize,
+}
+
+#[derive(Default)]
+struct Queue {
+    lines: VecDeque<String>,
+    enqueued: u64,
+    written: u64,
+    dropped: u64,
+    closed: bool,
+}
+
+struct Shared {
+    queue: Mutex<Queue>,
+    changed: Condvar,
+    capacity: usize,
+}
+
+/// Appends lines from a dedicated thread so logging callers never wait on
+/// disk. When the queue is full the oldest line is dropped and counted.
+/// Dropping the writer drains everything still queued.
+pub struct FileWriter {
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl FileWriter {
+    pub fn spawn(path: PathBuf, rotation: RotationPolicy, capacity: usize) -> std::io::Result<Self> {
+        let shared = Arc::new(Shared {
+            queue: Mutex::new(Queue::default()),
+            changed: Condvar::new(),
+            capacity: capacity.max(1),
+        });
+        let thread = {
+            let shared = shared.clone();
+            std::thread::Builder::new()
+                .name("log-writer".to_string())
+                .spawn(move || run(&shared, &path, rotation))?
+        };
+        Ok(Self {
+            shared,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn enqueue(&self, line: String) {
+        let mut queue = self.shared.queue.lock().unwrap();
+        if queue.lines.len() >= self.shared.capacity {
+            queue.lines.pop_front();
+            queue.dropped += 1;
+        }
+        queue.lines.push_back(line);
+        queue.enqueued += 1;
+        self.shared.changed.notify_all();
+    }
+
+    /// Blocks until every line enqueued so far is written or was dropped.
+    pub fn flush(&self) {
+        let queue = self.shared.queue.lock().unwrap();
+        let target = queue.enqueued;
+        let _queue = self
+            .shared
+            .changed
+            .wait_while(queue, |q| q.written + q.dropped < target && !q.closed)
+            .unwrap();
+    }
+
+    pub fn dropped(&self) -> u64 {
+        self.shared.queue.lock().unwrap().dropped
+    }
+}
+
+impl Drop for FileWriter {
+    fn drop(&mut self) {
+        self.shared.queue.lock().unwrap().closed = true;
+        self.shared.changed.notify_all();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn run(shared: &Shared, path: &Path, rotation: RotationPolicy) {
+    let mut reported_dropped = 0;
+    loop {
+        let (batch, dropped, closed) = {
+            let queue = shared.queue.lock().unwrap();
+            let mut queue = shared
+                .changed
+                .wait_while(queue, |q| q.lines.is_empty() && !q.closed)
+                .unwrap();
+            let batch: Vec<String> = queue.lines.drain(..).collect();
+            (batch, queue.dropped, queue.closed)
+        };
+
+        if dropped > reported_dropped {
+            warn!(
+                "Log queue full; dropped {} line(s) from {}",
+                dropped - reported_dropped,
+                path.display()
+            );
+            reported_dropped = dropped;
+        }
+
+        let count = batch.len() as u64;
+        if let Err(e) = write_batch(path, rotation, &batch) {
+            eprintln!("Failed to write log file {}: {}", path.display(), e);
+        }
+
+        let mut queue = shared.queue.lock().unwrap();
+        queue.written += count;
+        shared.changed.notify_all();
+        if closed && queue.lines.is_empty() {
+            return;
+        }
+    }
+}
+
+fn open_append(path: &Path) -> std::io::Result<(File, u64)> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let size = file.metadata()?.len();
+    Ok((file, size))
+}
+
+fn write_batch(path: &Path, rotation: RotationPolicy, lines: &[String]) -> std::io::Result<()> {
+    if lines.is_empty() {
+        return Ok(());
+    }
+
+    let (mut file, mut size) = open_append(path)?;
+    for line in lines {
+        if size > 0 && size + line.len() as u64 > rotation.max_file_size {
+            drop(file);
+            if let Err(e) = rotate(path, rotation.max_files) {
+                // Keep appending to the oversized file rather than lose the line.
+                eprintln!("Log rotation failed for {}: {}", path.display(), e);
+            }
+            (file, size) = open_append(path)?;
+        }
+        file.write_all(line.as_bytes())?;
+        size += line.len() as u64;
+    }
+    file.flush()
+}
+
+pub fn rotated_path(file: &Path, index: usize) -> PathBuf {
+    let stem = file.file_stem().and_then(|s| s.to_str()).unwrap_or("app");
+    file.with_file_name(format!("{}.{}.log", stem, index))
+}
+
+/// Shifts `{service}.N.log` up by one, dropping the oldest, then moves the
+/// live file to `.1`. Each step is a single rename, so a restart midway
+/// leaves at most a gap in the numbering, which is skipped.
+fn rotate(file: &Path, max_files: usize) -> std::io::Result<()> {
+    if max_files == 0 {
+        return std::fs::remove_file(file);
+    }
+
+    let oldest = rotated_path(file, max_files);
+    if oldest.exists() {
+        std::fs::remove_file(&oldest)?;
+    }
+    for index in (1..max_files).rev() {
+        let from = rotated_path(file, index);
+        if from.exists() {
+            std::fs::rename(&from, rotated_path(file, index + 1))?;
+        }
+    }
+    std::fs::rename(file, rotated_path(file, 1))
+}
@@ -1,18 +1,131 @@
 // Core logging - logging utilities (with re-exports for compatibility)
 pub use crate::model::ports::logger;
 
+pub mod correlation;
+pub mod file_writer;
+
+use file_writer::{FileWriter, RotationPolicy, DEFAULT_QUEUE_CAPACITY};
+
 use crate::core::ports::logger::{ContextualLogger, LogLevel, Logger};
-use chrono::Local;
+use chrono::{Local, Utc};
+use crate::core::timezone::format_log_timestamp;
 use log::{debug, error, info, warn, Level, LevelFilter};
 use std::fs::OpenOptions;
 use std::io::Write;
+use std::collections::HashMap;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// Process-wide `log` backend whose inner env_logger can be swapped, so a
+/// second `StructuredLogger::init` reconfigures logging instead of being ignored.
+struct ReloadableLogger {
+    inner: RwLock<Option<env_logger::Logger>>,
+}
+
+impl log::Log for ReloadableLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        match self.inner.read().unwrap().as_ref() {
+            Some(logger) => logger.enabled(metadata),
+            None => false,
+        }
+    }
+
+    fn log(&self, record: &log::Record) {
+        if let Some(logger) = self.inner.read().unwrap().as_ref() {
+            logger.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(logger) = self.inner.read().unwrap().as_ref() {
+            logger.flush();
+        }
+    }
+}
+
+static RELOADABLE_LOGGER: ReloadableLogger = ReloadableLogger {
+    inner: RwLock::new(None),
+};
+static LOGGER_INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// Installs `logger` as the process logger, or swaps it in if ours is
+/// already installed. Returns false if some other logger owns the `log` facade.
+fn install_or_reconfigure(logger: env_logger::Logger, level: &LogLevel) -> bool {
+    let filter = logger.filter();
+
+    if LOGGER_INSTALLED.load(Ordering::SeqCst) {
+        warn!("Logger already initialized; reconfiguring to level={:?}", level);
+        *RELOADABLE_LOGGER.inner.write().unwrap() = Some(logger);
+        log::set_max_level(filter);
+        return true;
+    }
+
+    *RELOADABLE_LOGGER.inner.write().unwrap() = Some(logger);
+    match log::set_logger(&RELOADABLE_LOGGER) {
+        Ok(()) => {
+            LOGGER_INSTALLED.store(true, Ordering::SeqCst);
+            log::set_max_level(filter);
+            true
+        }
+        Err(_) => {
+            warn!("A different logger is already installed; level={:?} not applied", level);
+            false
+        }
+    }
+}
+
+/// Output format for console and file logs. `Json` writes one object per line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// Top-level modules of this crate, accepted as per-module level keys
+/// with or without the crate name in front.
+const CRATE_MODULES: &[&str] = &[
+    "core", "di", "features", "infrastructure", "model", "mvvm", "platform", "plugins", "view",
+    "viewmodel",
+];
+
+/// Resolves a `logging.modules` key to a full log target prefix, or `None`
+/// if it names nothing in this crate.
+fn resolve_module_target(module: &str) -> Option<String> {
+    let crate_name = env!("CARGO_CRATE_NAME");
+    let relative = match module.strip_prefix(crate_name) {
+        Some("") => return Some(crate_name.to_string()),
+        Some(rest) => rest.strip_prefix("::")?,
+        None => module,
+    };
+    let root = relative.split("::").next().unwrap_or_default();
+    CRATE_MODULES
+        .contains(&root)
+        .then(|| format!("{}::{}", crate_name, relative))
+}
+
+/// ` [cid=<id>]` inside a correlation scope, otherwise empty.
+fn correlation_tag() -> String {
+    correlation::current()
+        .map(|id| format!(" [cid={}]", id))
+        .unwrap_or_default()
+}
+
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+pub const DEFAULT_MAX_FILES: usize = 5;
 
 pub struct StructuredLogger {
     level: LogLevel,
     service_name: String,
     log_file: Option<PathBuf>,
+    format: LogFormat,
+    module_levels: Vec<(String, String)>,
+    max_file_size: u64,
+    max_files: usize,
+    // Shared by clones; the last one dropped drains the queue.
+    writer: Option<Arc<FileWriter>>,
 }
 
 pub struct StructuredContextualLogger {
@@ -26,14 +139,74 @@ impl StructuredLogger {
             level,
             service_name: service_name.to_string(),
             log_file: None,
+            format: LogFormat::Text,
+            module_levels: Vec::new(),
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            max_files: DEFAULT_MAX_FILES,
+            writer: None,
         }
     }
 
+    pub fn with_format(mut self, format: LogFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Module prefix -> level overrides; `level` stays the fallback. Entries
+    /// naming an unknown module or level are skipped with a warning at `init`.
+    pub fn with_module_levels(mut self, modules: &HashMap<String, String>) -> Self {
+        let mut levels: Vec<_> = modules.iter().map(|(m, l)| (m.clone(), l.clone())).collect();
+        levels.sort();
+        self.module_levels = levels;
+        self
+    }
+
+    /// Rotate once the log file would exceed `max_file_size` bytes, keeping
+    /// `max_files` rotated files (`{service}.1.log` is the newest).
+    pub fn with_rotation(mut self, max_file_size: u64, max_files: usize) -> Self {
+        self.max_file_size = max_file_size.max(1);
+        self.max_files = max_files;
+        self
+    }
+
     pub fn with_default_config(service_name: &str) -> Self {
         Self::new(LogLevel::Info, service_name)
     }
 
     pub fn init(&mut self, log_dir: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+        let (env_logger, problems) = self.build_env_logger();
+        install_or_reconfigure(env_logger, &self.level);
+        for problem in problems {
+            warn!("Ignoring logging.modules entry: {}", problem);
+        }
+
+        if let Some(ref dir) = log_dir {
+            let logs_dir = dir.join("logs");
+            std::fs::create_dir_all(&logs_dir)?;
+            let log_file = logs_dir.join(format!("{}.log", self.service_name));
+            let rotation = RotationPolicy {
+                max_file_size: self.max_file_size,
+                max_files: self.max_files,
+            };
+            self.writer = Some(Arc::new(FileWriter::spawn(
+                log_file.clone(),
+                rotation,
+                DEFAULT_QUEUE_CAPACITY,
+            )?));
+            self.log_file = Some(log_file);
+
+            info!(
+                "Logging initialized: level={:?}, log_dir={}",
+                self.level,
+                logs_dir.display()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// The env_logger to install, plus any module overrides that were skipped.
+    fn build_env_logger(&self) -> (env_logger::Logger, Vec<String>) {
         let filter = match self.level {
             LogLevel::Trace => LevelFilter::Trace,
             LogLevel::Debug => LevelFilter::Debug,
@@ -42,80 +215,133 @@ impl StructuredLogger {
             LogLevel::Error => LevelFilter::Error,
         };
 
-        env_logger::Builder::new()
-            .filter_level(filter)
-            .format(|buf, record| {
-                writeln!(
+        let mut builder = env_logger::Builder::new();
+        builder.filter_level(filter);
+
+        let mut problems = Vec::new();
+        for (module, level) in &self.module_levels {
+            let Some(target) = resolve_module_target(module) else {
+                problems.push(format!("unknown module '{}'", module));
+                continue;
+            };
+            match level.parse::<LevelFilter>() {
+                Ok(level) => {
+                    builder.filter_module(&target, level);
+                }
+                Err(_) => problems.push(format!("'{}' has unknown level '{}'", module, level)),
+            }
+        }
+
+        let format = self.format;
+        let service = self.service_name.clone();
+        let logger = builder
+            .format(move |buf, record| match format {
+                LogFormat::Text => writeln!(
                     buf,
-                    "[{} {} {}:{}] {}",
-                    Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+                    "[{} {} {}:{}]{} {}",
+                    format_log_timestamp(Utc::now()),
                     record.level(),
                     record.file().unwrap_or("unknown"),
                     record.line().unwrap_or(0),
+                    correlation_tag(),
                     record.args()
-                )
+                ),
+                LogFormat::Json => {
+                    let mut line = serde_json::json!({
+                        "ts": Utc::now().to_rfc3339(),
+                        "level": record.level().as_str(),
+                        "service": service,
+                        "target": record.target(),
+                        "file": record.file(),
+                        "line": record.line(),
+                        "msg": record.args().to_string(),
+                    });
+                    if let Some(id) = correlation::current() {
+                        line["correlation_id"] = serde_json::Value::String(id);
+                    }
+                    writeln!(buf, "{}", line)
+                }
             })
-            .try_init()
-            .ok();
-
-        if let Some(ref dir) = log_dir {
-            let logs_dir = dir.join("logs");
-            std::fs::create_dir_all(&logs_dir)?;
-            self.log_file = Some(logs_dir.join(format!("{}.log", self.service_name)));
+            .build();
+        (logger, problems)
+    }
 
-            info!(
-                "Logging initialized: level={:?}, log_dir={}",
-                self.level,
-                logs_dir.display()
-            );
+    fn format_line(&self, level: Level, message: &str) -> String {
+        match self.format {
+            LogFormat::Text => format!(
+                "[{}] [{}]{} {}: {}\n",
+                format_log_timestamp(Utc::now()),
+                self.service_name,
+                correlation_tag(),
+                level,
+                message
+            ),
+            LogFormat::Json => {
+                let mut line = serde_json::json!({
+                    "ts": Utc::now().to_rfc3339(),
+                    "level": level.as_str(),
+                    "service": self.service_name,
+                    "msg": message,
+                });
+                if let Some(id) = correlation::current() {
+                    line["correlation_id"] = serde_json::Value::String(id);
+                }
+                format!("{}\n", line)
+            }
         }
+    }
 
-        Ok(())
+    /// Scoped logger whose lines carry `context`, e.g. `"user_handlers"`.
+    pub fn with_context(self: &Arc<Self>, context: &str) -> StructuredContextualLogger {
+        StructuredContextualLogger::new(self.clone(), context)
     }
 
-    fn write_to_file(&self, message: &str) {
-        if let Some(ref file) = self.log_file {
-            if let Ok(mut f) = OpenOptions::new().create(true).append(true).open(file) {
-                let _ = writeln!(
-                    f,
-                    "[{}] {}",
-                    Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
-                    message
-                );
-            }
+    pub fn enabled(&self, level: Level) -> bool {
+        level <= Level::from(self.level.clone())
+    }
+
+    /// Queues the line for the background writer; never blocks on disk.
+    fn write_to_file(&self, level: Level, message: &str) {
+        if !self.enabled(level) {
+            return;
+        }
+        if let Some(ref writer) = self.writer {
+            writer.enqueue(self.format_line(level, message));
+        }
+    }
+
+    /// Waits until every line logged so far has reached the log file.
+    pub fn flush(&self) {
+        if let Some(ref writer) = self.writer {
+            writer.flush();
         }
     }
 }
 
 impl Logger for StructuredLogger {
     fn debug(&self, message: &str) {
-        let msg = format!("[{}] DEBUG: {}", self.service_name, message);
         debug!("{}", message);
-        self.write_to_file(&msg);
+        self.write_to_file(Level::Debug, message);
     }
 
     fn info(&self, message: &str) {
-        let msg = format!("[{}] INFO: {}", self.service_name, message);
         info!("{}", message);
-        self.write_to_file(&msg);
+        self.write_to_file(Level::Info, message);
     }
 
     fn warn(&self, message: &str) {
-        let msg = format!("[{}] WARN: {}", self.service_name, message);
         warn!("{}", message);
-        self.write_to_file(&msg);
+        self.write_to_file(Level::Warn, message);
     }
 
     fn error(&self, message: &str) {
-        let msg = format!("[{}] ERROR: {}", self.service_name, message);
         error!("{}", message);
-        self.write_to_file(&msg);
+        self.write_to_file(Level::Error, message);
     }
 
     fn trace(&self, message: &str) {
-        let msg = format!("[{}] TRACE: {}", self.service_name, message);
         log::trace!("{}", message);
-        self.write_to_file(&msg);
+        self.write_to_file(Level::Trace, message);
     }
 }
 
@@ -125,37 +351,30 @@ impl Clone for StructuredLogger {
             level: self.level.clone(),
             service_name: self.service_name.clone(),
             log_file: self.log_file.clone(),
+            format: self.format,
+            module_levels: self.module_levels.clone(),
+            max_file_size: self.max_file_size,
+            max_files: self.max_files,
+            writer: self.writer.clone(),
         }
     }
 }
 
 impl ContextualLogger for StructuredContextualLogger {
     fn debug(&self, message: &str) {
-        debug!(
-            "[{}] [{}] {}",
-            self.inner.service_name, self.context, message
-        );
+        self.log(Level::Debug, message);
     }
 
     fn info(&self, message: &str) {
-        info!(
-            "[{}] [{}] {}",
-            self.inner.service_name, self.context, message
-        );
+        self.log(Level::Info, message);
     }
 
     fn warn(&self, message: &str) {
-        warn!(
-            "[{}] [{}] {}",
-            self.inner.service_name, self.context, message
-        );
+        self.log(Level::Warn, message);
     }
 
     fn error(&self, message: &str) {
-        error!(
-            "[{}] [{}] {}",
-            self.inner.service_name, self.context, message
-        );
+        self.log(Level::Error, message);
     }
 }
 
@@ -166,6 +385,20 @@ impl StructuredContextualLogger {
             context: context.to_string(),
         }
     }
+
+    pub fn context(&self) -> &str {
+        &self.context
+    }
+
+    /// Same level filter and log file as the parent, tagged with the context.
+    fn log(&self, level: Level, message: &str) {
+        if !self.inner.enabled(level) {
+            return;
+        }
+        let message = format!("[{}] {}", self.context, message);
+        log::log!(level, "[{}] {}", self.inner.service_name, message);
+        self.inner.write_to_file(level, &message);
+    }
 }
 
 pub struct BuildLogger {
@@ -222,3 +455,86 @@ impl BuildLogger {
         &self.log_file
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Barrier;
+
+    /// What a binding does: take the id from the payload and log inside its scope.
+    fn handle(logger: &StructuredLogger, payload: &str, barrier: &Barrier) -> Vec<serde_json::Value> {
+        correlation::scope(correlation::from_payload(payload), || {
+            (0..3)
+                .map(|step| {
+                    // Both handlers are inside their scopes at the same time.
+                    barrier.wait();
+                    let line = logger.format_line(Level::Info, &format!("step {}", step));
+                    serde_json::from_str(&line).unwrap()
+                })
+                .collect()
+        })
+    }
+
+    #[test]
+    fn concurrent_handlers_log_their_own_correlation_ids() {
+        let logger = Arc::new(StructuredLogger::new(LogLevel::Info, "test").with_format(LogFormat::Json));
+        let barrier = Arc::new(Barrier::new(2));
+
+        let workers: Vec<_> = ["req-a", "req-b"]
+            .into_iter()
+            .map(|id| {
+                let (logger, barrier) = (logger.clone(), barrier.clone());
+                let payload = serde_json::json!({ "correlation_id": id, "name": "Ada" }).to_string();
+                std::thread::spawn(move || (id, handle(&logger, &payload, &barrier)))
+            })
+            .collect();
+
+        for worker in workers {
+            let (id, lines) = worker.join().unwrap();
+            assert_eq!(lines.len(), 3);
+            assert!(lines.iter().all(|line| line["correlation_id"] == id), "{:?}", lines);
+        }
+
+        let outside: serde_json::Value = serde_json::from_str(&logger.format_line(Level::Info, "idle")).unwrap();
+        assert!(outside.get("correlation_id").is_none());
+        assert!(correlation::current().is_none());
+    }
+
+    /// Held by tests that call `init`, which replaces the process-wide logger.
+    static GLOBAL_LOGGER: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    #[test]
+    fn a_second_init_applies_its_level() {
+        let _global = GLOBAL_LOGGER.lock().unwrap_or_else(|e| e.into_inner());
+        StructuredLogger::new(LogLevel::Debug, "test").init(None).unwrap();
+        assert_eq!(log::max_level(), LevelFilter::Debug);
+        assert!(log::log_enabled!(Level::Debug));
+
+        StructuredLogger::new(LogLevel::Error, "test").init(None).unwrap();
+        assert_eq!(log::max_level(), LevelFilter::Error);
+        assert!(!log::log_enabled!(Level::Warn));
+        assert!(log::log_enabled!(Level::Error));
+    }
+
+    #[test]
+    fn unknown_modules_are_skipped_without_failing_init() {
+        let _global = GLOBAL_LOGGER.lock().unwrap_or_else(|e| e.into_inner());
+        let modules: HashMap<String, String> = [
+            ("no_such_module".to_string(), "debug".to_string()),
+            ("core".to_string(), "debug".to_string()),
+        ]
+        .into();
+        let mut logger = StructuredLogger::new(LogLevel::Warn, "test").with_module_levels(&modules);
+
+        let (env_logger, problems) = logger.build_env_logger();
+        assert_eq!(problems, ["unknown module 'no_such_module'"]);
+        let enabled = |target: &str| {
+            log::Log::enabled(&env_logger, &log::Metadata::builder().level(Level::Debug).target(target).build())
+        };
+        let crate_name = env!("CARGO_CRATE_NAME");
+        assert!(enabled(&format!("{}::core::config", crate_name)));
+        assert!(!enabled(&format!("{}::di", crate_name)));
+
+        assert!(logger.init(None).is_ok());
+    }
+}
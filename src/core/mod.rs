@@ -15,10 +15,21 @@ pub use crate::model::repositories as persistence;
 pub use crate::model::services;
 
 // Re-export config and logging
+pub mod boot;
 pub mod config;
 pub mod di;
+pub mod event_bus;
+pub mod health;
 pub mod logging;
+pub mod monitoring;
 pub mod platform;
+pub mod readiness;
+pub mod runtime;
+pub mod scheduler;
+pub mod serialization;
+pub mod shutdown;
+pub mod timezone;
+pub mod trace;
 
 pub use config::*;
 pub use di::*;
@@ -0,0 +1,189 @@
+// src/core/monitoring/mod.rs
+// Rate-of-change alerting over application counters
+
+use log::warn;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::core::config::MonitoringSettings;
+use crate::core::domain::events::ApplicationErrorEvent;
+use crate::core::event_bus::InMemoryEventBus;
+use crate::core::ports::event_bus::EventBus;
+use crate::core::runtime;
+use crate::core::shutdown::Shutdown;
+
+pub type MetricSource = Box<dyn Fn() -> u64 + Send + Sync>;
+
+#[derive(Debug, Clone)]
+pub struct RateAlert {
+    pub metric: String,
+    pub rate_per_sec: f64,
+    pub threshold: f64,
+}
+
+/// Samples monotonically increasing counters and raises an
+/// `ApplicationErrorEvent` whenever one grows faster than its threshold.
+pub struct RateMonitor {
+    thresholds: HashMap<String, f64>,
+    sources: HashMap<String, MetricSource>,
+    last_samples: Mutex<HashMap<String, (u64, Instant)>>,
+    event_bus: Arc<dyn EventBus>,
+}
+
+impl RateMonitor {
+    pub fn new(settings: &MonitoringSettings, event_bus: Arc<dyn EventBus>) -> Self {
+        Self {
+            thresholds: settings.rate_thresholds.clone(),
+            sources: HashMap::new(),
+            last_samples: Mutex::new(HashMap::new()),
+            event_bus,
+        }
+    }
+
+    /// Registers a counter to watch. Metrics without a configured threshold are ignored.
+    pub fn watch<F>(&mut self, metric: &str, source: F)
+    where
+        F: Fn() -> u64 + Send + Sync + 'static,
+    {
+        self.sources.insert(metric.to_string(), Box::new(source));
+    }
+
+    /// Watches the bus counters as `events_published`, `events_handled` and
+    /// `events_failed`.
+    pub fn watch_event_bus(&mut self, event_bus: Arc<InMemoryEventBus>) {
+        let metrics = |bus: &InMemoryEventBus| futures::executor::block_on(bus.get_metrics());
+        let bus = event_bus.clone();
+        self.watch("events_published", move || metrics(&bus).events_published as u64);
+        let bus = event_bus.clone();
+        self.watch("events_handled", move || metrics(&bus).events_handled as u64);
+        self.watch("events_failed", move || metrics(&event_bus).events_failed as u64);
+    }
+
+    pub fn sample(&self) -> Vec<RateAlert> {
+        self.sample_at(Instant::now())
+    }
+
+    pub fn sample_at(&self, now: Instant) -> Vec<RateAlert> {
+        let mut alerts = Vec::new();
+        let mut last_samples = self.last_samples.lock().unwrap();
+
+        for (metric, source) in &self.sources {
+            let Some(&threshold) = self.thresholds.get(metric) else {
+                continue;
+            };
+
+            let value = source();
+            let previous = last_samples.insert(metric.clone(), (value, now));
+
+            let Some((prev_value, prev_at)) = previous else {
+                continue;
+            };
+
+            let elapsed = now.saturating_duration_since(prev_at).as_secs_f64();
+            if elapsed <= 0.0 {
+                continue;
+            }
+
+            let rate = value.saturating_sub(prev_value) as f64 / elapsed;
+            if rate > threshold {
+                alerts.push(RateAlert {
+                    metric: metric.clone(),
+                    rate_per_sec: rate,
+                    threshold,
+                });
+            }
+        }
+        drop(last_samples);
+
+        for alert in &alerts {
+            let message = format!(
+                "{} rising at {:.2}/s (threshold {:.2}/s)",
+                alert.metric, alert.rate_per_sec, alert.threshold
+            );
+            warn!("Rate alert: {}", message);
+
+            let event = ApplicationErrorEvent::new("rate_monitor".to_string(), message);
+            if let Err(e) = self.event_bus.publish(Box::new(event)) {
+                warn!("Failed to publish rate alert: {}", e);
+            }
+        }
+
+        alerts
+    }
+
+    /// Samples on a fixed interval on the shared runtime until `shutdown` is triggered.
+    pub fn spawn(
+        self: Arc<Self>,
+        interval: Duration,
+        shutdown: Shutdown,
+    ) -> tokio::task::JoinHandle<()> {
+        runtime::shared().spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {
+                        self.sample();
+                    }
+                    _ = shutdown.cancelled() => break,
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use crate::core::event_bus::EventBusConfig;
+    use crate::core::logging::StructuredLogger;
+    use crate::core::ports::logger::LogLevel;
+
+    #[test]
+    fn alert_fires_only_once_the_rate_crosses_the_threshold() {
+        let bus = Arc::new(InMemoryEventBus::new(
+            EventBusConfig::new().with_logging(false),
+            StructuredLogger::new(LogLevel::Error, "monitoring_test"),
+        ));
+        let settings = MonitoringSettings {
+            sample_interval_ms: 1000,
+            rate_thresholds: HashMap::from([("events_failed".to_string(), 10.0)]),
+        };
+        let raised = Arc::new(Mutex::new(Vec::new()));
+        {
+            let raised = raised.clone();
+            futures::executor::block_on(bus.subscribe("application.error", move |_, payload| {
+                raised.lock().unwrap().push(payload)
+            }));
+        }
+        let counter = Arc::new(AtomicU64::new(0));
+        let mut monitor = RateMonitor::new(&settings, bus.clone());
+        {
+            let counter = counter.clone();
+            monitor.watch("events_failed", move || counter.load(Ordering::SeqCst));
+        }
+
+        let start = Instant::now();
+        assert!(monitor.sample_at(start).is_empty(), "first sample only sets a baseline");
+
+        counter.store(5, Ordering::SeqCst);
+        assert!(monitor.sample_at(start + Duration::from_secs(1)).is_empty());
+
+        counter.store(15, Ordering::SeqCst);
+        assert!(
+            monitor.sample_at(start + Duration::from_secs(2)).is_empty(),
+            "exactly at the threshold does not alert"
+        );
+
+        counter.store(40, Ordering::SeqCst);
+        let alerts = monitor.sample_at(start + Duration::from_secs(3));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].metric, "events_failed");
+        assert_eq!(alerts[0].rate_per_sec, 25.0);
+        assert_eq!(alerts[0].threshold, 10.0);
+
+        assert_eq!(raised.lock().unwrap().len(), 1);
+    }
+}
@@ -1,14 +1,32 @@
 // src/core/mvvm/viewmodel.rs
 // Base ViewModel for backend MVVM
 
+use std::any::Any;
 use std::result::Result as StdResult;
 use std::sync::Arc;
 
+/// Opaque copy of a viewmodel's state, used to roll back atomic batches.
+pub type Checkpoint = Box<dyn Any + Send>;
+
 pub trait ViewModel: Send + Sync {
     fn name(&self) -> &str;
     fn state(&self) -> ViewModelState;
     fn handle_command(&self, command: &str, payload: &str) -> StdResult<String, ViewModelError>;
     fn handle_query(&self, query: &str, params: &[String]) -> StdResult<String, ViewModelError>;
+
+    /// Command names this viewmodel handles, used by the dispatcher for routing.
+    fn commands(&self) -> &[&'static str] {
+        &[]
+    }
+
+    /// Snapshot of mutable state; `None` means the viewmodel cannot take part
+    /// in atomic batches.
+    fn checkpoint(&self) -> Option<Checkpoint> {
+        None
+    }
+
+    /// Restores a snapshot taken by `checkpoint`.
+    fn restore(&self, _checkpoint: Checkpoint) {}
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -20,6 +38,19 @@ pub enum ViewModelState {
     Busy,
 }
 
+impl ViewModelState {
+    /// `{ "state": "ready" }`, with `"error"` added for the error state.
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            ViewModelState::Initial => serde_json::json!({ "state": "initial" }),
+            ViewModelState::Loading => serde_json::json!({ "state": "loading" }),
+            ViewModelState::Ready => serde_json::json!({ "state": "ready" }),
+            ViewModelState::Error(msg) => serde_json::json!({ "state": "error", "error": msg }),
+            ViewModelState::Busy => serde_json::json!({ "state": "busy" }),
+        }
+    }
+}
+
 impl Default for ViewModelState {
     fn default() -> Self {
         ViewModelState::Initial
@@ -34,6 +65,7 @@ pub enum ViewModelError {
     NotFound(String),
     ValidationError(String),
     Unauthorized(String),
+    Busy(String),
 }
 
 impl ViewModelError {
@@ -60,6 +92,10 @@ impl ViewModelError {
     pub fn unauthorized(msg: impl Into<String>) -> Self {
         ViewModelError::Unauthorized(msg.into())
     }
+
+    pub fn busy(msg: impl Into<String>) -> Self {
+        ViewModelError::Busy(msg.into())
+    }
 }
 
 impl std::fmt::Display for ViewModelError {
@@ -71,10 +107,24 @@ impl std::fmt::Display for ViewModelError {
             ViewModelError::NotFound(msg) => write!(f, "Not found: {}", msg),
             ViewModelError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
             ViewModelError::Unauthorized(msg) => write!(f, "Unauthorized: {}", msg),
+            ViewModelError::Busy(msg) => write!(f, "Busy: {}", msg),
         }
     }
 }
 
 impl std::error::Error for ViewModelError {}
 
+impl From<crate::core::domain::errors::DomainError> for ViewModelError {
+    fn from(err: crate::core::domain::errors::DomainError) -> Self {
+        use crate::core::domain::errors::DomainError;
+        match err {
+            DomainError::ValidationError(msg) | DomainError::AlreadyExists(msg) => {
+                ViewModelError::ValidationError(msg)
+            }
+            DomainError::NotFound(msg) => ViewModelError::NotFound(msg),
+            DomainError::InvalidOperation(msg) => ViewModelError::ExecutionFailed(msg),
+        }
+    }
+}
+
 pub type SharedViewModel = Arc<dyn ViewModel>;
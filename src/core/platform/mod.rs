@@ -1 +1,58 @@
 // Core platform - platform-specific utilities
+
+use std::path::PathBuf;
+
+pub mod sysinfo;
+
+/// Where `resolve_data_dir` found its directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataDirSource {
+    Platform,
+    Fallback,
+}
+
+/// Picks the per-app data directory: the platform data dir joined with the
+/// app name when available, otherwise `fallback`, defaulting to the working
+/// directory so that the logger's `logs/` subdirectory ends up at `./logs`.
+pub fn resolve_data_dir(
+    platform_dir: Option<PathBuf>,
+    app_name: &str,
+    fallback: Option<&str>,
+) -> (PathBuf, DataDirSource) {
+    match platform_dir {
+        Some(dir) => (dir.join(app_name), DataDirSource::Platform),
+        None => (
+            PathBuf::from(fallback.unwrap_or(".")),
+            DataDirSource::Fallback,
+        ),
+    }
+}
+
+pub fn app_data_dir(app_name: &str, fallback: Option<&str>) -> (PathBuf, DataDirSource) {
+    resolve_data_dir(dirs::data_local_dir(), app_name, fallback)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_platform_dir_falls_back() {
+        assert_eq!(
+            resolve_data_dir(None, "app", Some("/srv/app")),
+            (PathBuf::from("/srv/app"), DataDirSource::Fallback)
+        );
+        assert_eq!(
+            resolve_data_dir(None, "app", None),
+            (PathBuf::from("."), DataDirSource::Fallback)
+        );
+    }
+
+    #[test]
+    fn platform_dir_is_joined_with_the_app_name() {
+        assert_eq!(
+            resolve_data_dir(Some(PathBuf::from("/data")), "app", Some("/srv/app")),
+            (PathBuf::from("/data/app"), DataDirSource::Platform)
+        );
+    }
+}
@@ -0,0 +1,237 @@
+// core/platform/sysinfo.rs
+// System info collection that reports which sources failed instead of hiding it
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::path::{Path, PathBuf};
+
+/// Where each source is read from; overridable so a failing source can be simulated.
+#[derive(Debug, Clone)]
+pub struct SystemInfoSources {
+    pub meminfo: PathBuf,
+    pub loadavg: PathBuf,
+    /// File whose volume the `disk` section describes; no section when unset.
+    pub database_path: Option<PathBuf>,
+}
+
+impl SystemInfoSources {
+    pub fn with_database_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.database_path = Some(path.into());
+        self
+    }
+}
+
+impl Default for SystemInfoSources {
+    fn default() -> Self {
+        Self {
+            meminfo: PathBuf::from("/proc/meminfo"),
+            loadavg: PathBuf::from("/proc/loadavg"),
+            database_path: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceWarning {
+    pub source: String,
+    pub error: String,
+}
+
+/// Whatever could be collected, plus one warning per source that failed.
+#[derive(Debug, Clone, Default)]
+pub struct SystemInfo {
+    pub data: Map<String, Value>,
+    pub warnings: Vec<SourceWarning>,
+}
+
+impl SystemInfo {
+    fn record(&mut self, source: &str, result: Result<Value, String>) {
+        match result {
+            Ok(value) => {
+                self.data.insert(source.to_string(), value);
+            }
+            Err(error) => self.warn(source, error),
+        }
+    }
+
+    fn warn(&mut self, source: &str, error: String) {
+        self.warnings.push(SourceWarning {
+            source: source.to_string(),
+            error,
+        });
+    }
+}
+
+pub fn collect(sources: &SystemInfoSources) -> SystemInfo {
+    let mut info = SystemInfo::default();
+    info.data.insert(
+        "os".to_string(),
+        json!({
+            "platform": std::env::consts::OS,
+            "arch": std::env::consts::ARCH,
+        }),
+    );
+    info.record("memory", read_memory(sources));
+    let cpu = collect_cpu(sources, &mut info);
+    info.data.insert("cpu".to_string(), cpu);
+    if let Some(path) = &sources.database_path {
+        info.record("disk", read_disk(path));
+    }
+    info
+}
+
+/// Total and free bytes of the volume holding `path`: the mounted disk with
+/// the longest mount point that is a prefix of the resolved path.
+fn read_disk(path: &Path) -> Result<Value, String> {
+    let resolved = resolve_existing(path)?;
+    let disks = ::sysinfo::Disks::new_with_refreshed_list();
+    let disk = disks
+        .list()
+        .iter()
+        .filter(|disk| resolved.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().components().count())
+        .ok_or_else(|| format!("No mounted volume contains {}", resolved.display()))?;
+
+    Ok(json!({
+        "path": resolved.display().to_string(),
+        "mount_point": disk.mount_point().display().to_string(),
+        "total_bytes": disk.total_space(),
+        "free_bytes": disk.available_space(),
+    }))
+}
+
+/// The database file may not exist yet, so fall back to its directory.
+fn resolve_existing(path: &Path) -> Result<PathBuf, String> {
+    if path.as_os_str().is_empty() || path == Path::new(":memory:") {
+        return Err("The database is in memory".to_string());
+    }
+    path.canonicalize()
+        .or_else(|_| {
+            let parent = path.parent().filter(|p| !p.as_os_str().is_empty());
+            parent.unwrap_or(Path::new(".")).canonicalize()
+        })
+        .map_err(|e| format!("Cannot resolve {}: {}", path.display(), e))
+}
+
+/// Logical core count and, on Linux, load averages. Each field is omitted
+/// on its own when unavailable, so `cpu` is always present.
+fn collect_cpu(sources: &SystemInfoSources, info: &mut SystemInfo) -> Value {
+    let mut cpu = Map::new();
+    match std::thread::available_parallelism() {
+        Ok(n) => {
+            cpu.insert("logical_cores".to_string(), json!(n.get()));
+        }
+        Err(e) => info.warn("cpu.logical_cores", e.to_string()),
+    }
+
+    if cfg!(target_os = "linux") {
+        match read_loadavg(sources) {
+            Ok(load) => {
+                cpu.insert("load_average".to_string(), load);
+            }
+            Err(error) => info.warn("cpu.load_average", error),
+        }
+    }
+
+    Value::Object(cpu)
+}
+
+fn read_loadavg(sources: &SystemInfoSources) -> Result<Value, String> {
+    let content = std::fs::read_to_string(&sources.loadavg)
+        .map_err(|e| format!("Cannot read {}: {}", sources.loadavg.display(), e))?;
+    parse_loadavg(&content)
+}
+
+/// Reads the 1/5/15-minute averages from the first three fields.
+pub fn parse_loadavg(content: &str) -> Result<Value, String> {
+    let mut fields = content.split_whitespace();
+    let mut next = |name: &str| -> Result<f64, String> {
+        let raw = fields
+            .next()
+            .ok_or_else(|| format!("loadavg is missing the {} average", name))?;
+        raw.parse::<f64>()
+            .map_err(|e| format!("{} average '{}' is not a number: {}", name, raw, e))
+    };
+
+    Ok(json!({
+        "one": next("1-minute")?,
+        "five": next("5-minute")?,
+        "fifteen": next("15-minute")?,
+    }))
+}
+
+/// Same `{ total_mb, free_mb }` shape on every platform. Linux reads
+/// `/proc/meminfo` so a failing source can be simulated; elsewhere the
+/// `sysinfo` crate asks the OS (`sysctl`, `GlobalMemoryStatusEx`).
+#[cfg(target_os = "linux")]
+fn read_memory(sources: &SystemInfoSources) -> Result<Value, String> {
+    read_meminfo(sources)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_memory(_sources: &SystemInfoSources) -> Result<Value, String> {
+    let mut system = ::sysinfo::System::new();
+    system.refresh_memory();
+    let total = system.total_memory();
+    if total == 0 {
+        return Err("The OS reported no memory information".to_string());
+    }
+    // The crate reports bytes; `free` is what the OS has not handed out at all,
+    // matching MemFree on Linux.
+    Ok(json!({
+        "total_mb": total / (1024 * 1024),
+        "free_mb": system.free_memory() / (1024 * 1024),
+    }))
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn read_meminfo(sources: &SystemInfoSources) -> Result<Value, String> {
+    let content = std::fs::read_to_string(&sources.meminfo)
+        .map_err(|e| format!("Cannot read {}: {}", sources.meminfo.display(), e))?;
+    parse_meminfo(&content)
+}
+
+/// Extracts MemTotal/MemFree (kB) as MiB. A missing or malformed entry for
+/// either key fails the whole source rather than returning half the numbers.
+pub fn parse_meminfo(content: &str) -> Result<Value, String> {
+    let field = |key: &str| -> Result<u64, String> {
+        let line = content
+            .lines()
+            .find(|line| line.split(':').next().map(str::trim) == Some(key))
+            .ok_or_else(|| format!("{} missing from meminfo", key))?;
+        let raw = line
+            .split_once(':')
+            .and_then(|(_, rest)| rest.split_whitespace().next())
+            .ok_or_else(|| format!("{} has no value", key))?;
+        raw.parse::<u64>()
+            .map_err(|e| format!("{} value '{}' is not a number: {}", key, raw, e))
+    };
+
+    Ok(json!({
+        "total_mb": field("MemTotal")? / 1024,
+        "free_mb": field("MemFree")? / 1024,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Elsewhere memory comes from the OS rather than an injectable file.
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn unreadable_source_becomes_a_warning() {
+        let sources = SystemInfoSources {
+            meminfo: std::env::temp_dir().join(format!("meminfo-{}", uuid::Uuid::new_v4())),
+            ..SystemInfoSources::default()
+        };
+
+        let info = collect(&sources);
+
+        let failed: Vec<_> = info.warnings.iter().map(|w| w.source.as_str()).collect();
+        assert_eq!(failed, vec!["memory"]);
+        assert!(info.warnings[0].error.contains("Cannot read"));
+        assert!(info.data.contains_key("os"));
+        assert!(!info.data.contains_key("memory"));
+    }
+}
@@ -0,0 +1,133 @@
+// src/core/readiness/mod.rs
+// Gate for frontend commands that arrive before the backend services are ready
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+pub const DEFAULT_QUEUE_CAPACITY: usize = 64;
+pub const DEFAULT_RETRY_AFTER_MS: u64 = 500;
+
+type QueuedJob = Box<dyn FnOnce() + Send>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotReady {
+    pub retry_after_ms: u64,
+}
+
+impl NotReady {
+    /// An error envelope like every other response, plus `retry_after` in ms.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "success": false,
+            "error": format!("Not ready; retry in {}ms", self.retry_after_ms),
+            "code": "NOT_READY",
+            "retry_after": self.retry_after_ms,
+        })
+    }
+}
+
+/// Runs jobs immediately once ready; until then holds up to `capacity` of
+/// them and replays them in arrival order from `mark_ready`.
+pub struct ReadinessGate {
+    ready: AtomicBool,
+    queue: Mutex<VecDeque<QueuedJob>>,
+    capacity: usize,
+    retry_after_ms: u64,
+}
+
+impl ReadinessGate {
+    pub fn new(capacity: usize, retry_after_ms: u64) -> Self {
+        Self {
+            ready: AtomicBool::new(false),
+            queue: Mutex::new(VecDeque::new()),
+            capacity,
+            retry_after_ms,
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Acquire)
+    }
+
+    pub fn queued(&self) -> usize {
+        self.queue.lock().unwrap().len()
+    }
+
+    pub fn submit<F>(&self, job: F) -> Result<(), NotReady>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        {
+            let mut queue = self.queue.lock().unwrap();
+            if !self.is_ready() {
+                if queue.len() >= self.capacity {
+                    return Err(NotReady { retry_after_ms: self.retry_after_ms });
+                }
+                queue.push_back(Box::new(job));
+                return Ok(());
+            }
+        }
+
+        job();
+        Ok(())
+    }
+
+    /// Opens the gate and runs everything queued so far. Returns how many
+    /// queued jobs were flushed.
+    pub fn mark_ready(&self) -> usize {
+        let pending: Vec<QueuedJob> = {
+            let mut queue = self.queue.lock().unwrap();
+            self.ready.store(true, Ordering::Release);
+            queue.drain(..).collect()
+        };
+
+        let flushed = pending.len();
+        for job in pending {
+            job();
+        }
+        flushed
+    }
+}
+
+impl Default for ReadinessGate {
+    fn default() -> Self {
+        Self::new(DEFAULT_QUEUE_CAPACITY, DEFAULT_RETRY_AFTER_MS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn command_before_ready_is_queued_then_runs() {
+        let gate = ReadinessGate::new(4, 100);
+        let (tx, rx) = mpsc::channel();
+
+        gate.submit(move || tx.send("create_user ok").unwrap()).unwrap();
+        assert_eq!(gate.queued(), 1);
+        assert!(rx.try_recv().is_err());
+
+        assert_eq!(gate.mark_ready(), 1);
+        assert_eq!(rx.try_recv().unwrap(), "create_user ok");
+        assert_eq!(gate.queued(), 0);
+    }
+
+    #[test]
+    fn full_queue_rejects_with_not_ready() {
+        let gate = ReadinessGate::new(1, 250);
+        gate.submit(|| {}).unwrap();
+
+        let rejected = gate.submit(|| panic!("rejected job must not run")).unwrap_err();
+        assert_eq!(rejected, NotReady { retry_after_ms: 250 });
+        assert_eq!(rejected.to_json()["code"], "NOT_READY");
+        assert_eq!(rejected.to_json()["retry_after"], 250);
+
+        gate.mark_ready();
+        let (tx, rx) = mpsc::channel();
+        gate.submit(move || tx.send(()).unwrap()).unwrap();
+        assert!(rx.try_recv().is_ok());
+    }
+}
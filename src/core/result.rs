@@ -51,6 +51,63 @@ impl<T, E> Result<T, E> {
         }
     }
 
+    /// Returns the provided default if Err, otherwise applies a function to the Ok value
+    pub fn map_or<U, F>(self, default: U, op: F) -> U
+    where
+        F: FnOnce(T) -> U,
+    {
+        match self {
+            Result::Ok(t) => op(t),
+            Result::Err(_) => default,
+        }
+    }
+
+    /// Maps the Ok value with op, or the Err value with default
+    pub fn map_or_else<U, D, F>(self, default: D, op: F) -> U
+    where
+        D: FnOnce(E) -> U,
+        F: FnOnce(T) -> U,
+    {
+        match self {
+            Result::Ok(t) => op(t),
+            Result::Err(e) => default(e),
+        }
+    }
+
+    /// Calls op with a reference to the Ok value, then returns the result unchanged
+    pub fn inspect<F>(self, op: F) -> Self
+    where
+        F: FnOnce(&T),
+    {
+        if let Result::Ok(t) = &self {
+            op(t);
+        }
+        self
+    }
+
+    /// Calls op with a reference to the Err value, then returns the result unchanged
+    pub fn inspect_err<F>(self, op: F) -> Self
+    where
+        F: FnOnce(&E),
+    {
+        if let Result::Err(e) = &self {
+            op(e);
+        }
+        self
+    }
+
+    /// Returns the contained Ok value
+    /// Panics with msg and the Err value if the value is an Err
+    pub fn expect(self, msg: &str) -> T
+    where
+        E: std::fmt::Debug,
+    {
+        match self {
+            Result::Ok(t) => t,
+            Result::Err(e) => panic!("{}: {:?}", msg, e),
+        }
+    }
+
     /// Returns the contained Ok value
     /// Panics if the value is an Err
     pub fn unwrap(self) -> T
@@ -63,6 +120,18 @@ impl<T, E> Result<T, E> {
         }
     }
 
+    /// Returns the contained Err value
+    /// Panics if the value is an Ok
+    pub fn unwrap_err(self) -> E
+    where
+        T: std::fmt::Debug,
+    {
+        match self {
+            Result::Ok(t) => panic!("unwrap_err called on Ok: {:?}", t),
+            Result::Err(e) => e,
+        }
+    }
+
     /// Returns the contained Ok value or a provided default
     pub fn unwrap_or(self, default: T) -> T {
         match self {
@@ -82,14 +151,22 @@ impl<T, E> Result<T, E> {
         }
     }
 
-    /// Converts from Option<T> to Result<T, E>
-    pub fn ok_or(self, err: E) -> Option<T> {
+    /// Converts to Option<T>, discarding the Err value
+    pub fn ok(self) -> Option<T> {
         match self {
             Result::Ok(t) => Some(t),
             Result::Err(_) => None,
         }
     }
 
+    /// Converts to Option<E>, discarding the Ok value
+    pub fn err(self) -> Option<E> {
+        match self {
+            Result::Ok(_) => None,
+            Result::Err(e) => Some(e),
+        }
+    }
+
     /// Calls op if the result is Ok, otherwise returns the Err value
     pub fn and_then<U, F>(self, op: F) -> Result<U, E>
     where
@@ -146,6 +223,18 @@ impl<T, E> IntoResult<T, E> for std::result::Result<T, E> {
     }
 }
 
+impl<T, E> From<std::result::Result<T, E>> for Result<T, E> {
+    fn from(result: std::result::Result<T, E>) -> Self {
+        result.into_result()
+    }
+}
+
+impl<T, E> From<Result<T, E>> for std::result::Result<T, E> {
+    fn from(result: Result<T, E>) -> Self {
+        result.into_std()
+    }
+}
+
 /// Convenience macro for creating Ok results
 #[macro_export]
 macro_rules! ok {
@@ -161,3 +250,158 @@ macro_rules! err {
         $crate::core::result::Result::Err($($arg)*)
     };
 }
+
+/// `?` for our Result: `std::ops::Try` is unstable, so this unwraps an Ok or
+/// returns the Err, converted with `From`, from the enclosing function. The
+/// operand may be our Result or a std one.
+///
+/// ```ignore
+/// fn load(id: i64) -> Result<User, DomainError> {
+///     let row = try_result!(fetch(id));
+///     ok!(try_result!(User::from_row(row)))
+/// }
+/// ```
+#[macro_export]
+macro_rules! try_result {
+    ($expr:expr) => {
+        match $crate::core::result::Result::from($expr) {
+            $crate::core::result::Result::Ok(value) => value,
+            $crate::core::result::Result::Err(e) => {
+                return $crate::core::result::Result::Err(::std::convert::From::from(e));
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    fn parsed(raw: &str) -> Result<i32, String> {
+        raw.parse::<i32>().map_err(|e| e.to_string()).into_result()
+    }
+
+    #[test]
+    fn map_or_applies_op_or_falls_back() {
+        assert_eq!(parsed("2").map_or(0, |n| n * 10), 20);
+        assert_eq!(parsed("x").map_or(0, |n| n * 10), 0);
+    }
+
+    #[test]
+    fn map_or_else_maps_either_side() {
+        assert_eq!(parsed("2").map_or_else(|e| e.len(), |n| n as usize), 2);
+        let message_len = "invalid digit found in string".len();
+        assert_eq!(parsed("x").map_or_else(|e| e.len(), |n| n as usize), message_len);
+    }
+
+    #[test]
+    fn inspect_sees_only_ok_values() {
+        let seen = RefCell::new(Vec::new());
+        let ok = parsed("3").inspect(|n| seen.borrow_mut().push(*n));
+        let err = parsed("x").inspect(|n| seen.borrow_mut().push(*n));
+
+        assert_eq!(ok.unwrap(), 3);
+        assert!(err.is_err());
+        assert_eq!(*seen.borrow(), vec![3]);
+    }
+
+    #[test]
+    fn inspect_err_sees_only_err_values() {
+        let seen = RefCell::new(Vec::new());
+        let ok = parsed("3").inspect_err(|e| seen.borrow_mut().push(e.clone()));
+        let err = parsed("x").inspect_err(|e| seen.borrow_mut().push(e.clone()));
+
+        assert!(ok.is_ok());
+        assert_eq!(err.unwrap_err(), "invalid digit found in string");
+        assert_eq!(*seen.borrow(), vec!["invalid digit found in string".to_string()]);
+    }
+
+    #[test]
+    fn ok_and_err_keep_only_their_own_side() {
+        assert_eq!(Result::<i32, String>::Ok(7).ok(), Some(7));
+        assert_eq!(Result::<i32, String>::Err("bad".to_string()).ok(), None);
+        assert_eq!(Result::<i32, String>::Err("bad".to_string()).err(), Some("bad".to_string()));
+        assert_eq!(Result::<i32, String>::Ok(7).err(), None);
+    }
+
+    #[test]
+    fn unwrap_err_returns_the_error() {
+        assert_eq!(parsed("").unwrap_err(), "cannot parse integer from empty string");
+    }
+
+    #[test]
+    #[should_panic(expected = "unwrap_err called on Ok: 5")]
+    fn unwrap_err_panics_on_ok() {
+        parsed("5").unwrap_err();
+    }
+
+    #[test]
+    fn expect_returns_the_ok_value() {
+        assert_eq!(parsed("7").expect("a number"), 7);
+    }
+
+    #[test]
+    #[should_panic(expected = "a number: \"invalid digit found in string\"")]
+    fn expect_panics_with_the_message_and_error() {
+        parsed("x").expect("a number");
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum LoadError {
+        Parse(String),
+        OutOfRange(i32),
+    }
+
+    impl From<std::num::ParseIntError> for LoadError {
+        fn from(e: std::num::ParseIntError) -> Self {
+            LoadError::Parse(e.to_string())
+        }
+    }
+
+    impl From<String> for LoadError {
+        fn from(e: String) -> Self {
+            LoadError::Parse(e)
+        }
+    }
+
+    fn in_range(n: i32) -> Result<i32, LoadError> {
+        if (1..=100).contains(&n) {
+            ok!(n)
+        } else {
+            err!(LoadError::OutOfRange(n))
+        }
+    }
+
+    /// Parses both operands (a std Result, then ours), range-checks them and
+    /// adds them, converting each error through `From`.
+    fn load(a: &str, b: &str) -> Result<i32, LoadError> {
+        let a = try_result!(a.parse::<i32>());
+        let b = try_result!(parsed(b));
+        let a = try_result!(in_range(a));
+        ok!(a + try_result!(in_range(b)))
+    }
+
+    #[test]
+    fn try_result_chains_fallible_calls_and_converts_errors() {
+        assert_eq!(load("2", "40").unwrap(), 42);
+        assert_eq!(
+            load("x", "40").unwrap_err(),
+            LoadError::Parse("invalid digit found in string".to_string())
+        );
+        assert_eq!(
+            load("2", "").unwrap_err(),
+            LoadError::Parse("cannot parse integer from empty string".to_string())
+        );
+        assert_eq!(load("200", "40").unwrap_err(), LoadError::OutOfRange(200));
+        assert_eq!(load("2", "-1").unwrap_err(), LoadError::OutOfRange(-1));
+    }
+
+    #[test]
+    fn conversions_round_trip_through_std() {
+        let std_ok: std::result::Result<i32, String> = parsed("4").into();
+        assert_eq!(std_ok, Ok(4));
+        let ours: Result<i32, String> = Result::from(std_ok);
+        assert_eq!(ours.into_std(), Ok(4));
+    }
+}
@@ -0,0 +1,21 @@
+// src/core/runtime/mod.rs
+// Shared tokio runtime for background work started from synchronous code
+
+use std::sync::OnceLock;
+use tokio::runtime::{Builder, Handle, Runtime};
+
+static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+pub fn shared() -> &'static Runtime {
+    RUNTIME.get_or_init(|| {
+        Builder::new_multi_thread()
+            .enable_all()
+            .thread_name("app-runtime")
+            .build()
+            .expect("failed to build shared tokio runtime")
+    })
+}
+
+pub fn handle() -> Handle {
+    shared().handle().clone()
+}
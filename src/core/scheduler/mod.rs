@@ -0,0 +1,45 @@
+// src/core/scheduler/mod.rs
+// Recurring background jobs on the shared runtime, stopped by the shutdown signal
+
+use std::time::Duration;
+
+use log::debug;
+
+use crate::core::runtime;
+use crate::core::shutdown::Shutdown;
+
+pub struct Scheduler {
+    shutdown: Shutdown,
+}
+
+impl Scheduler {
+    pub fn new(shutdown: Shutdown) -> Self {
+        Self { shutdown }
+    }
+
+    /// Runs `job` every `interval`, first after one full interval. Jobs run on
+    /// a blocking thread so synchronous database work does not stall the runtime.
+    pub fn every<F>(&self, name: &str, interval: Duration, job: F) -> tokio::task::JoinHandle<()>
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        let name = name.to_string();
+        let shutdown = self.shutdown.clone();
+        let job = std::sync::Arc::new(job);
+
+        runtime::shared().spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {
+                        debug!("Running scheduled job: {}", name);
+                        let job = job.clone();
+                        let _ = tokio::task::spawn_blocking(move || job()).await;
+                    }
+                    _ = shutdown.cancelled() => break,
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,109 @@
+// src/core/serialization/mod.rs
+// Wire formats for payloads leaving the process
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    #[default]
+    Json,
+    #[serde(alias = "msgpack")]
+    MessagePack,
+    Cbor,
+    Yaml,
+}
+
+impl Format {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::MessagePack => "messagepack",
+            Format::Cbor => "cbor",
+            Format::Yaml => "yaml",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Format::Json => "application/json",
+            Format::MessagePack => "application/msgpack",
+            Format::Cbor => "application/cbor",
+            Format::Yaml => "application/yaml",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SerializationError {
+    pub format: Format,
+    pub message: String,
+}
+
+impl SerializationError {
+    fn new(format: Format, error: impl std::fmt::Display) -> Self {
+        Self {
+            format,
+            message: error.to_string(),
+        }
+    }
+}
+
+fn failed<E: std::fmt::Display>(format: Format) -> impl FnOnce(E) -> SerializationError {
+    move |e| SerializationError::new(format, e)
+}
+
+impl std::fmt::Display for SerializationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} serialization failed: {}", self.format.as_str(), self.message)
+    }
+}
+
+impl std::error::Error for SerializationError {}
+
+pub fn serialize<T: Serialize + ?Sized>(format: Format, value: &T) -> Result<Vec<u8>, SerializationError> {
+    match format {
+        Format::Json => serde_json::to_vec(value).map_err(failed(format)),
+        // Maps keep their keys, so decoded values have the JSON shape.
+        Format::MessagePack => rmp_serde::to_vec_named(value).map_err(failed(format)),
+        Format::Cbor => {
+            let mut bytes = Vec::new();
+            ciborium::into_writer(value, &mut bytes).map_err(failed(format))?;
+            Ok(bytes)
+        }
+        Format::Yaml => serde_yaml::to_string(value).map(String::into_bytes).map_err(failed(format)),
+    }
+}
+
+pub fn deserialize<T: DeserializeOwned>(format: Format, bytes: &[u8]) -> Result<T, SerializationError> {
+    match format {
+        Format::Json => serde_json::from_slice(bytes).map_err(failed(format)),
+        Format::MessagePack => rmp_serde::from_slice(bytes).map_err(failed(format)),
+        Format::Cbor => ciborium::from_reader(bytes).map_err(failed(format)),
+        Format::Yaml => serde_yaml::from_slice(bytes).map_err(failed(format)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::domain::events::UserCreatedEvent;
+    use crate::core::domain::user::UserId;
+
+    #[test]
+    fn every_format_round_trips_a_user_created_event() {
+        let event = UserCreatedEvent::new(UserId(7), "Ada".to_string(), "ada@example.com".to_string());
+        let expected = serde_json::to_value(&event).unwrap();
+
+        for format in [Format::Json, Format::MessagePack, Format::Cbor, Format::Yaml] {
+            let bytes = serialize(format, &event).unwrap();
+
+            let decoded: UserCreatedEvent = deserialize(format, &bytes).unwrap();
+            assert_eq!(serde_json::to_value(&decoded).unwrap(), expected, "{}", format.as_str());
+
+            let shape: serde_json::Value = deserialize(format, &bytes).unwrap();
+            assert_eq!(shape, expected, "{}", format.as_str());
+        }
+    }
+}
@@ -0,0 +1,372 @@
+// src/core/shutdown/mod.rs
+// Process-wide shutdown signal and the coordinator that acts on it
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use crate::core::ports::event_bus::EventBus;
+use crate::core::runtime;
+use crate::model::entities::events::ApplicationShutdownEvent;
+
+pub const REASON_WINDOW_CLOSED: &str = "window_closed";
+pub const REASON_EXIT: &str = "exit";
+pub const REASON_SIGNAL: &str = "signal";
+pub const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+/// Cloneable shutdown flag. The first `trigger` wins and fixes the reason;
+/// background tasks await `cancelled()` to stop.
+#[derive(Clone)]
+pub struct Shutdown {
+    tx: Arc<watch::Sender<Option<String>>>,
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        let (tx, _rx) = watch::channel(None);
+        Self { tx: Arc::new(tx) }
+    }
+
+    /// Returns `false` if shutdown had already been triggered.
+    pub fn trigger(&self, reason: &str) -> bool {
+        self.tx.send_if_modified(|current| {
+            if current.is_some() {
+                return false;
+            }
+            *current = Some(reason.to_string());
+            true
+        })
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        self.tx.borrow().is_some()
+    }
+
+    pub fn reason(&self) -> Option<String> {
+        self.tx.borrow().clone()
+    }
+
+    pub async fn cancelled(&self) {
+        let mut rx = self.tx.subscribe();
+        let _ = rx.wait_for(|reason| reason.is_some()).await;
+    }
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// WebUI reports a disconnect on every page reload or navigation as well as
+/// on close, so a disconnect alone only logs and never requests shutdown.
+pub fn handle_window_disconnected(shutdown: &Shutdown) {
+    if !shutdown.is_triggered() {
+        info!("WebUI window disconnected; waiting for it to reconnect or close");
+    }
+}
+
+/// Runs once `webui::wait` has returned and no window is shown any more.
+pub fn handle_window_closed(shutdown: &Shutdown) -> String {
+    if shutdown.trigger(REASON_WINDOW_CLOSED) {
+        info!("WebUI window closed; shutdown requested");
+    }
+    shutdown.reason().unwrap_or_else(|| REASON_WINDOW_CLOSED.to_string())
+}
+
+/// Triggers `shutdown` on SIGINT, or SIGTERM on Unix, then calls `on_signal`
+/// so the main loop can return and run the coordinator. The task ends once
+/// shutdown is triggered by any means, so it can be tracked.
+pub fn listen_for_signals<F>(shutdown: Shutdown, on_signal: F) -> JoinHandle<()>
+where
+    F: FnOnce() + Send + 'static,
+{
+    runtime::shared().spawn(async move {
+        tokio::select! {
+            name = wait_for_signal() => {
+                if shutdown.trigger(REASON_SIGNAL) {
+                    info!("Received {}; shutdown requested", name);
+                }
+                on_signal();
+            }
+            _ = shutdown.cancelled() => {}
+        }
+    })
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() -> &'static str {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = match signal(SignalKind::terminate()) {
+        Ok(terminate) => terminate,
+        Err(e) => {
+            warn!("Cannot listen for SIGTERM: {}", e);
+            let _ = tokio::signal::ctrl_c().await;
+            return "SIGINT";
+        }
+    };
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => "SIGINT",
+        _ = terminate.recv() => "SIGTERM",
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() -> &'static str {
+    let _ = tokio::signal::ctrl_c().await;
+    "Ctrl-C"
+}
+
+type ShutdownHook = Box<dyn FnOnce() + Send>;
+
+/// Which tracked tasks stopped on their own and which were aborted.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DrainReport {
+    pub drained: Vec<String>,
+    pub aborted: Vec<String>,
+}
+
+/// Runs the shutdown steps exactly once: triggers the signal, publishes
+/// `ApplicationShutdownEvent`, waits up to the grace period for tracked tasks,
+/// then runs hooks in reverse registration order.
+pub struct ShutdownCoordinator {
+    app_name: String,
+    signal: Shutdown,
+    event_bus: Arc<dyn EventBus>,
+    hooks: Mutex<Vec<(String, ShutdownHook)>>,
+    tasks: Mutex<Vec<(String, JoinHandle<()>)>>,
+    grace_period: Duration,
+    finished: AtomicBool,
+}
+
+impl ShutdownCoordinator {
+    pub fn new(app_name: &str, signal: Shutdown, event_bus: Arc<dyn EventBus>) -> Self {
+        Self {
+            app_name: app_name.to_string(),
+            signal,
+            event_bus,
+            hooks: Mutex::new(Vec::new()),
+            tasks: Mutex::new(Vec::new()),
+            grace_period: DEFAULT_GRACE_PERIOD,
+            finished: AtomicBool::new(false),
+        }
+    }
+
+    pub fn with_grace_period(mut self, grace_period: Duration) -> Self {
+        self.grace_period = grace_period;
+        self
+    }
+
+    /// Registers a background task that should stop once the signal fires.
+    pub fn track(&self, name: &str, task: JoinHandle<()>) {
+        self.tasks.lock().unwrap().push((name.to_string(), task));
+    }
+
+    /// Waits for tracked tasks until the grace period runs out, then aborts
+    /// whatever is still running.
+    pub fn drain_tasks(&self) -> DrainReport {
+        let tasks: Vec<_> = self.tasks.lock().unwrap().drain(..).collect();
+        let deadline = Instant::now() + self.grace_period;
+        let mut report = DrainReport::default();
+
+        for (name, mut task) in tasks {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let finished = runtime::shared()
+                .block_on(async { tokio::time::timeout(remaining, &mut task).await.is_ok() });
+            if finished {
+                report.drained.push(name);
+            } else {
+                task.abort();
+                warn!(
+                    "Task '{}' still running after {:?} grace period; aborted",
+                    name, self.grace_period
+                );
+                report.aborted.push(name);
+            }
+        }
+        report
+    }
+
+    pub fn signal(&self) -> Shutdown {
+        self.signal.clone()
+    }
+
+    pub fn on_shutdown<F>(&self, name: &str, hook: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.hooks
+            .lock()
+            .unwrap()
+            .push((name.to_string(), Box::new(hook)));
+    }
+
+    /// Runs shutdown with `reason` unless the signal already carries one.
+    /// Returns the reason that was used, or `None` if shutdown already ran.
+    pub fn run(&self, reason: &str) -> Option<String> {
+        if self.finished.swap(true, Ordering::SeqCst) {
+            return None;
+        }
+
+        self.signal.trigger(reason);
+        let reason = self.signal.reason().unwrap_or_else(|| reason.to_string());
+        info!("Shutting down (reason: {})", reason);
+
+        let event = ApplicationShutdownEvent::new(self.app_name.clone(), Some(reason.clone()));
+        if let Err(e) = self.event_bus.publish(Box::new(event)) {
+            warn!("Failed to publish shutdown event: {}", e);
+        }
+
+        let report = self.drain_tasks();
+        if !report.aborted.is_empty() {
+            warn!("Forcibly terminated: {}", report.aborted.join(", "));
+        }
+
+        let hooks: Vec<_> = self.hooks.lock().unwrap().drain(..).collect();
+        for (name, hook) in hooks.into_iter().rev() {
+            info!("Running shutdown hook: {}", name);
+            hook();
+        }
+
+        Some(reason)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    use crate::core::event_bus::{EventBusConfig, InMemoryEventBus};
+    use crate::core::logging::StructuredLogger;
+    use crate::core::ports::logger::LogLevel;
+
+    /// Sets its flag when dropped, which is how an aborted task is observed.
+    struct DropFlag(Arc<AtomicBool>);
+
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    fn bus() -> Arc<InMemoryEventBus> {
+        Arc::new(InMemoryEventBus::new(
+            EventBusConfig::new().with_logging(false),
+            StructuredLogger::new(LogLevel::Error, "shutdown_test"),
+        ))
+    }
+
+    /// A coordinator tracking a task that stops on the signal and one that
+    /// ignores it; the flags report that each has finished or been dropped.
+    fn coordinator_with_tasks(
+        grace: Duration,
+    ) -> (ShutdownCoordinator, Arc<AtomicBool>, Arc<AtomicBool>) {
+        let coordinator =
+            ShutdownCoordinator::new("test", Shutdown::new(), bus()).with_grace_period(grace);
+        let (quick_done, stuck_dropped) = (
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(AtomicBool::new(false)),
+        );
+
+        let (signal, done) = (coordinator.signal(), quick_done.clone());
+        coordinator.track(
+            "quick",
+            runtime::shared().spawn(async move {
+                signal.cancelled().await;
+                done.store(true, Ordering::SeqCst);
+            }),
+        );
+        let flag = DropFlag(stuck_dropped.clone());
+        coordinator.track(
+            "stuck",
+            runtime::shared().spawn(async move {
+                let _flag = flag;
+                tokio::time::sleep(Duration::from_secs(60)).await;
+            }),
+        );
+        (coordinator, quick_done, stuck_dropped)
+    }
+
+    #[test]
+    fn closing_the_window_triggers_shutdown_once() {
+        let shutdown = Shutdown::new();
+        assert!(!shutdown.is_triggered());
+
+        assert_eq!(handle_window_closed(&shutdown), REASON_WINDOW_CLOSED);
+        assert!(shutdown.is_triggered());
+        assert_eq!(shutdown.reason().as_deref(), Some(REASON_WINDOW_CLOSED));
+
+        let waiter = shutdown.clone();
+        runtime::shared()
+            .block_on(async {
+                tokio::time::timeout(Duration::from_secs(5), waiter.cancelled()).await
+            })
+            .expect("cancelled() resolves once triggered");
+    }
+
+    #[test]
+    fn a_disconnect_alone_does_not_shut_down() {
+        let shutdown = Shutdown::new();
+
+        handle_window_disconnected(&shutdown);
+        handle_window_disconnected(&shutdown);
+
+        assert!(!shutdown.is_triggered());
+        assert_eq!(shutdown.reason(), None);
+        assert_eq!(handle_window_closed(&shutdown), REASON_WINDOW_CLOSED);
+    }
+
+    #[test]
+    fn an_earlier_reason_wins_over_the_window_close() {
+        let shutdown = Shutdown::new();
+        assert!(shutdown.trigger(REASON_SIGNAL));
+
+        assert_eq!(handle_window_closed(&shutdown), REASON_SIGNAL);
+        assert!(!shutdown.trigger(REASON_EXIT));
+        assert_eq!(shutdown.reason().as_deref(), Some(REASON_SIGNAL));
+    }
+
+    #[test]
+    fn drain_reports_the_task_that_outlived_the_grace_period() {
+        let (coordinator, quick_done, stuck_dropped) =
+            coordinator_with_tasks(Duration::from_millis(200));
+        coordinator.signal().trigger(REASON_EXIT);
+
+        let report = coordinator.drain_tasks();
+
+        assert_eq!(report.drained, ["quick"]);
+        assert_eq!(report.aborted, ["stuck"]);
+        assert!(quick_done.load(Ordering::SeqCst));
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(stuck_dropped.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn run_drains_within_the_grace_period_then_runs_hooks_once() {
+        let (coordinator, quick_done, stuck_dropped) =
+            coordinator_with_tasks(Duration::from_millis(200));
+        let hooks = Arc::new(AtomicUsize::new(0));
+        let counter = hooks.clone();
+        coordinator.on_shutdown("count", move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let started = Instant::now();
+        assert_eq!(coordinator.run(REASON_EXIT).as_deref(), Some(REASON_EXIT));
+
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(quick_done.load(Ordering::SeqCst));
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(stuck_dropped.load(Ordering::SeqCst));
+        assert_eq!(hooks.load(Ordering::SeqCst), 1);
+
+        assert_eq!(coordinator.run(REASON_SIGNAL), None);
+        assert_eq!(hooks.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,60 @@
+// src/core/timezone/mod.rs
+// Display time zone for timestamps shown to users; storage always stays UTC
+
+use chrono::{DateTime, Local, Utc};
+use chrono_tz::Tz;
+use std::sync::RwLock;
+
+static DISPLAY_TZ: RwLock<Option<Tz>> = RwLock::new(None);
+
+pub fn parse_timezone(name: &str) -> Result<Tz, String> {
+    name.parse::<Tz>()
+        .map_err(|_| format!("Unknown IANA time zone: '{}'", name))
+}
+
+pub fn set_display_timezone(tz: Option<Tz>) {
+    *DISPLAY_TZ.write().unwrap() = tz;
+}
+
+pub fn display_timezone() -> Option<Tz> {
+    *DISPLAY_TZ.read().unwrap()
+}
+
+pub fn format_in(ts: DateTime<Utc>, tz: Tz) -> String {
+    ts.with_timezone(&tz).to_rfc3339()
+}
+
+/// RFC 3339 in the configured zone, or UTC when none is configured.
+pub fn format_for_display(ts: DateTime<Utc>) -> String {
+    match display_timezone() {
+        Some(tz) => format_in(ts, tz),
+        None => ts.to_rfc3339(),
+    }
+}
+
+/// Human log timestamp in the configured zone, or system local time when none is configured.
+pub fn format_log_timestamp(ts: DateTime<Utc>) -> String {
+    const FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f";
+    match display_timezone() {
+        Some(tz) => ts.with_timezone(&tz).format(FORMAT).to_string(),
+        None => ts.with_timezone(&Local).format(FORMAT).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_in_shows_each_zones_offset_for_the_same_instant() {
+        let ts = DateTime::parse_from_rfc3339("2024-01-15T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let new_york = format_in(ts, parse_timezone("America/New_York").unwrap());
+        let tokyo = format_in(ts, parse_timezone("Asia/Tokyo").unwrap());
+
+        assert_eq!(new_york, "2024-01-15T07:00:00-05:00");
+        assert_eq!(tokyo, "2024-01-15T21:00:00+09:00");
+    }
+}
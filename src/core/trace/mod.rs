@@ -0,0 +1,104 @@
+// src/core/trace/mod.rs
+// Per-execution span tree for seeing where a command spends its time
+
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub struct Span {
+    pub name: String,
+    pub duration: Duration,
+    pub children: Vec<Span>,
+}
+
+impl Span {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            duration: Duration::ZERO,
+            children: Vec::new(),
+        }
+    }
+
+    /// Depth-first search by name, including this span.
+    pub fn find(&self, name: &str) -> Option<&Span> {
+        if self.name == name {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(name))
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "name": self.name,
+            "duration_ms": self.duration.as_secs_f64() * 1000.0,
+            "children": self.children.iter().map(Span::to_json).collect::<Vec<_>>(),
+        })
+    }
+}
+
+thread_local! {
+    // Open spans, innermost last. Empty means no trace is being captured.
+    static OPEN: RefCell<Vec<(Span, Instant)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Closes its span on drop and attaches it to the enclosing one.
+pub struct SpanGuard(());
+
+impl Drop for SpanGuard {
+    fn drop(&mut self) {
+        OPEN.with(|open| {
+            let mut open = open.borrow_mut();
+            if open.len() < 2 {
+                return;
+            }
+            let (mut span, started) = open.pop().unwrap();
+            span.duration = started.elapsed();
+            open.last_mut().unwrap().0.children.push(span);
+        });
+    }
+}
+
+/// Opens a span if a trace is being captured on this thread; otherwise a
+/// no-op. Hold the guard for the duration of the work, including across
+/// `.await` when the future is driven with `block_on` on this thread.
+pub fn enter(name: &str) -> Option<SpanGuard> {
+    OPEN.with(|open| {
+        let mut open = open.borrow_mut();
+        if open.is_empty() {
+            return None;
+        }
+        open.push((Span::new(name), Instant::now()));
+        Some(SpanGuard(()))
+    })
+}
+
+pub fn span<T>(name: &str, work: impl FnOnce() -> T) -> T {
+    let _guard = enter(name);
+    work()
+}
+
+/// Runs `work` under a root span and returns the completed tree.
+pub fn capture<T>(name: &str, work: impl FnOnce() -> T) -> (T, Span) {
+    let depth = OPEN.with(|open| {
+        let mut open = open.borrow_mut();
+        open.push((Span::new(name), Instant::now()));
+        open.len()
+    });
+
+    let output = work();
+
+    let root = OPEN.with(|open| {
+        let mut open = open.borrow_mut();
+        // Spans left open by a guard that escaped `work` are closed here.
+        while open.len() > depth {
+            let (mut span, started) = open.pop().unwrap();
+            span.duration = started.elapsed();
+            open.last_mut().unwrap().0.children.push(span);
+        }
+        let (mut root, started) = open.pop().unwrap();
+        root.duration = started.elapsed();
+        root
+    });
+    (output, root)
+}
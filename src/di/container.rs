@@ -1,10 +1,103 @@
 // di/container.rs
-use std::sync::Arc;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 
-pub struct Container;
+/// Services keyed by type. `T` may be a trait object, so
+/// `register::<dyn UserService>(service)` and `resolve::<dyn UserService>()`
+/// work alongside concrete types.
+pub struct Container {
+    services: RwLock<HashMap<TypeId, Box<dyn Any + Send + Sync>>>,
+}
 
 impl Container {
     pub fn new() -> Self {
-        Self
+        Self {
+            services: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Registers `service` as the instance of `T`, replacing any earlier one.
+    pub fn register<T>(&self, service: Arc<T>)
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.services
+            .write()
+            .unwrap()
+            .insert(TypeId::of::<T>(), Box::new(service));
+    }
+
+    pub fn resolve<T>(&self) -> Option<Arc<T>>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.services
+            .read()
+            .unwrap()
+            .get(&TypeId::of::<T>())
+            .and_then(|service| service.downcast_ref::<Arc<T>>())
+            .cloned()
+    }
+
+    pub fn contains<T>(&self) -> bool
+    where
+        T: ?Sized + 'static,
+    {
+        self.services.read().unwrap().contains_key(&TypeId::of::<T>())
+    }
+
+    pub fn len(&self) -> usize {
+        self.services.read().unwrap().len()
+    }
+}
+
+impl Default for Container {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Clock(u64);
+
+    trait Greeter: Send + Sync {
+        fn greet(&self) -> String;
+    }
+
+    struct English;
+
+    impl Greeter for English {
+        fn greet(&self) -> String {
+            "hello".to_string()
+        }
+    }
+
+    #[test]
+    fn resolves_each_registered_type() {
+        let container = Container::new();
+        let clock = Arc::new(Clock(42));
+        container.register(clock.clone());
+        container.register::<dyn Greeter>(Arc::new(English));
+
+        assert!(Arc::ptr_eq(&container.resolve::<Clock>().unwrap(), &clock));
+        assert_eq!(container.resolve::<dyn Greeter>().unwrap().greet(), "hello");
+        assert_eq!(container.len(), 2);
+    }
+
+    #[test]
+    fn unknown_types_resolve_to_none_and_registration_replaces() {
+        let container = Container::new();
+        assert!(container.resolve::<Clock>().is_none());
+        assert!(!container.contains::<dyn Greeter>());
+
+        container.register(Arc::new(Clock(1)));
+        container.register(Arc::new(Clock(2)));
+
+        assert_eq!(container.resolve::<Clock>().unwrap().0, 2);
+        assert_eq!(container.len(), 1);
     }
 }
@@ -2,65 +2,398 @@
 pub mod container;
 pub mod module;
 
-use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
 
-use crate::core::config::AppConfig;
+use self::container::Container;
+use crate::core::config::{AppConfig, NamedDatabaseSettings};
+use crate::core::domain::errors::DomainError;
 use crate::core::logging::StructuredLogger;
+use crate::core::domain::user::EmailDomainAllowlist;
+use crate::core::event_bus::{EventBusConfig, InMemoryEventBus};
 use crate::core::ports::logger::LogLevel;
 use crate::core::ports::repository::UserRepository;
+use crate::model::repositories::sqlite::audit_log::AuditLog;
+use crate::model::repositories::sqlite::connection::SqliteDatabase;
+use crate::model::repositories::sqlite::event_store::EventStore;
+use crate::model::repositories::sqlite::kv_store::KvStore;
+use crate::model::repositories::sqlite::migrations::run_dir_migrations;
+use crate::model::repositories::sqlite::user_projection::UserListProjection;
 use crate::model::repositories::sqlite::user_repository::SqliteUserRepository;
 use crate::model::services::user_service::{UserService, UserServiceImpl};
 
+pub const MAIN_DATABASE: &str = "main";
+
+static GLOBAL: OnceLock<Arc<ServiceProvider>> = OnceLock::new();
+
+/// A service constructed on first use. A mutex rather than a OnceLock so
+/// construction never runs twice and a failed attempt is retried on the
+/// next lookup.
+struct Lazy<T: ?Sized> {
+    slot: Mutex<Option<Arc<T>>>,
+}
+
+impl<T: ?Sized> Lazy<T> {
+    fn new() -> Self {
+        Self {
+            slot: Mutex::new(None),
+        }
+    }
+
+    fn is_initialized(&self) -> bool {
+        self.slot.lock().unwrap().is_some()
+    }
+
+    fn get_or_try_init<F>(&self, init: F) -> Result<Arc<T>, DomainError>
+    where
+        F: FnOnce() -> Result<Arc<T>, DomainError>,
+    {
+        let mut slot = self.slot.lock().unwrap();
+        if let Some(service) = slot.as_ref() {
+            return Ok(service.clone());
+        }
+        let service = init()?;
+        *slot = Some(service.clone());
+        Ok(service)
+    }
+}
+
+/// A `[[databases]]` entry, opened and migrated on first use so runs that
+/// never touch it pay nothing.
+struct NamedDatabase {
+    settings: NamedDatabaseSettings,
+    db: Lazy<SqliteDatabase>,
+}
+
+impl NamedDatabase {
+    fn new(settings: NamedDatabaseSettings) -> Self {
+        Self {
+            settings,
+            db: Lazy::new(),
+        }
+    }
+
+    fn get(&self) -> Result<Arc<SqliteDatabase>, DomainError> {
+        self.db.get_or_try_init(|| {
+            let db = SqliteDatabase::open(&self.settings.path, self.settings.read_pool_size())?;
+            if let Some(dir) = &self.settings.migrations_dir {
+                run_dir_migrations(&db.writer(), std::path::Path::new(dir))?;
+            }
+            Ok(Arc::new(db))
+        })
+    }
+}
+
+/// Application services, each built on its first lookup: the main database
+/// is not opened until something needs it. Built services are also
+/// registered in `container`.
 pub struct ServiceProvider {
     pub config: AppConfig,
-    pub user_repository: Arc<dyn UserRepository>,
-    pub user_service: Arc<dyn UserService>,
+    pub event_bus: Arc<InMemoryEventBus>,
     pub logger: Arc<StructuredLogger>,
+    /// Services constructed so far, resolvable by type, plus any registered later.
+    pub container: Container,
+    database: Lazy<SqliteDatabase>,
+    databases: HashMap<String, NamedDatabase>,
+    kv_store: Lazy<KvStore>,
+    audit_log: Lazy<AuditLog>,
+    event_store: Lazy<EventStore>,
+    user_repository: Lazy<dyn UserRepository>,
+    user_list_projection: Lazy<UserListProjection>,
+    user_service: Lazy<dyn UserService>,
 }
 
 impl ServiceProvider {
+    /// Validates the configuration and sets up the event bus; nothing here
+    /// touches a database.
     pub fn new(config: AppConfig) -> Result<Self, Box<dyn std::error::Error>> {
-        let conn = Arc::new(Mutex::new(rusqlite::Connection::open(&config.database.path)?));
-
-        let user_repository: Arc<dyn UserRepository> =
-            Arc::new(SqliteUserRepository::new(conn.clone()));
-
-        {
-            let repo = user_repository.clone();
-            let runtime = tokio::runtime::Runtime::new()?;
-            runtime.block_on(async {
-                let _ = repo.get_all().await;
-            });
+        let mut databases = HashMap::new();
+        for entry in &config.databases {
+            if entry.name == MAIN_DATABASE || databases.contains_key(&entry.name) {
+                return Err(format!("Duplicate database name '{}'", entry.name).into());
+            }
+            databases.insert(entry.name.clone(), NamedDatabase::new(entry.clone()));
         }
 
-        let sqlite_repo = SqliteUserRepository::new(conn);
-        sqlite_repo.init_schema()?;
-
         let log_level = LogLevel::from(config.logging.level.as_str());
-        let mut logger = StructuredLogger::new(log_level, "app");
-        logger.init(None)?;
+        // The process-wide logger is installed by `main`; this one only tags output.
+        let logger = Arc::new(StructuredLogger::new(log_level.clone(), "app"));
 
-        let event_bus = Arc::new(NoopEventBus);
+        let event_bus = Arc::new(InMemoryEventBus::new(
+            EventBusConfig::default(),
+            StructuredLogger::new(log_level, "event_bus"),
+        ));
 
-        let user_service: Arc<dyn UserService> =
-            Arc::new(UserServiceImpl::new(user_repository.clone(), event_bus));
+        let container = Container::new();
+        container.register(event_bus.clone());
+        container.register(logger.clone());
 
         Ok(Self {
             config,
-            user_repository,
-            user_service,
-            logger: Arc::new(logger),
+            event_bus,
+            logger,
+            container,
+            database: Lazy::new(),
+            databases,
+            kv_store: Lazy::new(),
+            audit_log: Lazy::new(),
+            event_store: Lazy::new(),
+            user_repository: Lazy::new(),
+            user_list_projection: Lazy::new(),
+            user_service: Lazy::new(),
+        })
+    }
+
+    /// Builds a service through `lazy` and registers it in the container.
+    fn resolve<T, F>(&self, lazy: &Lazy<T>, init: F) -> Result<Arc<T>, DomainError>
+    where
+        T: ?Sized + Send + Sync + 'static,
+        F: FnOnce() -> Result<Arc<T>, DomainError>,
+    {
+        lazy.get_or_try_init(|| {
+            let service = init()?;
+            self.container.register(service.clone());
+            Ok(service)
+        })
+    }
+
+    /// The `[database]` connection, opened on first use.
+    pub fn main_database(&self) -> Result<Arc<SqliteDatabase>, DomainError> {
+        self.resolve(&self.database, || {
+            let db = SqliteDatabase::open(
+                &self.config.database.path,
+                self.config.database.read_pool_size(),
+            )?;
+            Ok(Arc::new(db))
+        })
+    }
+
+    /// Whether the main database has been opened yet.
+    pub fn is_database_open(&self) -> bool {
+        self.database.is_initialized()
+    }
+
+    /// The user repository, with the users table migrated. Nothing may query
+    /// users before this has run.
+    pub fn user_repository(&self) -> Result<Arc<dyn UserRepository>, DomainError> {
+        self.resolve(&self.user_repository, || {
+            let repository = Arc::new(SqliteUserRepository::with_database(self.main_database()?));
+            repository.init_schema()?;
+            repository.apply_unique_by(self.config.users.unique_by)?;
+            Ok(repository as Arc<dyn UserRepository>)
+        })
+    }
+
+    /// The user list projection, rebuilt from `users` and subscribed to
+    /// user events.
+    pub fn user_list_projection(&self) -> Result<Arc<UserListProjection>, DomainError> {
+        self.resolve(&self.user_list_projection, || {
+            self.user_repository()?;
+            let projection = UserListProjection::new(self.main_database()?);
+            projection.init_schema()?;
+            projection.rebuild()?;
+            let projection = Arc::new(projection);
+            futures::executor::block_on(projection.clone().attach(&self.event_bus));
+            Ok(projection)
+        })
+    }
+
+    pub fn kv_store(&self) -> Result<Arc<KvStore>, DomainError> {
+        self.resolve(&self.kv_store, || {
+            let kv_store = KvStore::new(self.main_database()?);
+            kv_store.init_schema()?;
+            Ok(Arc::new(kv_store))
+        })
+    }
+
+    pub fn audit_log(&self) -> Result<Arc<AuditLog>, DomainError> {
+        self.resolve(&self.audit_log, || {
+            let audit_log = AuditLog::new(self.main_database()?);
+            audit_log.init_schema()?;
+            let audit_log = Arc::new(audit_log);
+            futures::executor::block_on(audit_log.clone().attach(&self.event_bus));
+            Ok(audit_log)
+        })
+    }
+
+    pub fn event_store(&self) -> Result<Arc<EventStore>, DomainError> {
+        self.resolve(&self.event_store, || {
+            let event_store = EventStore::new(self.main_database()?);
+            event_store.init_schema()?;
+            let event_store = Arc::new(event_store);
+            self.event_bus.set_journal(event_store.clone());
+            Ok(event_store)
+        })
+    }
+
+    /// The user service. Everything that follows user events is subscribed
+    /// first, so no event the service publishes goes unseen.
+    pub fn user_service(&self) -> Result<Arc<dyn UserService>, DomainError> {
+        self.resolve(&self.user_service, || {
+            self.user_list_projection()?;
+            self.audit_log()?;
+            self.event_store()?;
+            let service = UserServiceImpl::new(self.user_repository()?, self.event_bus.clone())
+                .with_email_allowlist(EmailDomainAllowlist::new(
+                    &self.config.users.allowed_email_domains,
+                ));
+            Ok(Arc::new(service) as Arc<dyn UserService>)
         })
     }
+
+    /// Makes `provider` the process-wide provider returned by `global`.
+    /// Only the first call succeeds; later ones get their provider back.
+    pub fn set_global(provider: Arc<Self>) -> Result<(), Arc<Self>> {
+        GLOBAL.set(provider)
+    }
+
+    /// The provider installed by `main`, for code that is not handed one,
+    /// or `None` before startup has built it.
+    pub fn global() -> Option<Arc<Self>> {
+        GLOBAL.get().cloned()
+    }
+
+    /// Looks up a database by its configured name; `"main"` is `[database]`.
+    /// Each database is opened, and a `[[databases]]` entry migrated, on its
+    /// first lookup.
+    pub fn database(&self, name: &str) -> Result<Option<Arc<SqliteDatabase>>, DomainError> {
+        if name == MAIN_DATABASE {
+            return self.main_database().map(Some);
+        }
+        self.databases.get(name).map(NamedDatabase::get).transpose()
+    }
 }
 
-struct NoopEventBus;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::{Path, PathBuf};
+
+    fn config_at(path: &Path) -> AppConfig {
+        let mut config = AppConfig::default();
+        config.database.path = path.to_string_lossy().into_owned();
+        config.logging.level = String::from("error");
+        config
+    }
+
+    fn temp_db_path() -> PathBuf {
+        std::env::temp_dir().join(format!("di-test-{}.db", uuid::Uuid::new_v4()))
+    }
+
+    fn remove_database(path: &Path) {
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    #[test]
+    fn database_is_not_opened_until_a_service_is_resolved() {
+        let path = temp_db_path();
+        let provider = ServiceProvider::new(config_at(&path)).unwrap();
+
+        assert!(!path.exists());
+        assert!(!provider.is_database_open());
+        assert!(!provider.container.contains::<dyn UserService>());
+
+        provider.user_service().unwrap();
+
+        assert!(path.exists());
+        assert!(provider.is_database_open());
+        assert!(provider.container.contains::<dyn UserService>());
+        assert!(provider.container.contains::<dyn UserRepository>());
+
+        remove_database(&path);
+    }
+
+    #[test]
+    fn services_are_built_once() {
+        let path = temp_db_path();
+        let provider = ServiceProvider::new(config_at(&path)).unwrap();
+
+        let first = provider.user_repository().unwrap();
+        let second = provider.user_repository().unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        remove_database(&path);
+    }
+
+    #[test]
+    fn named_databases_are_isolated_from_each_other() {
+        let migrations = std::env::temp_dir().join(format!("di-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&migrations).unwrap();
+        std::fs::write(
+            migrations.join("001_notes.sql"),
+            "CREATE TABLE notes (body TEXT NOT NULL);",
+        )
+        .unwrap();
+
+        let path = temp_db_path();
+        let mut config = config_at(&path);
+        let paths = [("analytics", temp_db_path()), ("archive", temp_db_path())];
+        for (name, db_path) in &paths {
+            config.databases.push(NamedDatabaseSettings {
+                name: name.to_string(),
+                path: db_path.to_string_lossy().into_owned(),
+                read_pool_size: Some(1),
+                migrations_dir: Some(migrations.to_string_lossy().into_owned()),
+            });
+        }
+        let provider = ServiceProvider::new(config).unwrap();
+
+        for (name, _) in &paths {
+            let db = provider.database(name).unwrap().unwrap();
+            db.writer()
+                .execute("INSERT INTO notes (body) VALUES (?1)", [name])
+                .unwrap();
+        }
+
+        for (name, _) in &paths {
+            let db = provider.database(name).unwrap().unwrap();
+            let bodies: Vec<String> = db
+                .writer()
+                .prepare("SELECT body FROM notes")
+                .unwrap()
+                .query_map([], |row| row.get(0))
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(bodies, vec![name.to_string()]);
+        }
+        assert!(provider.database("missing").unwrap().is_none());
+        assert!(!provider.is_database_open());
+
+        for (_, db_path) in &paths {
+            remove_database(db_path);
+        }
+        let _ = std::fs::remove_dir_all(&migrations);
+    }
+
+    #[test]
+    fn global_provider_shares_one_repository_across_code_paths() {
+        let path = temp_db_path();
+        let provider = Arc::new(ServiceProvider::new(config_at(&path)).unwrap());
+        assert!(ServiceProvider::set_global(provider.clone()).is_ok());
+        assert!(ServiceProvider::set_global(provider.clone()).is_err());
+
+        let from_handler = || ServiceProvider::global().unwrap().user_repository().unwrap();
+        let from_thread = std::thread::spawn(from_handler).join().unwrap();
+
+        assert!(Arc::ptr_eq(&ServiceProvider::global().unwrap(), &provider));
+        assert!(Arc::ptr_eq(&from_handler(), &from_thread));
+
+        remove_database(&path);
+    }
+
+    #[test]
+    fn fresh_empty_database_file_gets_its_schema_on_first_use() {
+        let path = temp_db_path();
+        std::fs::write(&path, b"").unwrap();
+        let provider = ServiceProvider::new(config_at(&path)).unwrap();
+
+        let service = provider.user_service().unwrap();
+        assert!(futures::executor::block_on(service.get_all_users()).unwrap().is_empty());
+        assert!(provider.main_database().unwrap().has_table("users").unwrap());
 
-impl crate::core::ports::event_bus::EventBus for NoopEventBus {
-    fn publish(
-        &self,
-        _event: Box<dyn crate::model::entities::events::DomainEvent>,
-    ) -> Result<(), crate::model::entities::errors::DomainError> {
-        Ok(())
+        remove_database(&path);
     }
 }
@@ -4,3 +4,87 @@
 pub mod user;
 
 pub use user::UserPlugin;
+
+use std::sync::Arc;
+use log::info;
+use webui_rs::webui;
+
+use crate::core::config::{AppConfig, FeatureSettings};
+use crate::core::domain::errors::DomainError;
+use crate::core::mvvm::viewmodel::SharedViewModel;
+use crate::di::ServiceProvider;
+use crate::plugins::Plugin;
+
+/// A feature that can be switched on or off as a unit at startup.
+pub trait FeatureModule: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Plugin registered with the plugin registry.
+    fn plugin(&self) -> Arc<dyn Plugin>;
+
+    /// Viewmodels whose commands are routed by the dispatcher.
+    fn viewmodels(&self) -> Vec<SharedViewModel>;
+
+    /// Binds the feature's WebUI handlers, resolving the services they use.
+    fn register(
+        &self,
+        window: &mut webui::Window,
+        provider: &Arc<ServiceProvider>,
+    ) -> Result<(), DomainError>;
+}
+
+/// Every feature module the application knows about.
+pub fn all_modules() -> Vec<Arc<dyn FeatureModule>> {
+    vec![Arc::new(user::feature::UserFeature::new())]
+}
+
+/// The modules enabled by `[features.modules]`; unlisted modules stay enabled.
+pub fn enabled_modules(config: &AppConfig) -> Vec<Arc<dyn FeatureModule>> {
+    all_modules()
+        .into_iter()
+        .filter(|module| {
+            let enabled = config.features.is_enabled(module.name());
+            if !enabled {
+                info!("Feature module disabled: {}", module.name());
+            }
+            enabled
+        })
+        .collect()
+}
+
+impl FeatureSettings {
+    pub fn is_enabled(&self, module: &str) -> bool {
+        self.modules.get(module).copied().unwrap_or(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::viewmodel::dispatcher::CommandDispatcher;
+
+    /// Registers the enabled modules' viewmodels the way `main` does.
+    fn dispatcher_for(config: &AppConfig) -> CommandDispatcher {
+        let dispatcher = CommandDispatcher::new();
+        for module in enabled_modules(config) {
+            for viewmodel in module.viewmodels() {
+                dispatcher.register_viewmodel(viewmodel);
+            }
+        }
+        dispatcher
+    }
+
+    #[test]
+    fn disabled_module_registers_none_of_its_commands() {
+        let mut config = AppConfig::default();
+        assert!(dispatcher_for(&config).commands().contains(&String::from("create_user")));
+
+        config.features.modules.insert(String::from("user"), false);
+
+        assert!(enabled_modules(&config).is_empty());
+        let commands = dispatcher_for(&config).commands();
+        for command in ["create_user", "update_user", "delete_user", "activate_user", "deactivate_user"] {
+            assert!(!commands.contains(&command.to_string()), "{} is registered", command);
+        }
+    }
+}
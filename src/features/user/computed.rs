@@ -0,0 +1,70 @@
+// src/features/user/computed.rs
+// Derived user fields attached on request via a query's `include` list
+
+use chrono::{DateTime, Utc};
+use log::warn;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Source of "now" for computed fields; swapped out to pin time.
+pub type Clock = Arc<dyn Fn() -> DateTime<Utc> + Send + Sync>;
+
+pub fn system_clock() -> Clock {
+    Arc::new(Utc::now)
+}
+
+pub const COMPUTED_FIELDS: &[&str] = &["initials", "account_age_days"];
+
+/// First letter of up to the first two words of the name, uppercased.
+pub fn initials(name: &str) -> String {
+    name.split_whitespace()
+        .filter_map(|word| word.chars().next())
+        .take(2)
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+/// Whole days since creation; never negative.
+pub fn account_age_days(created_at: DateTime<Utc>, now: DateTime<Utc>) -> i64 {
+    (now - created_at).num_days().max(0)
+}
+
+/// Reads `include` from query params, given as `include=a,b`.
+pub fn parse_include(params: &[String]) -> Vec<String> {
+    params
+        .iter()
+        .filter_map(|p| p.strip_prefix("include="))
+        .flat_map(|list| list.split(','))
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Adds the requested computed fields to a serialized user. Unknown names
+/// are skipped with a warning.
+pub fn attach(
+    user: &mut Value,
+    include: &[String],
+    name: &str,
+    created_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) {
+    let Some(fields) = user.as_object_mut() else {
+        return;
+    };
+    for field in include {
+        match field.as_str() {
+            "initials" => {
+                fields.insert(field.clone(), Value::from(initials(name)));
+            }
+            "account_age_days" => {
+                fields.insert(field.clone(), Value::from(account_age_days(created_at, now)));
+            }
+            other => warn!(
+                "Ignoring unknown computed user field '{}' (known: {})",
+                other,
+                COMPUTED_FIELDS.join(", ")
+            ),
+        }
+    }
+}
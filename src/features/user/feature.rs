@@ -0,0 +1,82 @@
+// src/features/user/feature.rs
+// Startup entry point for the user feature
+
+use std::sync::Arc;
+use webui_rs::webui;
+
+use crate::core::domain::errors::DomainError;
+use crate::core::mvvm::viewmodel::{SharedViewModel, ViewModel};
+use crate::di::ServiceProvider;
+use crate::features::FeatureModule;
+use crate::plugins::Plugin;
+use crate::viewmodel::bindings::response;
+use crate::viewmodel::dispatcher::CommandDispatcher;
+use crate::viewmodel::bindings::user_handlers::{
+    setup_user_avatar_handlers, setup_user_handlers, setup_user_import_handlers,
+};
+
+use super::plugin::UserPlugin;
+
+pub struct UserFeature {
+    plugin: Arc<UserPlugin>,
+}
+
+impl UserFeature {
+    /// Configuration is applied when the plugin is registered; see
+    /// `UserPlugin::initialize_with`.
+    pub fn new() -> Self {
+        Self {
+            plugin: Arc::new(UserPlugin::new()),
+        }
+    }
+}
+
+impl Default for UserFeature {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FeatureModule for UserFeature {
+    fn name(&self) -> &'static str {
+        "user"
+    }
+
+    fn plugin(&self) -> Arc<dyn Plugin> {
+        self.plugin.clone()
+    }
+
+    fn viewmodels(&self) -> Vec<SharedViewModel> {
+        vec![self.plugin.viewmodel() as SharedViewModel]
+    }
+
+    fn register(
+        &self,
+        window: &mut webui::Window,
+        provider: &Arc<ServiceProvider>,
+    ) -> Result<(), DomainError> {
+        let user_service = provider.user_service()?;
+        let user_repository = provider.user_repository()?;
+
+        // The dispatcher carries the configured concurrency limits; a default
+        // one would silently lift them.
+        let dispatcher = provider.container.resolve::<CommandDispatcher>().ok_or_else(|| {
+            DomainError::InvalidOperation("CommandDispatcher is not registered".to_string())
+        })?;
+        setup_user_handlers(window, provider.user_list_projection()?, user_service.clone(), dispatcher.clone());
+        setup_user_import_handlers(window, user_service.clone(), dispatcher);
+        setup_user_avatar_handlers(window, user_repository);
+
+        let viewmodel = self.plugin.viewmodel();
+        viewmodel.set_service(Some(user_service));
+
+        let window_id = window.id;
+        let name = viewmodel.name().to_string();
+        viewmodel.subscribe(move |state| {
+            let mut detail = state.to_json();
+            detail["viewmodel"] = serde_json::Value::String(name.clone());
+            response::dispatch(window_id, "state_change", detail);
+        });
+        Ok(())
+    }
+}
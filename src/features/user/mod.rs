@@ -1,10 +1,17 @@
 // src/features/user/mod.rs
 // User feature module
 
+pub mod computed;
+pub mod feature;
 pub mod model;
 pub mod viewmodel;
 pub mod plugin;
+pub mod redaction;
+pub mod replay;
+#[cfg(test)]
+pub mod testing;
 
 pub use model::{User, UserId, UserRole, UserStatus, NewUser, Email};
 pub use viewmodel::UserViewModel;
+pub use feature::UserFeature;
 pub use plugin::UserPlugin;
@@ -3,6 +3,7 @@
 
 use crate::core::mvvm::{Entity, ValueObject};
 use chrono::{DateTime, Utc};
+pub use crate::core::domain::user::{validate_email, EmailError};
 use std::sync::Arc;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -22,6 +23,18 @@ pub enum UserRole {
     Guest,
 }
 
+impl UserRole {
+    /// Case-insensitive; unrecognised roles fall back to the least privileged one.
+    pub fn parse(role: &str) -> Self {
+        match role.to_ascii_lowercase().as_str() {
+            "admin" => UserRole::Admin,
+            "editor" => UserRole::Editor,
+            "user" => UserRole::User,
+            _ => UserRole::Guest,
+        }
+    }
+}
+
 impl std::fmt::Display for UserRole {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -41,6 +54,17 @@ pub enum UserStatus {
     Suspended,
 }
 
+impl UserStatus {
+    pub fn parse(status: &str) -> Self {
+        match status.to_ascii_lowercase().as_str() {
+            "inactive" => UserStatus::Inactive,
+            "pending" => UserStatus::Pending,
+            "suspended" => UserStatus::Suspended,
+            _ => UserStatus::Active,
+        }
+    }
+}
+
 impl std::fmt::Display for UserStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -57,11 +81,8 @@ pub struct Email(String);
 
 impl Email {
     pub fn new(s: &str) -> Result<Self, EmailError> {
-        if s.contains('@') && s.contains('.') {
-            Ok(Email(s.to_string()))
-        } else {
-            Err(EmailError::InvalidFormat(s.to_string()))
-        }
+        validate_email(s)?;
+        Ok(Email(s.to_string()))
     }
 
     pub fn as_str(&self) -> &str {
@@ -69,23 +90,10 @@ impl Email {
     }
 }
 
-#[derive(Debug)]
-pub enum EmailError {
-    InvalidFormat(String),
-}
-
-impl std::fmt::Display for EmailError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            EmailError::InvalidFormat(s) => write!(f, "Invalid email format: {}", s),
-        }
-    }
-}
-
 impl ValueObject for Email {
     type ValidationError = EmailError;
     fn validate(&self) -> Result<(), Self::ValidationError> {
-        Email::new(self.0.as_str()).map(|_| ()).map_err(|e| e)
+        validate_email(&self.0)
     }
 }
 
@@ -147,3 +155,29 @@ impl User {
         self.updated_at = Utc::now();
     }
 }
+
+/// Lifts a persisted user into the viewmodel model, keeping both timestamps.
+impl From<crate::core::domain::user::User> for User {
+    fn from(user: crate::core::domain::user::User) -> Self {
+        use crate::core::domain::user::{UserRole as StoredRole, UserStatus as StoredStatus};
+
+        User {
+            id: UserId(user.id.0),
+            name: user.name,
+            email: Email(user.email.0),
+            role: match user.role {
+                StoredRole::Admin => UserRole::Admin,
+                StoredRole::Editor => UserRole::Editor,
+                StoredRole::User => UserRole::User,
+                StoredRole::Guest => UserRole::Guest,
+            },
+            status: match user.status {
+                StoredStatus::Active => UserStatus::Active,
+                StoredStatus::Inactive => UserStatus::Inactive,
+                StoredStatus::Suspended => UserStatus::Suspended,
+            },
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+        }
+    }
+}
@@ -1,12 +1,17 @@
 // src/features/user/plugin.rs
 // User Plugin implementation
 
-use crate::core::mvvm::ViewModel;
-use crate::plugins::{Plugin, PluginError};
+use crate::core::domain::user::EmailDomainAllowlist;
+use crate::core::mvvm::{ViewModel, ViewModelState};
+use crate::plugins::{
+    CommandPlugin, Plugin, PluginContext, PluginError, PluginHealth, QueryPlugin,
+};
 use std::any::Any;
 use std::result::Result as StdResult;
 use std::sync::Arc;
 
+use super::model::UserRole;
+use super::redaction::FieldAccess;
 use super::viewmodel::{SharedUserViewModel, UserViewModel};
 
 pub struct UserPlugin {
@@ -44,6 +49,22 @@ impl Plugin for UserPlugin {
         Ok(())
     }
 
+    /// Applies the `[users]` and `[field_access]` settings to the viewmodel.
+    fn initialize_with(&self, ctx: &PluginContext) -> StdResult<(), PluginError> {
+        let config = &ctx.config;
+        self.viewmodel
+            .set_email_allowlist(EmailDomainAllowlist::new(&config.users.allowed_email_domains));
+        self.viewmodel
+            .set_field_access(FieldAccess::new(&config.field_access));
+        self.viewmodel
+            .set_current_role(config.users.actor_role.as_deref().map(UserRole::parse));
+
+        if let Some(role) = &config.users.actor_role {
+            ctx.logger.debug(&format!("user plugin acting as role {}", role));
+        }
+        Ok(())
+    }
+
     fn shutdown(&self) -> StdResult<(), PluginError> {
         Ok(())
     }
@@ -51,4 +72,38 @@ impl Plugin for UserPlugin {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    /// Degraded while the viewmodel is in its error state.
+    fn health(&self) -> PluginHealth {
+        match self.viewmodel.state() {
+            ViewModelState::Error(msg) => PluginHealth::Degraded(msg),
+            _ => PluginHealth::Ok,
+        }
+    }
+
+    fn as_command(&self) -> Option<&dyn CommandPlugin> {
+        Some(self)
+    }
+
+    fn as_query(&self) -> Option<&dyn QueryPlugin> {
+        Some(self)
+    }
+}
+
+/// Commands take their JSON payload as the first argument.
+impl CommandPlugin for UserPlugin {
+    fn execute(&self, command: &str, args: &[String]) -> StdResult<String, PluginError> {
+        let payload = args.first().map(String::as_str).unwrap_or("{}");
+        self.viewmodel
+            .handle_command(command, payload)
+            .map_err(|e| PluginError::ExecutionFailed(e.to_string()))
+    }
+}
+
+impl QueryPlugin for UserPlugin {
+    fn query(&self, query: &str, params: &[String]) -> StdResult<String, PluginError> {
+        self.viewmodel
+            .handle_query(query, params)
+            .map_err(|e| PluginError::ExecutionFailed(e.to_string()))
+    }
 }
@@ -0,0 +1,83 @@
+// src/features/user/redaction.rs
+// Role-aware field visibility for serialized users
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+use super::model::UserRole;
+
+/// Fields hidden from a role are masked if they have a masked form
+/// (`email`) and removed otherwise.
+#[derive(Debug, Clone)]
+pub struct FieldAccess {
+    visible: HashMap<String, Vec<String>>,
+}
+
+impl FieldAccess {
+    /// Built-in defaults with `overrides` (role -> visible fields) applied on top.
+    pub fn new(overrides: &HashMap<String, Vec<String>>) -> Self {
+        let restricted: Vec<String> = ["id", "name", "role", "status", "created_at", "external_id"]
+            .iter()
+            .map(|f| f.to_string())
+            .collect();
+
+        let mut visible = HashMap::from([
+            ("admin".to_string(), vec!["*".to_string()]),
+            ("editor".to_string(), restricted.clone()),
+            ("user".to_string(), restricted.clone()),
+            ("guest".to_string(), restricted),
+        ]);
+        for (role, fields) in overrides {
+            visible.insert(role.to_lowercase(), fields.clone());
+        }
+        Self { visible }
+    }
+
+    pub fn can_see(&self, role: &UserRole, field: &str) -> bool {
+        self.visible
+            .get(&role.to_string().to_lowercase())
+            .is_some_and(|fields| fields.iter().any(|f| f == "*" || f == field))
+    }
+
+    pub fn redact(&self, role: &UserRole, user: &mut Value) {
+        let Some(fields) = user.as_object_mut() else {
+            return;
+        };
+        let hidden: Vec<String> = fields
+            .keys()
+            .filter(|field| !self.can_see(role, field))
+            .cloned()
+            .collect();
+        for field in hidden {
+            let masked = match (field.as_str(), fields.get(&field)) {
+                ("email", Some(Value::String(email))) => Some(mask_email(email)),
+                _ => None,
+            };
+            match masked {
+                Some(masked) => {
+                    fields.insert(field, Value::from(masked));
+                }
+                None => {
+                    fields.remove(&field);
+                }
+            }
+        }
+    }
+}
+
+impl Default for FieldAccess {
+    fn default() -> Self {
+        Self::new(&HashMap::new())
+    }
+}
+
+/// `jane@example.com` -> `j***@example.com`.
+pub fn mask_email(email: &str) -> String {
+    match email.split_once('@') {
+        Some((local, domain)) => {
+            let first: String = local.chars().take(1).collect();
+            format!("{}***@{}", first, domain)
+        }
+        None => "***".to_string(),
+    }
+}
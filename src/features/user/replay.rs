@@ -0,0 +1,134 @@
+// src/features/user/replay.rs
+// Rebuilds UserViewModel state from a recorded event sequence
+
+use crate::core::domain::events::{decode_event, DomainEvent};
+use crate::core::mvvm::ViewModelError;
+use chrono::Utc;
+use std::result::Result as StdResult;
+
+use super::model::{Email, NewUser, User, UserId, UserRole, UserStatus};
+use super::viewmodel::UserViewModel;
+
+/// Applies each event's effect to the viewmodel in order. Events that don't
+/// concern users are skipped. Returns how many events changed state.
+pub fn replay_events(
+    vm: &UserViewModel,
+    events: &[Box<dyn DomainEvent>],
+) -> StdResult<usize, ViewModelError> {
+    let mut applied = 0;
+
+    for event in events {
+        let payload = event.payload();
+        let id = || {
+            payload["user_id"]
+                .as_i64()
+                .map(UserId)
+                .ok_or_else(|| ViewModelError::validation(format!(
+                    "{} event without user_id",
+                    event.event_type()
+                )))
+        };
+
+        match event.event_type() {
+            "user.created" => {
+                let email = Email::new(payload["email"].as_str().unwrap_or_default())
+                    .map_err(|e| ViewModelError::validation(e.to_string()))?;
+                let new_user = NewUser {
+                    name: payload["name"].as_str().unwrap_or_default().to_string(),
+                    email,
+                    role: UserRole::User,
+                };
+                vm.add_user(User::new(id()?, new_user));
+                applied += 1;
+            }
+            "user.updated" => {
+                let Some(mut user) = vm.find_by_id(&id()?) else {
+                    continue;
+                };
+                if let Some(name) = payload["name"].as_str() {
+                    user.name = name.to_string();
+                }
+                if let Some(email) = payload["email"].as_str() {
+                    user.email =
+                        Email::new(email).map_err(|e| ViewModelError::validation(e.to_string()))?;
+                }
+                if let Some(role) = payload["role"].as_str() {
+                    user.role = UserRole::parse(role);
+                }
+                if let Some(status) = payload["status"].as_str() {
+                    user.status = UserStatus::parse(status);
+                }
+                user.updated_at = Utc::now();
+                vm.replace_user(user);
+                applied += 1;
+            }
+            "user.deleted" if vm.remove_user(&id()?) => applied += 1,
+            _ => {}
+        }
+    }
+
+    Ok(applied)
+}
+
+/// Decodes stored `(event_type, payload)` records through the event registry
+/// and replays them.
+pub fn replay_stored(
+    vm: &UserViewModel,
+    records: &[(String, serde_json::Value)],
+) -> StdResult<usize, ViewModelError> {
+    let events = records
+        .iter()
+        .map(|(event_type, payload)| decode_event(event_type, payload))
+        .collect::<StdResult<Vec<_>, _>>()
+        .map_err(|e| ViewModelError::validation(e.to_string()))?;
+
+    replay_events(vm, &events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::domain::events::{UserCreatedEvent, UserDeletedEvent};
+    use crate::core::domain::user as stored;
+
+    fn created(id: i64) -> Box<dyn DomainEvent> {
+        Box::new(UserCreatedEvent::new(
+            stored::UserId(id),
+            "Ada".to_string(),
+            "ada@example.com".to_string(),
+        ))
+    }
+
+    #[test]
+    fn create_then_delete_replays_to_an_empty_set() {
+        let vm = UserViewModel::new();
+        let deleted: Box<dyn DomainEvent> = Box::new(UserDeletedEvent::new(stored::UserId(1)));
+        let events = vec![created(1), deleted];
+
+        assert_eq!(replay_events(&vm, &events).unwrap(), 2);
+        assert!(vm.get_users().is_empty());
+    }
+
+    #[test]
+    fn create_only_replays_to_one_user() {
+        let vm = UserViewModel::new();
+
+        assert_eq!(replay_events(&vm, &[created(7)]).unwrap(), 1);
+        let users = vm.get_users();
+        assert_eq!(users.len(), 1);
+        assert_eq!(users[0].id, UserId(7));
+        assert_eq!(users[0].email.as_str(), "ada@example.com");
+    }
+
+    #[test]
+    fn stored_records_decode_through_the_registry() {
+        let vm = UserViewModel::new();
+        let records = vec![
+            ("user.created".to_string(), created(3).payload()),
+            ("user.updated".to_string(), serde_json::json!({ "user_id": 3, "name": "Grace" })),
+        ];
+
+        assert_eq!(replay_stored(&vm, &records).unwrap(), 2);
+        assert_eq!(vm.find_by_id(&UserId(3)).unwrap().name, "Grace");
+    }
+}
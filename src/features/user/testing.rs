@@ -0,0 +1,68 @@
+// src/features/user/testing.rs
+// Persisted user stack shared by tests
+
+use std::sync::{Arc, Mutex};
+
+use crate::core::domain::user::UniqueBy;
+use crate::core::ports::logger::LogLevel;
+use crate::core::services::user_service::{UserService, UserServiceImpl};
+use crate::infrastructure::event_bus::{EventBusConfig, InMemoryEventBus};
+use crate::infrastructure::logging::StructuredLogger;
+use crate::model::repositories::sqlite::connection::SqliteDatabase;
+use crate::model::repositories::sqlite::user_projection::UserListProjection;
+use crate::model::repositories::sqlite::user_repository::SqliteUserRepository;
+
+use super::model::UserRole;
+use super::viewmodel::UserViewModel;
+
+pub struct Persisted {
+    pub db: Arc<SqliteDatabase>,
+    pub bus: Arc<InMemoryEventBus>,
+    pub service: Arc<dyn UserService>,
+    pub projection: Arc<UserListProjection>,
+    /// Every `user.*` event type published, in order.
+    pub events: Arc<Mutex<Vec<String>>>,
+}
+
+impl Persisted {
+    /// A viewmodel backed by the service, acting as an admin.
+    pub fn admin_viewmodel(&self) -> UserViewModel {
+        let vm = UserViewModel::new();
+        vm.set_service(Some(self.service.clone()));
+        vm.set_current_role(Some(UserRole::Admin));
+        vm
+    }
+}
+
+/// A user service over an in-memory database, with the projection attached.
+pub fn persisted() -> Persisted {
+    persisted_on(Arc::new(SqliteDatabase::open(":memory:", 0).unwrap()))
+}
+
+/// Like `persisted`, over a database the caller opened.
+pub fn persisted_on(db: Arc<SqliteDatabase>) -> Persisted {
+    let repository = Arc::new(SqliteUserRepository::with_database(db.clone()));
+    repository.init_schema().unwrap();
+    repository.apply_unique_by(UniqueBy::Email).unwrap();
+    let projection = Arc::new(UserListProjection::new(db.clone()));
+    projection.init_schema().unwrap();
+
+    let bus = Arc::new(InMemoryEventBus::new(
+        EventBusConfig::new().with_logging(false),
+        StructuredLogger::new(LogLevel::Error, "user_test"),
+    ));
+    futures::executor::block_on(projection.clone().attach(&bus));
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let seen = events.clone();
+    futures::executor::block_on(bus.subscribe("user.*", move |event_type, _| {
+        seen.lock().unwrap().push(event_type);
+    }));
+
+    Persisted {
+        db,
+        service: Arc::new(UserServiceImpl::new(repository, bus.clone())),
+        bus,
+        projection,
+        events,
+    }
+}
@@ -1,31 +1,262 @@
 // src/features/user/viewmodel.rs
 // User ViewModel
 
+use crate::core::domain::errors::DomainError;
+use crate::core::domain::user as stored;
+use crate::core::domain::user::EmailDomainAllowlist;
+use crate::core::mvvm::viewmodel::Checkpoint;
 use crate::core::mvvm::{ViewModel, ViewModelError, ViewModelState};
+use crate::core::services::user_service::{UserChanges, UserService};
+use crate::core::timezone::format_for_display;
+use crate::core::trace;
 use serde_json::{json, Value};
 use std::result::Result as StdResult;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
 
+use super::computed::{self, Clock};
+use super::redaction::FieldAccess;
 use super::model::{Email, NewUser, User, UserId, UserRole, UserStatus};
 
+pub const DEFAULT_PAGE_SIZE: usize = 20;
+pub const MAX_PAGE_SIZE: usize = 200;
+
+/// `[page, page_size]` from query params. Missing or non-numeric values use
+/// the defaults (page 1, `DEFAULT_PAGE_SIZE`); numbers out of range are errors.
+fn parse_paging(params: &[String]) -> StdResult<(usize, usize), ViewModelError> {
+    let number = |idx: usize| params.get(idx).and_then(|s| s.trim().parse::<i64>().ok());
+
+    let page = number(0).unwrap_or(1);
+    if page < 1 {
+        return Err(ViewModelError::validation(format!("page must be at least 1, got {}", page)));
+    }
+    let page_size = number(1).unwrap_or(DEFAULT_PAGE_SIZE as i64);
+    if !(1..=MAX_PAGE_SIZE as i64).contains(&page_size) {
+        return Err(ViewModelError::validation(format!(
+            "page_size must be between 1 and {}, got {}",
+            MAX_PAGE_SIZE, page_size
+        )));
+    }
+    Ok((page as usize, page_size as usize))
+}
+
+/// `{ name?, role?, status? }` for `search_users`; absent fields match everything.
+#[derive(Debug, Default, serde::Deserialize)]
+struct UserFilter {
+    name: Option<String>,
+    role: Option<String>,
+    status: Option<String>,
+}
+
+impl UserFilter {
+    /// The first param, if it is not an `include=` list.
+    fn from_params(params: &[String]) -> StdResult<Self, ViewModelError> {
+        match params.first().filter(|p| !p.starts_with("include=")) {
+            Some(raw) if !raw.trim().is_empty() => serde_json::from_str(raw)
+                .map_err(|e| ViewModelError::InvalidQuery(format!("Invalid search filter: {}", e))),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Name is a case-insensitive substring; role and status must match whole.
+    fn matches(&self, user: &User) -> bool {
+        let name = self
+            .name
+            .as_ref()
+            .is_none_or(|n| user.name.to_lowercase().contains(&n.to_lowercase()));
+        let role = self
+            .role
+            .as_ref()
+            .is_none_or(|r| user.role.to_string().eq_ignore_ascii_case(r));
+        let status = self
+            .status
+            .as_ref()
+            .is_none_or(|s| user.status.to_string().eq_ignore_ascii_case(s));
+        name && role && status
+    }
+}
+
+/// Called with the current state after `set_state` and after every command
+/// that succeeds.
+pub type StateObserver = Arc<dyn Fn(&ViewModelState) + Send + Sync>;
+
 pub struct UserViewModel {
     state: RwLock<ViewModelState>,
+    observers: RwLock<Vec<(u64, StateObserver)>>,
+    next_observer: AtomicU64,
     users: RwLock<Vec<User>>,
     current_user_id: RwLock<Option<UserId>>,
+    current_role: RwLock<Option<UserRole>>,
+    email_allowlist: RwLock<EmailDomainAllowlist>,
+    field_access: RwLock<FieldAccess>,
+    service: RwLock<Option<Arc<dyn UserService>>>,
+    clock: Clock,
 }
 
 impl UserViewModel {
     pub fn new() -> Self {
         Self {
             state: RwLock::new(ViewModelState::Initial),
+            observers: RwLock::new(Vec::new()),
+            next_observer: AtomicU64::new(1),
             users: RwLock::new(Vec::new()),
             current_user_id: RwLock::new(None),
+            current_role: RwLock::new(None),
+            email_allowlist: RwLock::new(EmailDomainAllowlist::default()),
+            field_access: RwLock::new(FieldAccess::default()),
+            service: RwLock::new(None),
+            clock: computed::system_clock(),
+        }
+    }
+
+    /// Persists users through `service`, which also publishes the user
+    /// events; the in-memory list becomes a cache of the stored users.
+    /// Without one, users live only in memory.
+    pub fn set_service(&self, service: Option<Arc<dyn UserService>>) {
+        *self.service.write().unwrap() = service;
+    }
+
+    fn service(&self) -> Option<Arc<dyn UserService>> {
+        self.service.read().unwrap().clone()
+    }
+
+    /// Users for queries, refreshed from the service when there is one.
+    fn load_users(&self) -> StdResult<Vec<User>, ViewModelError> {
+        let Some(service) = self.service() else {
+            return Ok(self.get_users());
+        };
+        let users: Vec<User> = futures::executor::block_on(service.get_all_users())?
+            .into_iter()
+            .map(User::from)
+            .collect();
+        *self.users.write().unwrap() = users.clone();
+        Ok(users)
+    }
+
+    /// Applies `changes` to the stored user and refreshes the cached copy.
+    /// The user is looked up in storage, not the cache.
+    fn change_stored_user(
+        &self,
+        service: &dyn UserService,
+        id: &UserId,
+        changes: UserChanges,
+    ) -> StdResult<User, ViewModelError> {
+        let user = futures::executor::block_on(service.change_user(stored::UserId(id.0), changes))?;
+        let user = User::from(user);
+        if !self.replace_user(user.clone()) {
+            self.add_user(user.clone());
         }
+        Ok(user)
+    }
+
+    pub fn set_field_access(&self, field_access: FieldAccess) {
+        *self.field_access.write().unwrap() = field_access;
+    }
+
+    pub fn set_current_user(&self, id: Option<UserId>) {
+        *self.current_user_id.write().unwrap() = id;
+    }
+
+    /// Acts as `role` regardless of the current user, e.g. for a single-user
+    /// desktop session with no sign-in. `None` goes back to the user's role.
+    pub fn set_current_role(&self, role: Option<UserRole>) {
+        *self.current_role.write().unwrap() = role;
+    }
+
+    /// Role commands and queries run as: an explicitly set role, else the
+    /// current user's, else guest.
+    pub fn actor_role(&self) -> UserRole {
+        if let Some(role) = self.current_role.read().unwrap().clone() {
+            return role;
+        }
+        self.current_user_id
+            .read()
+            .unwrap()
+            .as_ref()
+            .and_then(|id| self.find_by_id(id))
+            .map(|user| user.role)
+            .unwrap_or(UserRole::Guest)
+    }
+
+    /// Guests are read-only; changing another user's status or removing
+    /// them is reserved for admins.
+    fn authorize(&self, command: &str) -> StdResult<(), ViewModelError> {
+        let role = self.actor_role();
+        let allowed = match command {
+            "delete_user" | "activate_user" | "deactivate_user" => role == UserRole::Admin,
+            _ => role != UserRole::Guest,
+        };
+        if allowed {
+            Ok(())
+        } else {
+            Err(ViewModelError::Unauthorized(format!(
+                "{} may not run {}",
+                role, command
+            )))
+        }
+    }
+
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    fn user_json(&self, user: &User, role: &UserRole, include: &[String]) -> Value {
+        let mut value = json!({
+            "id": user.id.0,
+            "name": user.name,
+            "email": user.email.as_str(),
+            "role": user.role.to_string(),
+            "status": user.status.to_string(),
+            "created_at": format_for_display(user.created_at),
+        });
+        self.field_access.read().unwrap().redact(role, &mut value);
+        computed::attach(&mut value, include, &user.name, user.created_at, (self.clock)());
+        value
+    }
+
+    pub fn set_email_allowlist(&self, allowlist: EmailDomainAllowlist) {
+        *self.email_allowlist.write().unwrap() = allowlist;
     }
 
     pub fn set_state(&self, state: ViewModelState) {
-        let mut s = self.state.write().unwrap();
-        *s = state;
+        *self.state.write().unwrap() = state;
+        self.notify();
+    }
+
+    /// Registers `observer` and returns an id for `unsubscribe`.
+    pub fn subscribe<F>(&self, observer: F) -> u64
+    where
+        F: Fn(&ViewModelState) + Send + Sync + 'static,
+    {
+        let id = self.next_observer.fetch_add(1, Ordering::Relaxed);
+        self.observers.write().unwrap().push((id, Arc::new(observer)));
+        id
+    }
+
+    pub fn unsubscribe(&self, id: u64) -> bool {
+        let mut observers = self.observers.write().unwrap();
+        let before = observers.len();
+        observers.retain(|(observer_id, _)| *observer_id != id);
+        observers.len() != before
+    }
+
+    /// Observers run without any lock held, so they may call back in.
+    fn notify(&self) {
+        let observers: Vec<StateObserver> = self
+            .observers
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(_, observer)| observer.clone())
+            .collect();
+        if observers.is_empty() {
+            return;
+        }
+        let state = self.state();
+        for observer in observers {
+            observer(&state);
+        }
     }
 
     pub fn get_users(&self) -> Vec<User> {
@@ -36,6 +267,24 @@ impl UserViewModel {
         self.users.write().unwrap().push(user);
     }
 
+    pub fn remove_user(&self, id: &UserId) -> bool {
+        let mut users = self.users.write().unwrap();
+        let before = users.len();
+        users.retain(|u| &u.id != id);
+        users.len() != before
+    }
+
+    pub fn replace_user(&self, user: User) -> bool {
+        let mut users = self.users.write().unwrap();
+        match users.iter_mut().find(|u| u.id == user.id) {
+            Some(existing) => {
+                *existing = user;
+                true
+            }
+            None => false,
+        }
+    }
+
     pub fn find_by_id(&self, id: &UserId) -> Option<User> {
         self.users
             .read()
@@ -45,21 +294,40 @@ impl UserViewModel {
             .cloned()
     }
 
+    /// Checks an address against the format rules and the domain allowlist.
+    fn validate_email(&self, email: &str) -> StdResult<Email, ViewModelError> {
+        self.email_allowlist
+            .read()
+            .unwrap()
+            .check(email)
+            .map_err(ViewModelError::validation)?;
+        Email::new(email).map_err(|e| ViewModelError::validation(e.to_string()))
+    }
+
     pub fn create_user(
         &self,
         name: &str,
         email: &str,
         role: &str,
     ) -> StdResult<User, ViewModelError> {
-        let email =
-            Email::new(email).map_err(|e| ViewModelError::ExecutionFailed(e.to_string()))?;
-
-        let role = match role {
-            "admin" => UserRole::Admin,
-            "editor" => UserRole::Editor,
-            "user" => UserRole::User,
-            _ => UserRole::Guest,
-        };
+        let email = trace::span("validation", || self.validate_email(email))?;
+        let role = UserRole::parse(role);
+
+        if let Some(service) = self.service() {
+            let new_user = stored::NewUser {
+                name: name.to_string(),
+                email: stored::Email(email.as_str().to_string()),
+                role: stored::UserRole::from_str(&role.to_string()),
+                external_id: None,
+            };
+            let user = futures::executor::block_on(async {
+                let id = service.create_user(new_user).await?;
+                service.get_user(id).await
+            })
+            .map(User::from)?;
+            self.add_user(user.clone());
+            return Ok(user);
+        }
 
         let new_user = NewUser {
             name: name.to_string(),
@@ -70,28 +338,55 @@ impl UserViewModel {
         let id = UserId(self.users.read().unwrap().len() as i64 + 1);
         let user = User::new(id, new_user);
 
-        self.add_user(user.clone());
+        trace::span("store.insert", || self.add_user(user.clone()));
 
         Ok(user)
     }
-}
 
-impl Default for UserViewModel {
-    fn default() -> Self {
-        Self::new()
-    }
-}
+    /// Applies whichever of `name`, `email` and `role` are given. Nothing is
+    /// changed unless every provided field is valid.
+    pub fn update_user(
+        &self,
+        id: &UserId,
+        name: Option<&str>,
+        email: Option<&str>,
+        role: Option<&str>,
+    ) -> StdResult<User, ViewModelError> {
+        if let Some(service) = self.service() {
+            let email = email
+                .map(|email| trace::span("validation", || self.validate_email(email)))
+                .transpose()?;
+            let changes = UserChanges {
+                name: name.map(String::from),
+                email: email.map(|email| email.as_str().to_string()),
+                role: role.map(|role| stored::UserRole::from_str(&UserRole::parse(role).to_string())),
+                status: None,
+            };
+            return self.change_stored_user(service.as_ref(), id, changes);
+        }
 
-impl ViewModel for UserViewModel {
-    fn name(&self) -> &str {
-        "UserViewModel"
-    }
+        let mut user = self
+            .find_by_id(id)
+            .ok_or_else(|| ViewModelError::NotFound(format!("User {}", id)))?;
 
-    fn state(&self) -> ViewModelState {
-        self.state.read().unwrap().clone()
+        if let Some(email) = email {
+            user.email = trace::span("validation", || self.validate_email(email))?;
+        }
+        if let Some(name) = name {
+            user.name = name.to_string();
+        }
+        if let Some(role) = role {
+            user.role = UserRole::parse(role);
+        }
+        user.updated_at = (self.clock)();
+
+        if !self.replace_user(user.clone()) {
+            return Err(ViewModelError::NotFound(format!("User {}", id)));
+        }
+        Ok(user)
     }
 
-    fn handle_command(&self, command: &str, payload: &str) -> StdResult<String, ViewModelError> {
+    fn run_command(&self, command: &str, payload: &str) -> StdResult<String, ViewModelError> {
         match command {
             "create_user" => {
                 let data: Value = serde_json::from_str(payload)
@@ -115,6 +410,34 @@ impl ViewModel for UserViewModel {
                 }))
                 .unwrap())
             }
+            "update_user" => {
+                let data: Value = serde_json::from_str(payload)
+                    .map_err(|e| ViewModelError::InvalidCommand(e.to_string()))?;
+
+                let id = data["id"]
+                    .as_i64()
+                    .ok_or_else(|| ViewModelError::validation("update_user requires an id"))?;
+
+                let user = self.update_user(
+                    &UserId(id),
+                    data["name"].as_str(),
+                    data["email"].as_str(),
+                    data["role"].as_str(),
+                )?;
+
+                Ok(serde_json::to_string(&json!({
+                    "success": true,
+                    "user": {
+                        "id": user.id.0,
+                        "name": user.name,
+                        "email": user.email.as_str(),
+                        "role": user.role.to_string(),
+                        "status": user.status.to_string(),
+                        "updated_at": format_for_display(user.updated_at),
+                    }
+                }))
+                .unwrap())
+            }
             "delete_user" => {
                 let data: Value = serde_json::from_str(payload)
                     .map_err(|e| ViewModelError::InvalidCommand(e.to_string()))?;
@@ -122,8 +445,10 @@ impl ViewModel for UserViewModel {
                 let id = data["id"].as_i64().unwrap_or(0);
                 let user_id = UserId(id);
 
-                let mut users = self.users.write().unwrap();
-                users.retain(|u| u.id != user_id);
+                if let Some(service) = self.service() {
+                    futures::executor::block_on(service.delete_user(stored::UserId(id)))?;
+                }
+                self.remove_user(&user_id);
 
                 Ok(serde_json::to_string(&json!({"success": true})).unwrap())
             }
@@ -134,39 +459,129 @@ impl ViewModel for UserViewModel {
                 let id = data["id"].as_i64().unwrap_or(0);
                 let user_id = UserId(id);
 
-                if let Some(user) = self.find_by_id(&user_id) {
-                    let mut users = self.users.write().unwrap();
-                    if let Some(u) = users.iter_mut().find(|u| u.id == user_id) {
-                        u.activate();
-                    }
+                if let Some(service) = self.service() {
+                    let changes = UserChanges {
+                        status: Some(stored::UserStatus::Active),
+                        ..UserChanges::default()
+                    };
+                    self.change_stored_user(service.as_ref(), &user_id, changes)?;
+                    return Ok(serde_json::to_string(&json!({"success": true})).unwrap());
+                }
+
+                if let Some(mut user) = self.find_by_id(&user_id) {
+                    user.activate();
+                    self.replace_user(user);
                     Ok(serde_json::to_string(&json!({"success": true})).unwrap())
                 } else {
                     Err(ViewModelError::NotFound(format!("User {}", id)))
                 }
             }
+            "deactivate_user" => {
+                let data: Value = serde_json::from_str(payload)
+                    .map_err(|e| ViewModelError::InvalidCommand(e.to_string()))?;
+
+                let id = data["id"].as_i64().unwrap_or(0);
+                let user_id = UserId(id);
+
+                if let Some(service) = self.service() {
+                    let changes = UserChanges {
+                        status: Some(stored::UserStatus::Inactive),
+                        ..UserChanges::default()
+                    };
+                    self.change_stored_user(service.as_ref(), &user_id, changes)?;
+                    return Ok(serde_json::to_string(&json!({"success": true})).unwrap());
+                }
+
+                match self.find_by_id(&user_id) {
+                    Some(mut user) => {
+                        user.deactivate();
+                        self.replace_user(user);
+                        Ok(serde_json::to_string(&json!({"success": true})).unwrap())
+                    }
+                    None => Err(ViewModelError::NotFound(format!("User {}", id))),
+                }
+            }
             _ => Err(ViewModelError::InvalidCommand(format!(
                 "Unknown command: {}",
                 command
             ))),
         }
     }
+}
+
+impl Default for UserViewModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ViewModel for UserViewModel {
+    fn name(&self) -> &str {
+        "UserViewModel"
+    }
+
+    fn state(&self) -> ViewModelState {
+        self.state.read().unwrap().clone()
+    }
+
+    fn commands(&self) -> &[&'static str] {
+        &["create_user", "update_user", "delete_user", "activate_user", "deactivate_user"]
+    }
+
+    /// Snapshots the user list. In persisted mode this is only the cache;
+    /// the stored writes are undone by the batch's transaction.
+    fn checkpoint(&self) -> Option<Checkpoint> {
+        let users = self.users.read().unwrap().clone();
+        let current = self.current_user_id.read().unwrap().clone();
+        Some(Box::new((users, current)))
+    }
+
+    fn restore(&self, checkpoint: Checkpoint) {
+        if let Ok(state) = checkpoint.downcast::<(Vec<User>, Option<UserId>)>() {
+            let (users, current) = *state;
+            *self.users.write().unwrap() = users;
+            *self.current_user_id.write().unwrap() = current;
+        }
+        self.notify();
+    }
+
+    fn handle_command(&self, command: &str, payload: &str) -> StdResult<String, ViewModelError> {
+        if self.commands().contains(&command) {
+            self.authorize(command)?;
+        }
+
+        let result = self.run_command(command, payload);
+        if result.is_ok() {
+            self.notify();
+        }
+        result
+    }
 
     fn handle_query(&self, query: &str, params: &[String]) -> StdResult<String, ViewModelError> {
         match query {
             "get_users" => {
-                let users = self.get_users();
-                let user_list: Vec<Value> = users
+                let include = computed::parse_include(params);
+                let role = self.actor_role();
+                let users = self.load_users()?;
+                let user_list: Vec<Value> =
+                    users.iter().map(|u| self.user_json(u, &role, &include)).collect();
+
+                Ok(serde_json::to_string(&json!({
+                    "success": true,
+                    "users": user_list,
+                    "count": user_list.len(),
+                }))
+                .unwrap())
+            }
+            "search_users" => {
+                let filter = UserFilter::from_params(params)?;
+                let include = computed::parse_include(params);
+                let role = self.actor_role();
+                let user_list: Vec<Value> = self
+                    .load_users()?
                     .iter()
-                    .map(|u| {
-                        json!({
-                            "id": u.id.0,
-                            "name": u.name,
-                            "email": u.email.as_str(),
-                            "role": u.role.to_string(),
-                            "status": u.status.to_string(),
-                            "created_at": u.created_at.to_rfc3339(),
-                        })
-                    })
+                    .filter(|u| filter.matches(u))
+                    .map(|u| self.user_json(u, &role, &include))
                     .collect();
 
                 Ok(serde_json::to_string(&json!({
@@ -176,22 +591,53 @@ impl ViewModel for UserViewModel {
                 }))
                 .unwrap())
             }
+            "get_users_paged" => {
+                let (page, page_size) = parse_paging(params)?;
+                let include = computed::parse_include(params);
+                let role = self.actor_role();
+                let users = self.load_users()?;
+
+                let total = users.len();
+                let page_users: Vec<Value> = users
+                    .iter()
+                    .skip((page - 1).saturating_mul(page_size))
+                    .take(page_size)
+                    .map(|u| self.user_json(u, &role, &include))
+                    .collect();
+
+                Ok(serde_json::to_string(&json!({
+                    "success": true,
+                    "users": page_users,
+                    "page": page,
+                    "page_size": page_size,
+                    "total": total,
+                    "total_pages": total.div_ceil(page_size),
+                }))
+                .unwrap())
+            }
             "get_user_by_id" => {
                 let id = params
                     .first()
                     .and_then(|s| s.parse::<i64>().ok())
                     .unwrap_or(0);
 
-                if let Some(user) = self.find_by_id(&UserId(id)) {
+                let user = match self.service() {
+                    Some(service) => {
+                        match futures::executor::block_on(service.get_user(stored::UserId(id))) {
+                            Ok(user) => Some(User::from(user)),
+                            Err(DomainError::NotFound(_)) => None,
+                            Err(e) => return Err(e.into()),
+                        }
+                    }
+                    None => self.find_by_id(&UserId(id)),
+                };
+
+                if let Some(user) = user {
+                    let include = computed::parse_include(params);
+                    let role = self.actor_role();
                     Ok(serde_json::to_string(&json!({
                         "success": true,
-                        "user": {
-                            "id": user.id.0,
-                            "name": user.name,
-                            "email": user.email.as_str(),
-                            "role": user.role.to_string(),
-                            "status": user.status.to_string(),
-                        }
+                        "user": self.user_json(&user, &role, &include),
                     }))
                     .unwrap())
                 } else {
@@ -211,3 +657,287 @@ pub type SharedUserViewModel = Arc<UserViewModel>;
 pub fn create_user_viewmodel() -> SharedUserViewModel {
     Arc::new(UserViewModel::new())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::user::testing::persisted;
+
+    #[test]
+    fn computed_fields_use_the_injected_clock_and_the_name() {
+        // Created just after this, so ten days and slightly under an hour old.
+        let pinned = chrono::Utc::now() + chrono::Duration::days(10) + chrono::Duration::hours(1);
+        let vm = UserViewModel::new().with_clock(Arc::new(move || pinned));
+        vm.set_current_role(Some(UserRole::Admin));
+        vm.create_user("ada lovelace byron", "ada@example.com", "user").unwrap();
+
+        let response: Value = serde_json::from_str(
+            &vm.handle_query("get_users", &["include=initials,account_age_days,shoe_size".to_string()])
+                .unwrap(),
+        )
+        .unwrap();
+
+        let listed = &response["users"][0];
+        assert_eq!(listed["initials"], "AL");
+        assert_eq!(listed["account_age_days"], 10);
+        assert!(listed.get("shoe_size").is_none());
+    }
+
+    fn listed_user(vm: &UserViewModel) -> Value {
+        let response: Value = serde_json::from_str(&vm.handle_query("get_users", &[]).unwrap()).unwrap();
+        response["users"][0].clone()
+    }
+
+    #[test]
+    fn guests_see_masked_emails_and_admins_see_them_in_full() {
+        let vm = UserViewModel::new();
+        vm.set_current_role(Some(UserRole::Admin));
+        vm.create_user("Ada", "ada@example.com", "user").unwrap();
+        assert_eq!(listed_user(&vm)["email"], "ada@example.com");
+
+        vm.set_current_role(Some(UserRole::Guest));
+        let listed = listed_user(&vm);
+        assert_eq!(listed["email"], "a***@example.com");
+        assert_eq!(listed["name"], "Ada");
+    }
+
+    #[test]
+    fn field_access_config_overrides_the_defaults() {
+        let vm = UserViewModel::new();
+        vm.set_current_role(Some(UserRole::Admin));
+        vm.create_user("Ada", "ada@example.com", "user").unwrap();
+        vm.set_field_access(FieldAccess::new(&std::collections::HashMap::from([
+            ("Guest".to_string(), vec!["*".to_string()]),
+            ("admin".to_string(), vec!["id".to_string(), "name".to_string()]),
+        ])));
+
+        let listed = listed_user(&vm);
+        assert_eq!(listed["email"], "a***@example.com");
+        assert!(listed.get("status").is_none());
+
+        vm.set_current_role(Some(UserRole::Guest));
+        assert_eq!(listed_user(&vm)["email"], "ada@example.com");
+    }
+
+    fn admin_vm() -> UserViewModel {
+        let vm = UserViewModel::new();
+        vm.set_current_role(Some(UserRole::Admin));
+        vm
+    }
+
+    #[test]
+    fn update_user_applies_only_the_given_fields() {
+        let vm = admin_vm();
+        let created = vm.create_user("Ada", "ada@example.com", "user").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+
+        let response: Value = serde_json::from_str(
+            &vm.handle_command("update_user", &json!({ "id": created.id.0, "name": "Ada L" }).to_string())
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(response["user"]["name"], "Ada L");
+        assert_eq!(response["user"]["email"], "ada@example.com");
+
+        let stored = vm.find_by_id(&created.id).unwrap();
+        assert_eq!((stored.name.as_str(), stored.role), ("Ada L", UserRole::User));
+        assert!(stored.updated_at > created.updated_at);
+
+        let bad_email = json!({ "id": created.id.0, "email": "a@b" }).to_string();
+        assert!(matches!(vm.handle_command("update_user", &bad_email), Err(ViewModelError::ValidationError(_))));
+        let unknown = json!({ "id": 999, "name": "Nobody" }).to_string();
+        assert!(matches!(vm.handle_command("update_user", &unknown), Err(ViewModelError::NotFound(_))));
+    }
+
+    #[test]
+    fn deactivate_then_activate_toggles_status() {
+        let vm = admin_vm();
+        let id = vm.create_user("Ada", "ada@example.com", "user").unwrap().id;
+        let payload = json!({ "id": id.0 }).to_string();
+
+        vm.handle_command("deactivate_user", &payload).unwrap();
+        assert_eq!(vm.find_by_id(&id).unwrap().status, UserStatus::Inactive);
+        vm.handle_command("activate_user", &payload).unwrap();
+        assert_eq!(vm.find_by_id(&id).unwrap().status, UserStatus::Active);
+        vm.handle_command("deactivate_user", &payload).unwrap();
+        assert_eq!(vm.find_by_id(&id).unwrap().status, UserStatus::Inactive);
+
+        let unknown = json!({ "id": 999 }).to_string();
+        assert!(matches!(vm.handle_command("deactivate_user", &unknown), Err(ViewModelError::NotFound(_))));
+    }
+
+    #[test]
+    fn only_admins_may_delete_and_guests_may_only_read() {
+        let vm = admin_vm();
+        let id = vm.create_user("Ada", "ada@example.com", "user").unwrap().id;
+        let payload = json!({ "id": id.0 }).to_string();
+
+        vm.set_current_role(Some(UserRole::Guest));
+        assert!(matches!(vm.handle_command("delete_user", &payload), Err(ViewModelError::Unauthorized(_))));
+        let create = json!({ "name": "Grace", "email": "grace@example.com" }).to_string();
+        assert!(matches!(vm.handle_command("create_user", &create), Err(ViewModelError::Unauthorized(_))));
+        assert!(vm.handle_query("get_users", &[]).is_ok());
+
+        vm.set_current_role(Some(UserRole::Editor));
+        assert!(matches!(vm.handle_command("delete_user", &payload), Err(ViewModelError::Unauthorized(_))));
+        assert!(vm.find_by_id(&id).is_some());
+
+        vm.set_current_role(Some(UserRole::Admin));
+        vm.handle_command("delete_user", &payload).unwrap();
+        assert!(vm.find_by_id(&id).is_none());
+    }
+
+    fn vm_with_users(names: &[&str]) -> UserViewModel {
+        let vm = admin_vm();
+        for name in names {
+            vm.create_user(name, &format!("{}@example.com", name.to_lowercase()), "user").unwrap();
+        }
+        vm
+    }
+
+    fn page(vm: &UserViewModel, params: &[&str]) -> Value {
+        let params: Vec<String> = params.iter().map(|p| p.to_string()).collect();
+        serde_json::from_str(&vm.handle_query("get_users_paged", &params).unwrap()).unwrap()
+    }
+
+    fn names(users: &Value) -> Vec<String> {
+        users
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|u| u["name"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn paged_query_returns_first_middle_and_out_of_range_pages() {
+        let vm = vm_with_users(&["Ada", "Grace", "Alan", "Edsger", "Barbara"]);
+
+        let first = page(&vm, &["1", "2"]);
+        assert_eq!(names(&first["users"]), ["Ada", "Grace"]);
+        assert_eq!((first["total"].as_u64(), first["total_pages"].as_u64()), (Some(5), Some(3)));
+
+        let middle = page(&vm, &["2", "2"]);
+        assert_eq!(names(&middle["users"]), ["Alan", "Edsger"]);
+        assert_eq!(middle["page"], 2);
+
+        let beyond = page(&vm, &["9", "2"]);
+        assert!(names(&beyond["users"]).is_empty());
+        assert_eq!((beyond["total"].as_u64(), beyond["total_pages"].as_u64()), (Some(5), Some(3)));
+    }
+
+    #[test]
+    fn paged_query_defaults_and_validates_its_params() {
+        let vm = vm_with_users(&["Ada", "Grace"]);
+
+        let defaulted = page(&vm, &["first", "many"]);
+        assert_eq!(defaulted["page"], 1);
+        assert_eq!(defaulted["page_size"], DEFAULT_PAGE_SIZE);
+        assert_eq!(names(&defaulted["users"]), ["Ada", "Grace"]);
+
+        for params in [["1", "0"], ["1", "201"], ["0", "10"]] {
+            let params: Vec<String> = params.iter().map(|p| p.to_string()).collect();
+            assert!(matches!(
+                vm.handle_query("get_users_paged", &params),
+                Err(ViewModelError::ValidationError(_))
+            ));
+        }
+    }
+
+    fn search(vm: &UserViewModel, filter: Value) -> Vec<String> {
+        let response: Value =
+            serde_json::from_str(&vm.handle_query("search_users", &[filter.to_string()]).unwrap()).unwrap();
+        names(&response["users"])
+    }
+
+    #[test]
+    fn search_combines_name_and_role_filters() {
+        let vm = vm_with_users(&["Ada", "Adam", "Grace"]);
+        vm.create_user("Adele", "adele@example.com", "admin").unwrap();
+
+        assert_eq!(search(&vm, json!({ "name": "AD" })), ["Ada", "Adam", "Adele"]);
+        assert_eq!(search(&vm, json!({ "name": "ad", "role": "admin" })), ["Adele"]);
+        assert_eq!(search(&vm, json!({ "name": "ad", "role": "user", "status": "inactive" })), Vec::<String>::new());
+    }
+
+    #[test]
+    fn empty_search_returns_every_user() {
+        let vm = vm_with_users(&["Ada", "Grace"]);
+
+        assert_eq!(search(&vm, json!({})), ["Ada", "Grace"]);
+        let unfiltered: Value = serde_json::from_str(&vm.handle_query("search_users", &[]).unwrap()).unwrap();
+        assert_eq!(unfiltered["count"], 2);
+    }
+
+    #[test]
+    fn observers_hear_about_commands_and_state_changes() {
+        let vm = admin_vm();
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let id = vm.subscribe(move |state| sink.lock().unwrap().push(state.clone()));
+
+        let create = json!({ "name": "Ada", "email": "ada@example.com" }).to_string();
+        vm.handle_command("create_user", &create).unwrap();
+        assert_eq!(seen.lock().unwrap().len(), 1);
+
+        let unknown = json!({ "id": 999 }).to_string();
+        assert!(vm.handle_command("activate_user", &unknown).is_err());
+        assert_eq!(seen.lock().unwrap().len(), 1);
+
+        vm.set_state(ViewModelState::Busy);
+        assert_eq!(seen.lock().unwrap().last(), Some(&ViewModelState::Busy));
+
+        assert!(vm.unsubscribe(id));
+        vm.handle_command("create_user", &create.replace("ada@", "ada2@")).unwrap();
+        assert_eq!(seen.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn persisted_commands_go_through_the_service() {
+        let persisted = persisted();
+        let vm = persisted.admin_viewmodel();
+
+        let user = vm.create_user("Ada", "ada@example.com", "user").unwrap();
+        let id = user.id.0;
+        vm.handle_command("update_user", &json!({ "id": id, "name": "Ada L", "role": "editor" }).to_string())
+            .unwrap();
+        vm.handle_command("deactivate_user", &json!({ "id": id }).to_string()).unwrap();
+
+        let listed = persisted.projection.list().unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(
+            (listed[0].name.as_str(), listed[0].role.as_str(), listed[0].status.as_str()),
+            ("Ada L", "Editor", "Inactive")
+        );
+
+        vm.handle_command("delete_user", &json!({ "id": id }).to_string()).unwrap();
+        assert!(persisted.projection.list().unwrap().is_empty());
+        assert_eq!(
+            *persisted.events.lock().unwrap(),
+            ["user.created", "user.updated", "user.updated", "user.deleted"]
+        );
+    }
+
+    #[test]
+    fn persisted_commands_find_users_missing_from_the_cache() {
+        let persisted = persisted();
+        let id = persisted
+            .admin_viewmodel()
+            .create_user("Ada", "ada@example.com", "user")
+            .unwrap()
+            .id;
+
+        // A fresh viewmodel, as after a restart: nothing is cached yet.
+        let vm = persisted.admin_viewmodel();
+        assert!(vm.find_by_id(&id).is_none());
+
+        let updated = vm.update_user(&id, Some("Grace"), None, None).unwrap();
+        assert_eq!(updated.name, "Grace");
+        vm.handle_command("deactivate_user", &json!({ "id": id.0 }).to_string()).unwrap();
+        vm.handle_command("activate_user", &json!({ "id": id.0 }).to_string()).unwrap();
+        assert_eq!(vm.find_by_id(&id).unwrap().status, UserStatus::Active);
+
+        let missing = vm.handle_command("activate_user", &json!({ "id": 999 }).to_string());
+        assert!(matches!(missing, Err(ViewModelError::NotFound(_))));
+    }
+}
@@ -1,6 +1,71 @@
 // infrastructure/web/logging.rs
 use log::{debug, error, info, warn};
-use std::time::Instant;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Aggregated timings of one handler, as reported by `get_handler_metrics`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HandlerStats {
+    pub count: u64,
+    pub failures: u64,
+    pub total_ms: f64,
+    pub avg_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Per-handler durations recorded by `RequestLogger`, keyed by handler name.
+pub struct HandlerMetrics {
+    handlers: Mutex<BTreeMap<String, HandlerStats>>,
+}
+
+static HANDLER_METRICS: HandlerMetrics = HandlerMetrics::new();
+
+impl HandlerMetrics {
+    pub const fn new() -> Self {
+        Self {
+            handlers: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// The registry `RequestLogger` records into.
+    pub fn global() -> &'static HandlerMetrics {
+        &HANDLER_METRICS
+    }
+
+    pub fn record(&self, handler_name: &str, duration: Duration, success: bool) {
+        let ms = duration.as_secs_f64() * 1000.0;
+        let mut handlers = self.handlers.lock().unwrap();
+        let stats = handlers.entry(handler_name.to_string()).or_default();
+        stats.count += 1;
+        if !success {
+            stats.failures += 1;
+        }
+        stats.total_ms += ms;
+        stats.avg_ms = stats.total_ms / stats.count as f64;
+        stats.max_ms = stats.max_ms.max(ms);
+    }
+
+    pub fn get(&self, handler_name: &str) -> Option<HandlerStats> {
+        self.handlers.lock().unwrap().get(handler_name).cloned()
+    }
+
+    /// All handlers, sorted by name.
+    pub fn snapshot(&self) -> BTreeMap<String, HandlerStats> {
+        self.handlers.lock().unwrap().clone()
+    }
+
+    pub fn reset(&self) {
+        self.handlers.lock().unwrap().clear();
+    }
+}
+
+impl Default for HandlerMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 pub struct RequestLogger {
     handler_name: String,
@@ -19,6 +84,7 @@ impl RequestLogger {
 
     pub fn success(&self, message: &str) {
         let duration = self.start_time.elapsed();
+        HandlerMetrics::global().record(&self.handler_name, duration, true);
         info!(
             "[{}] {} ({}ms)",
             self.handler_name,
@@ -29,6 +95,7 @@ impl RequestLogger {
 
     pub fn failure(&self, err_msg: &str) {
         let duration = self.start_time.elapsed();
+        HandlerMetrics::global().record(&self.handler_name, duration, false);
         error!(
             "[{}] Handler failed: {} ({}ms)",
             self.handler_name,
@@ -2,9 +2,9 @@
 #![allow(unused_imports)]
 #![allow(unused_variables)]
 
-use log::{error, info};
+use log::{error, info, warn};
 use std::ffi::CStr;
-use std::net::TcpListener;
+use std::net::{IpAddr, TcpListener};
 use std::sync::Arc;
 use webui_rs::webui;
 
@@ -19,30 +19,53 @@ mod platform;
 mod view;
 mod viewmodel;
 
-use plugins::{create_plugin_registry, PluginRegistry, Plugin};
-use features::user::UserPlugin;
+use plugins::{create_plugin_registry, PluginContext, PluginRegistry, Plugin};
+use features::FeatureModule;
 
-use mvvm::shared::config::AppConfig;
+use mvvm::shared::config::{AppConfig, ConfigWatcher, PortRange, PortStrategy};
+use crate::core::serialization;
+use mvvm::shared::platform::{app_data_dir, DataDirSource};
+use crate::core::boot::BootSequence;
+use crate::core::event_bus::webhook::WebhookSubscriber;
+use crate::core::monitoring::RateMonitor;
+use crate::core::readiness::ReadinessGate;
+use crate::core::shutdown::{
+    handle_window_closed, handle_window_disconnected, listen_for_signals, Shutdown,
+    ShutdownCoordinator, REASON_EXIT,
+};
+use crate::core::scheduler::Scheduler;
+use crate::core::timezone;
 use mvvm::shared::di::ServiceProvider;
 use mvvm::shared::logging::StructuredLogger;
 use mvvm::shared::ports::logger::LogLevel;
+use model::entities::errors::DomainError;
+use model::repositories::sqlite::connection::SqliteDatabase;
+use model::repositories::sqlite::event_store::RetentionPolicy;
+use model::repositories::sqlite::state_bundle::{ImportMode, StateTransfer};
+use infrastructure::web::logging::BuildMetrics;
+use mvvm::viewmodel::dispatcher::CommandDispatcher;
 use mvvm::viewmodel::bindings::{
+    audit_handlers::setup_audit_handlers,
+    command_handlers::setup_command_handlers,
+    health_handlers::setup_health_handlers,
     system_handlers::setup_system_handlers,
-    user_handlers::setup_user_handlers,
 };
 
 include!(concat!(env!("OUT_DIR"), "/build_config.rs"));
 
-fn allocate_ephemeral_port() -> Option<u16> {
-    let listener = TcpListener::bind(("127.0.0.1", 0)).ok()?;
+// Mirrors `WEBUI_EVENT_DISCONNECTED` in webui.h.
+const WEBUI_EVENT_DISCONNECTED: usize = 0;
+
+fn allocate_ephemeral_port(ip: IpAddr) -> Option<u16> {
+    let listener = TcpListener::bind((ip, 0)).ok()?;
     let port = listener.local_addr().ok()?.port();
     drop(listener);
     Some(port)
 }
 
-fn configure_random_webui_port(window: &webui::Window) -> Option<u16> {
+fn configure_random_webui_port(window: &webui::Window, ip: IpAddr) -> Option<u16> {
     for _ in 0..24 {
-        let Some(candidate) = allocate_ephemeral_port() else {
+        let Some(candidate) = allocate_ephemeral_port(ip) else {
             continue;
         };
 
@@ -54,6 +77,14 @@ fn configure_random_webui_port(window: &webui::Window) -> Option<u16> {
     None
 }
 
+/// The first port of `range` that is free on `ip` and accepted by WebUI.
+fn configure_webui_port_in_range(window: &webui::Window, ip: IpAddr, range: PortRange) -> Option<u16> {
+    range.candidates().find(|&candidate| {
+        TcpListener::bind((ip, candidate)).is_ok()
+            && unsafe { webui::bindgen::webui_set_port(window.id, candidate as usize) }
+    })
+}
+
 fn get_window_url(window: &webui::Window) -> Option<String> {
     let url_ptr = unsafe { webui::bindgen::webui_get_url(window.id) };
     if url_ptr.is_null() {
@@ -70,23 +101,224 @@ fn get_window_url(window: &webui::Window) -> Option<String> {
     }
 }
 
+/// The main database with every table a state bundle covers migrated.
+fn state_database(provider: &ServiceProvider) -> Result<Arc<SqliteDatabase>, DomainError> {
+    provider.user_repository()?;
+    provider.kv_store()?;
+    provider.main_database()
+}
+
+/// The `--export-state` and `--import-state` paths given on the command line.
+fn state_transfer_paths(
+    args: &[String],
+) -> (Option<std::path::PathBuf>, Option<std::path::PathBuf>) {
+    let value_of = |flag: &str| {
+        args.iter()
+            .position(|a| a == flag)
+            .and_then(|i| args.get(i + 1))
+            .map(std::path::PathBuf::from)
+    };
+    (value_of("--export-state"), value_of("--import-state"))
+}
+
+/// Handles `--export-state <path>` and `--import-state <path> [--merge|--replace]`.
+/// Returns the outcome when one of them ran, in which case the app should exit;
+/// a failure exits with a non-zero status.
+fn run_state_transfer(
+    args: &[String],
+    provider: &ServiceProvider,
+) -> Option<Result<(), DomainError>> {
+    let (export, import) = state_transfer_paths(args);
+    if export.is_none() && import.is_none() {
+        return None;
+    }
+    let transfer = match state_database(provider) {
+        Ok(database) => StateTransfer::new(database),
+        Err(e) => {
+            error!("State transfer failed to open the database: {}", e);
+            return Some(Err(e));
+        }
+    };
+
+    if let Some(path) = export {
+        return Some(match transfer.export_state(&path) {
+            Ok(bundle) => {
+                info!(
+                    "Exported {} user(s) and {} kv entr(ies) to {}",
+                    bundle.users.len(),
+                    bundle.kv.len(),
+                    path.display()
+                );
+                Ok(())
+            }
+            Err(e) => {
+                error!("State export failed: {}", e);
+                Err(e)
+            }
+        });
+    }
+
+    if let Some(path) = import {
+        let mode = if args.iter().any(|a| a == "--replace") {
+            ImportMode::Replace
+        } else {
+            ImportMode::Merge
+        };
+        return Some(match transfer.import_state(&path, mode) {
+            Ok(bundle) => {
+                if let Err(e) = provider.user_list_projection().and_then(|p| p.rebuild()) {
+                    warn!("User list projection rebuild failed after import: {}", e);
+                }
+                info!(
+                    "Imported {} user(s) and {} kv entr(ies) from {} ({:?})",
+                    bundle.users.len(),
+                    bundle.kv.len(),
+                    path.display(),
+                    mode
+                );
+                Ok(())
+            }
+            Err(e) => {
+                error!("State import failed: {}", e);
+                Err(e)
+            }
+        });
+    }
+
+    None
+}
+
+/// State threaded through the boot steps in `main`.
+struct Startup {
+    config: AppConfig,
+    plugin_registry: plugins::SharedPluginRegistry,
+    modules: Vec<Arc<dyn FeatureModule>>,
+    dispatcher: Option<Arc<CommandDispatcher>>,
+    provider: Option<Arc<ServiceProvider>>,
+    window: Option<webui::Window>,
+    startup_gate: Arc<ReadinessGate>,
+}
+
+/// Adds the steps that create the window and bind its handlers.
+fn with_window_steps(boot: BootSequence<Startup>) -> BootSequence<Startup> {
+    boot.step("window", &[], |s: &mut Startup| {
+        s.window = Some(webui::Window::new());
+        Ok(())
+    })
+    .step("features", &["dispatcher", "services", "window"], |s: &mut Startup| {
+        let (Some(window), Some(dispatcher), Some(provider)) =
+            (s.window.as_mut(), &s.dispatcher, &s.provider)
+        else {
+            return Err("the window, dispatcher and services steps must run first".to_string());
+        };
+        provider.container.register(dispatcher.clone());
+        for module in &s.modules {
+            module
+                .register(window, provider)
+                .map_err(|e| format!("Feature module {} failed to start: {}", module.name(), e))?;
+            info!("Feature module enabled: {}", module.name());
+        }
+        Ok(())
+    })
+    .step("database", &["dispatcher", "services"], |s: &mut Startup| {
+        let (Some(dispatcher), Some(provider)) = (&s.dispatcher, &s.provider) else {
+            return Err("the dispatcher and services steps must run first".to_string());
+        };
+        let database = provider
+            .main_database()
+            .map_err(|e| format!("Failed to open the main database: {}", e))?;
+        dispatcher.set_database(Some(database));
+        Ok(())
+    })
+    .step("system_handlers", &["plugins", "services", "window"], |s: &mut Startup| {
+        let (Some(window), Some(provider)) = (s.window.as_mut(), &s.provider) else {
+            return Err("the window and services steps must run first".to_string());
+        };
+        setup_system_handlers(window, provider.clone(), s.plugin_registry.clone());
+        setup_health_handlers(window, provider.clone(), s.plugin_registry.clone());
+        let audit_log = provider
+            .audit_log()
+            .map_err(|e| format!("Failed to open the audit log: {}", e))?;
+        setup_audit_handlers(window, audit_log);
+        Ok(())
+    })
+    .step("command_handlers", &["features", "database"], |s: &mut Startup| {
+        let (Some(window), Some(dispatcher)) = (s.window.as_mut(), &s.dispatcher) else {
+            return Err("the window and dispatcher steps must run first".to_string());
+        };
+        setup_command_handlers(window, dispatcher.clone(), s.startup_gate.clone());
+        Ok(())
+    })
+}
+
 fn main() {
-    let config = AppConfig::load().unwrap_or_default();
+    let args: Vec<String> = std::env::args().collect();
+    let explicit_config =
+        AppConfig::explicit_path(&args, std::env::var(AppConfig::CONFIG_PATH_ENV).ok());
+    let config = match &explicit_config {
+        // An explicit path that fails to load is an error, never the defaults.
+        Some(path) => match AppConfig::load_from(path) {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("Failed to load config: {}", e);
+                return;
+            }
+        },
+        None => AppConfig::load().unwrap_or_default(),
+    };
+    if let Err(e) = config.validate() {
+        eprintln!("Invalid configuration: {}", e);
+        return;
+    }
+
+    if let Some(name) = &config.app.timezone {
+        match timezone::parse_timezone(name) {
+            Ok(tz) => timezone::set_display_timezone(Some(tz)),
+            Err(e) => {
+                eprintln!("Invalid app.timezone: {}", e);
+                return;
+            }
+        }
+    }
 
     let log_level = LogLevel::from(config.logging.level.as_str());
 
-    let log_dir = if let Some(data_dir) = dirs::data_local_dir() {
-        Some(data_dir.join(&config.app.name))
-    } else {
-        None
-    };
+    let (log_dir, log_dir_source) =
+        app_data_dir(&config.app.name, config.logging.fallback_dir.as_deref());
 
-    let mut logger = StructuredLogger::new(log_level, &config.app.name);
-    if let Err(e) = logger.init(log_dir) {
+    let mut logger = StructuredLogger::new(log_level, &config.app.name)
+        .with_format(config.logging.format)
+        .with_module_levels(&config.logging.modules)
+        .with_rotation(config.logging.max_file_size_mb * 1024 * 1024, config.logging.max_files);
+    if let Err(e) = logger.init(Some(log_dir.clone())) {
         eprintln!("Failed to initialize logging: {}", e);
     }
 
+    if log_dir_source == DataDirSource::Fallback {
+        warn!(
+            "Platform data directory unavailable; using fallback directory {}",
+            log_dir.display()
+        );
+    }
+
     info!("Application starting: {} v{}", config.app.name, config.app.version);
+    let config_files = match &explicit_config {
+        Some(path) => vec![path.clone()],
+        None => AppConfig::existing_config_files(),
+    };
+    match config_files.as_slice() {
+        [] => info!("No config file found; using defaults"),
+        [chosen] => info!("Config file: {}", chosen.display()),
+        [chosen, ignored @ ..] => warn!(
+            "Config file: {} (ignoring {})",
+            chosen.display(),
+            ignored
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
     info!("Window title: {}", config.window.title);
 
     info!("=== Backend-Frontend Communication Configuration ===");
@@ -96,48 +328,292 @@ fn main() {
     info!("  - HTTP REST API");
     info!("  - IPC (local)");
     info!("Serialization Options:");
-    info!("  - JSON [SELECTED]");
-    info!("  - MessagePack");
-    info!("  - CBOR");
-    info!("  - Protocol Buffers (protobuf)");
-    info!("  - YAML");
-    info!("Selected: WebUI (transport) + JSON (serialization)");
+    let selected_format = config.serialization.format;
+    for (name, format) in [
+        ("JSON", Some(serialization::Format::Json)),
+        ("MessagePack", Some(serialization::Format::MessagePack)),
+        ("CBOR", Some(serialization::Format::Cbor)),
+        ("Protocol Buffers (protobuf)", None),
+        ("YAML", Some(serialization::Format::Yaml)),
+    ] {
+        if format == Some(selected_format) {
+            info!("  - {} [SELECTED]", name);
+        } else {
+            info!("  - {}", name);
+        }
+    }
+    info!("Selected: WebUI (transport) + {} (serialization)", selected_format.as_str());
     info!("===================================================");
 
-    // Initialize plugin system
-    let plugin_registry = create_plugin_registry();
-    let user_plugin = Arc::new(UserPlugin::new());
-    
-    if let Err(e) = plugin_registry.register(user_plugin.clone() as Arc<dyn plugins::Plugin>) {
-        error!("Failed to register user plugin: {}", e);
+    let mut startup = Startup {
+        config: config.clone(),
+        plugin_registry: create_plugin_registry(),
+        modules: features::enabled_modules(&config),
+        dispatcher: None,
+        provider: None,
+        window: None,
+        startup_gate: Arc::new(ReadinessGate::default()),
+    };
+
+    let boot = BootSequence::new()
+        .step("plugins", &[], |s: &mut Startup| {
+            s.plugin_registry.set_call_timeout(std::time::Duration::from_millis(
+                s.config.plugins.call_timeout_ms,
+            ));
+            s.plugin_registry.set_context(PluginContext::new(
+                Arc::new(s.config.clone()),
+                Arc::new(StructuredLogger::new(
+                    LogLevel::from(s.config.logging.level.as_str()),
+                    "plugins",
+                )),
+            ));
+
+            for module in &s.modules {
+                let plugin = module.plugin();
+                if let Err(e) = s.plugin_registry.register(plugin.clone()) {
+                    error!("Failed to register {} plugin: {}", module.name(), e);
+                } else {
+                    info!("Plugin registered: {} v{}", plugin.name(), plugin.version());
+                }
+            }
+
+            let directory = std::path::Path::new(&s.config.plugins.directory);
+            for result in s.plugin_registry.load_directory(directory) {
+                match result {
+                    Ok(name) => info!("Dynamic plugin registered: {}", name),
+                    Err(e) => error!("Failed to load dynamic plugin: {}", e),
+                }
+            }
+
+            s.plugin_registry.mark_initialized();
+            info!("Plugin system initialized with {} plugins", s.plugin_registry.len());
+            Ok(())
+        })
+        .step("dispatcher", &["plugins"], |s: &mut Startup| {
+            let dispatcher = Arc::new(CommandDispatcher::new());
+            for module in &s.modules {
+                for viewmodel in module.viewmodels() {
+                    dispatcher.register_viewmodel(viewmodel);
+                }
+            }
+            dispatcher
+                .set_aliases(&s.config.command_aliases)
+                .map_err(|e| format!("Invalid [command_aliases] configuration: {}", e))?;
+            dispatcher.set_concurrency(&s.config.concurrency);
+            info!("Command dispatcher ready: {}", dispatcher.commands().join(", "));
+            s.dispatcher = Some(dispatcher);
+            Ok(())
+        })
+        .step("services", &[], |s: &mut Startup| {
+            let provider = ServiceProvider::new(s.config.clone())
+                .map_err(|e| format!("Failed to initialize services: {}", e))?;
+            info!("DI container initialized successfully");
+            s.provider = Some(Arc::new(provider));
+            Ok(())
+        });
+    // A state transfer exits once the services are up, so it gets no window.
+    let (export_state, import_state) = state_transfer_paths(&args);
+    let boot = if export_state.is_some() || import_state.is_some() {
+        boot
     } else {
-        info!("Plugin registered: {} v{}", user_plugin.name(), user_plugin.version());
+        with_window_steps(boot)
     }
+    .with_dependencies(&config.boot.after);
 
-    info!("Plugin system initialized with {} plugins", plugin_registry.len());
+    let mut boot_metrics = BuildMetrics::new();
+    if let Err(e) = boot.run(&mut startup, &mut boot_metrics) {
+        error!("Startup failed: {}", e);
+        return;
+    }
+    info!("{}", boot_metrics.summary().trim_end());
 
-    let _provider = match ServiceProvider::new(config.clone()) {
-        Ok(p) => {
-            info!("DI container initialized successfully");
-            p
+    let Startup {
+        plugin_registry,
+        dispatcher,
+        provider,
+        window,
+        startup_gate,
+        ..
+    } = startup;
+    let (Some(dispatcher), Some(provider)) = (dispatcher, provider) else {
+        error!("Startup finished without a dispatcher or service provider");
+        return;
+    };
+
+    if ServiceProvider::set_global(provider.clone()).is_err() {
+        warn!("A global service provider was already installed; keeping it");
+    }
+
+    match run_state_transfer(&args, &provider) {
+        Some(Ok(())) => return,
+        Some(Err(_)) => {
+            logger.flush();
+            std::process::exit(1);
+        }
+        None => {}
+    }
+    let Some(mut window) = window else {
+        error!("Startup finished without a window");
+        return;
+    };
+
+    plugin_registry.set_event_bus(provider.event_bus.clone());
+
+    mvvm::viewmodel::bindings::compression::configure(config.compression.clone());
+    mvvm::viewmodel::bindings::serializer::configure(config.serialization.format);
+
+    let shutdown = Shutdown::new();
+    let coordinator = ShutdownCoordinator::new(
+        &config.app.name,
+        shutdown.clone(),
+        provider.event_bus.clone(),
+    )
+    .with_grace_period(std::time::Duration::from_secs(config.shutdown.grace_period_secs));
+
+    // Hooks run in reverse, so plugins stop after everything registered later.
+    {
+        let plugin_registry = plugin_registry.clone();
+        coordinator.on_shutdown("plugins", move || {
+            for (name, e) in plugin_registry.shutdown_all() {
+                warn!("Plugin {} failed to shut down: {}", name, e);
+            }
+        });
+    }
+    coordinator.track(
+        "signals",
+        listen_for_signals(shutdown.clone(), || unsafe { webui::bindgen::webui_exit() }),
+    );
+
+    let scheduler = Scheduler::new(shutdown.clone());
+    {
+        let event_store = match provider.event_store() {
+            Ok(event_store) => event_store,
+            Err(e) => {
+                error!("Failed to open the event store: {}", e);
+                return;
+            }
+        };
+        let policy = RetentionPolicy {
+            max_age_days: config.event_store.max_age_days,
+            max_rows: config.event_store.max_rows,
+        };
+        let prune = scheduler.every(
+            "event_store.prune",
+            std::time::Duration::from_secs(config.event_store.prune_interval_secs.max(1)),
+            move || match event_store.prune(&policy, chrono::Utc::now()) {
+                Ok(0) => {}
+                Ok(pruned) => info!("Pruned {} event(s) from the event store", pruned),
+                Err(e) => warn!("Event store pruning failed: {}", e),
+            },
+        );
+        coordinator.track("event_store.prune", prune);
+    }
+
+    {
+        let mut monitor = RateMonitor::new(&config.monitoring, provider.event_bus.clone());
+        monitor.watch_event_bus(provider.event_bus.clone());
+        let interval = std::time::Duration::from_millis(config.monitoring.sample_interval_ms.max(1));
+        coordinator.track("rate_monitor", Arc::new(monitor).spawn(interval, shutdown.clone()));
+    }
+
+    if !config.webhooks.endpoints.is_empty() {
+        let mut webhooks = futures::executor::block_on(WebhookSubscriber::attach(
+            &provider.event_bus,
+            &config.webhooks,
+            shutdown.clone(),
+        ));
+        if let Some(worker) = webhooks.take_worker() {
+            coordinator.track("webhooks", worker);
         }
+    }
+
+    if let Some(path) = config_files.first() {
+        let app_name = config.app.name.clone();
+        let watched = ConfigWatcher::watch(path, config.clone(), move |reloaded| {
+            // Only the console level, filters and format apply live; the
+            // startup logger keeps its file writer and rotation, and other
+            // sections take effect on restart.
+            let level = LogLevel::from(reloaded.logging.level.as_str());
+            let mut logger = StructuredLogger::new(level, &app_name)
+                .with_format(reloaded.logging.format)
+                .with_module_levels(&reloaded.logging.modules);
+            if let Err(e) = logger.init(None) {
+                warn!("Failed to apply reloaded logging config: {}", e);
+            }
+        });
+        match watched {
+            Ok(watcher) => coordinator.on_shutdown("config.watcher", move || watcher.stop()),
+            Err(e) => warn!("Config hot-reload unavailable: {}", e),
+        }
+    }
+
+    {
+        let shutdown = shutdown.clone();
+        window.bind("", move |event| {
+            if event.event_type as usize == WEBUI_EVENT_DISCONNECTED {
+                handle_window_disconnected(&shutdown);
+            }
+        });
+    }
+
+
+    let bind_address = match config.window.resolve_bind_address() {
+        Ok(address) => address,
         Err(e) => {
-            error!("Failed to initialize services: {}", e);
+            error!("{}", e);
             return;
         }
     };
+    if bind_address.is_external() {
+        unsafe { webui::bindgen::webui_set_public(window.id, true) };
+        warn!("==================================================================");
+        warn!("WebUI listens on ALL network interfaces ({})", bind_address.ip());
+        warn!("Anyone on the network can reach the app; only use on trusted networks");
+        warn!("==================================================================");
+    } else {
+        info!("WebUI bound to loopback ({})", bind_address.ip());
+    }
 
-    let mut window = webui::Window::new();
-
-    setup_user_handlers(&mut window);
-    setup_system_handlers(&mut window);
-
-    let selected_port = configure_random_webui_port(&window);
+    let selected_port = match config.window.port_strategy() {
+        PortStrategy::Fixed(port) => {
+            info!("WebUI port strategy: fixed {}", port);
+            if !unsafe { webui::bindgen::webui_set_port(window.id, port as usize) } {
+                error!("WebUI could not use window.port {}; is it already in use?", port);
+                return;
+            }
+            Some(port)
+        }
+        PortStrategy::Range(range) => {
+            info!("WebUI port strategy: range {}-{}", range.min, range.max);
+            let Some(port) = configure_webui_port_in_range(&window, bind_address.ip(), range) else {
+                error!(
+                    "No port in window.port_range {}-{} could be bound on {}",
+                    range.min,
+                    range.max,
+                    bind_address.ip()
+                );
+                return;
+            };
+            Some(port)
+        }
+        PortStrategy::Ephemeral => {
+            info!("WebUI port strategy: ephemeral");
+            configure_random_webui_port(&window, bind_address.ip())
+        }
+    };
     match selected_port {
         Some(port) => info!("WebUI runtime port selected: {}", port),
         None => info!("WebUI runtime port selection fallback: automatic WebUI port"),
     }
 
+    match config.window.window_size() {
+        Some((width, height)) => {
+            unsafe { webui::bindgen::webui_set_size(window.id, width, height) };
+            info!("Window size: {}x{}", width, height);
+        }
+        None => info!("Window size not configured; using WebUI default"),
+    }
+
     info!("Loading UI from frontend/dist/index.html");
     if !window.show("frontend/dist/index.html") {
         error!("Failed to show WebUI window");
@@ -156,8 +632,20 @@ fn main() {
         let _ = window.run_js(js);
     }
 
+    let flushed = startup_gate.mark_ready();
+    if flushed > 0 {
+        info!("Flushed {} command(s) received during startup", flushed);
+    }
+
     info!("Application started, waiting for events...");
     webui::wait();
 
+    // `wait` also returns after `webui_exit`, so only a window that is no
+    // longer shown counts as closed; otherwise WebUI exited on its own.
+    if !unsafe { webui::bindgen::webui_is_shown(window.id) } {
+        handle_window_closed(&shutdown);
+    }
+    coordinator.run(REASON_EXIT);
     info!("Application shutting down gracefully");
+    logger.flush();
 }
@@ -34,6 +34,13 @@ impl From<rusqlite::Error> for DomainError {
             rusqlite::Error::QueryReturnedNoRows => {
                 DomainError::NotFound("Record not found".to_string())
             }
+            rusqlite::Error::SqliteFailure(e, Some(msg))
+                if e.code == rusqlite::ErrorCode::ConstraintViolation
+                    && msg.starts_with("UNIQUE constraint failed") =>
+            {
+                let column = msg.rsplit('.').next().unwrap_or_default();
+                DomainError::AlreadyExists(format!("A record with this {} already exists", column))
+            }
             _ => DomainError::InvalidOperation(err.to_string()),
         }
     }
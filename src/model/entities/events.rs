@@ -1,5 +1,8 @@
 // core/domain/events.rs
+use crate::core::domain::errors::DomainError;
 use crate::core::domain::user::UserId;
+use crate::core::logging::correlation;
+use crate::core::serialization::{self, Format, SerializationError};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -30,6 +33,16 @@ impl EventMetadata {
         }
     }
 
+    /// Metadata for an event raised while handling the current request,
+    /// correlated with it when a correlation scope is active.
+    pub fn current(source: &str) -> Self {
+        let metadata = Self::new(source);
+        match correlation::current() {
+            Some(id) => metadata.with_correlation(id),
+            None => metadata,
+        }
+    }
+
     pub fn with_correlation(mut self, correlation_id: String) -> Self {
         self.correlation_id = Some(correlation_id);
         self
@@ -42,12 +55,42 @@ impl EventMetadata {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Envelope<T: DomainEvent> {
+pub struct Envelope<T> {
     pub metadata: EventMetadata,
     pub payload: T,
 }
 
-impl<T: DomainEvent> Envelope<T> {
+/// A published event in serialized form, for carrying through channels
+/// where the concrete event type is gone.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventRecord {
+    pub event_type: String,
+    pub aggregate_id: String,
+    pub occurred_at: DateTime<Utc>,
+    pub payload: serde_json::Value,
+}
+
+impl EventRecord {
+    pub fn from_event(event: &dyn DomainEvent) -> Self {
+        Self {
+            event_type: event.event_type().to_string(),
+            aggregate_id: event.aggregate_id(),
+            occurred_at: event.occurred_at(),
+            payload: event.payload(),
+        }
+    }
+}
+
+pub type EventEnvelope = Envelope<EventRecord>;
+
+impl<T: Serialize> Envelope<T> {
+    /// The envelope, metadata included, in `format`.
+    pub fn encode(&self, format: Format) -> Result<Vec<u8>, SerializationError> {
+        serialization::serialize(format, self)
+    }
+}
+
+impl<T> Envelope<T> {
     pub fn new(payload: T, source: &str) -> Self {
         Self {
             metadata: EventMetadata::new(source),
@@ -147,6 +190,10 @@ pub struct UserUpdatedEvent {
     pub user_id: UserId,
     pub name: Option<String>,
     pub email: Option<String>,
+    #[serde(default)]
+    pub role: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
     pub occurred_at: DateTime<Utc>,
 }
 
@@ -156,9 +203,21 @@ impl UserUpdatedEvent {
             user_id,
             name,
             email,
+            role: None,
+            status: None,
             occurred_at: Utc::now(),
         }
     }
+
+    pub fn with_role(mut self, role: Option<String>) -> Self {
+        self.role = role;
+        self
+    }
+
+    pub fn with_status(mut self, status: Option<String>) -> Self {
+        self.status = status;
+        self
+    }
 }
 
 impl DomainEvent for UserUpdatedEvent {
@@ -179,6 +238,8 @@ impl DomainEvent for UserUpdatedEvent {
             "user_id": self.user_id.0,
             "name": self.name,
             "email": self.email,
+            "role": self.role,
+            "status": self.status,
         })
     }
 }
@@ -258,3 +319,83 @@ impl DomainEvent for ApplicationShutdownEvent {
         })
     }
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplicationErrorEvent {
+    pub source: String,
+    pub message: String,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl ApplicationErrorEvent {
+    pub fn new(source: String, message: String) -> Self {
+        Self {
+            source,
+            message,
+            occurred_at: Utc::now(),
+        }
+    }
+}
+
+impl DomainEvent for ApplicationErrorEvent {
+    fn event_type(&self) -> &'static str {
+        "application.error"
+    }
+
+    fn occurred_at(&self) -> DateTime<Utc> {
+        self.occurred_at
+    }
+
+    fn aggregate_id(&self) -> String {
+        self.source.clone()
+    }
+
+    fn payload(&self) -> serde_json::Value {
+        serde_json::json!({
+            "source": self.source,
+            "message": self.message,
+        })
+    }
+}
+
+/// Rebuilds a stored event from its type tag and `payload()` JSON.
+pub fn decode_event(
+    event_type: &str,
+    payload: &serde_json::Value,
+) -> Result<Box<dyn DomainEvent>, DomainError> {
+    let str_field = |name: &str| payload.get(name).and_then(|v| v.as_str()).map(String::from);
+    let user_id = || {
+        payload
+            .get("user_id")
+            .and_then(|v| v.as_i64())
+            .map(UserId)
+            .ok_or_else(|| DomainError::ValidationError(format!("{} payload missing user_id", event_type)))
+    };
+
+    match event_type {
+        "user.created" => Ok(Box::new(UserCreatedEvent::new(
+            user_id()?,
+            str_field("name").unwrap_or_default(),
+            str_field("email").unwrap_or_default(),
+        ))),
+        "user.updated" => Ok(Box::new(
+            UserUpdatedEvent::new(user_id()?, str_field("name"), str_field("email"))
+                .with_role(str_field("role"))
+                .with_status(str_field("status")),
+        )),
+        "user.deleted" => Ok(Box::new(UserDeletedEvent::new(user_id()?))),
+        "application.started" => Ok(Box::new(ApplicationStartedEvent::new(
+            str_field("app_name").unwrap_or_default(),
+            str_field("version").unwrap_or_default(),
+        ))),
+        "application.shutdown" => Ok(Box::new(ApplicationShutdownEvent::new(
+            str_field("app_name").unwrap_or_default(),
+            str_field("reason"),
+        ))),
+        "application.error" => Ok(Box::new(ApplicationErrorEvent::new(
+            str_field("source").unwrap_or_default(),
+            str_field("message").unwrap_or_default(),
+        ))),
+        other => Err(DomainError::NotFound(format!("Unknown event type: {}", other))),
+    }
+}
@@ -2,6 +2,8 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::errors::DomainError;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct User {
     pub id: UserId,
@@ -10,6 +12,11 @@ pub struct User {
     pub role: UserRole,
     pub status: UserStatus,
     pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub external_id: Option<String>,
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -20,24 +27,168 @@ pub struct NewUser {
     pub name: String,
     pub email: Email,
     pub role: UserRole,
+    #[serde(default)]
+    pub external_id: Option<String>,
+}
+
+/// Which columns must be unique across users.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UniqueBy {
+    #[default]
+    Email,
+    ExternalId,
+    Both,
+}
+
+impl UniqueBy {
+    pub fn email(&self) -> bool {
+        matches!(self, UniqueBy::Email | UniqueBy::Both)
+    }
+
+    pub fn external_id(&self) -> bool {
+        matches!(self, UniqueBy::ExternalId | UniqueBy::Both)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Email(pub String);
 
+/// Why an address was rejected by `validate_email`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailError {
+    Empty,
+    ContainsWhitespace,
+    MissingAt,
+    MultipleAt,
+    EmptyLocalPart,
+    InvalidDomain,
+}
+
+impl std::fmt::Display for EmailError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EmailError::Empty => write!(f, "Email is empty"),
+            EmailError::ContainsWhitespace => write!(f, "Email must not contain spaces"),
+            EmailError::MissingAt => write!(f, "Email must contain '@'"),
+            EmailError::MultipleAt => write!(f, "Email must contain a single '@'"),
+            EmailError::EmptyLocalPart => write!(f, "Email needs a name before '@'"),
+            EmailError::InvalidDomain => {
+                write!(f, "Email domain must be dotted, like example.com")
+            }
+        }
+    }
+}
+
+impl std::error::Error for EmailError {}
+
+impl From<EmailError> for DomainError {
+    fn from(err: EmailError) -> Self {
+        DomainError::ValidationError(err.to_string())
+    }
+}
+
+/// The single email check shared by the repository and viewmodel models: a
+/// non-empty local part, exactly one `@`, and a domain of two or more
+/// non-empty dot-separated labels, with no whitespace anywhere.
+pub fn validate_email(email: &str) -> Result<(), EmailError> {
+    if email.is_empty() {
+        return Err(EmailError::Empty);
+    }
+    if email.chars().any(char::is_whitespace) {
+        return Err(EmailError::ContainsWhitespace);
+    }
+    let (local, domain) = email.split_once('@').ok_or(EmailError::MissingAt)?;
+    if domain.contains('@') {
+        return Err(EmailError::MultipleAt);
+    }
+    if local.is_empty() {
+        return Err(EmailError::EmptyLocalPart);
+    }
+    let labels: Vec<&str> = domain.split('.').collect();
+    if labels.len() < 2 || labels.iter().any(|label| label.is_empty()) {
+        return Err(EmailError::InvalidDomain);
+    }
+    Ok(())
+}
+
 impl Email {
-    pub fn new(email: &str) -> Result<Self, String> {
-        if email.contains('@') {
-            Ok(Email(email.to_string()))
+    pub fn new(email: &str) -> Result<Self, EmailError> {
+        validate_email(email)?;
+        Ok(Email(email.to_string()))
+    }
+
+    pub fn domain(&self) -> &str {
+        self.0.rsplit_once('@').map(|(_, domain)| domain).unwrap_or("")
+    }
+}
+
+/// Email domains users may register with. An empty list allows every domain.
+#[derive(Debug, Clone, Default)]
+pub struct EmailDomainAllowlist {
+    domains: Vec<String>,
+}
+
+impl EmailDomainAllowlist {
+    pub fn new(domains: &[String]) -> Self {
+        Self {
+            domains: domains
+                .iter()
+                .map(|d| d.trim().trim_start_matches('@').to_lowercase())
+                .filter(|d| !d.is_empty())
+                .collect(),
+        }
+    }
+
+    pub fn check(&self, email: &str) -> Result<(), String> {
+        if self.domains.is_empty() {
+            return Ok(());
+        }
+        let domain = email
+            .rsplit_once('@')
+            .map(|(_, domain)| domain.trim().to_lowercase())
+            .unwrap_or_default();
+        if self.domains.contains(&domain) {
+            Ok(())
         } else {
-            Err("Invalid email format".to_string())
+            Err(format!("Email domain '{}' is not allowed", domain))
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Avatar {
+    pub mime: String,
+    pub bytes: Vec<u8>,
+}
+
+impl Avatar {
+    pub const MAX_BYTES: usize = 512 * 1024;
+    pub const ALLOWED_TYPES: [&'static str; 4] = ["image/png", "image/jpeg", "image/gif", "image/webp"];
+
+    pub fn new(bytes: Vec<u8>, mime: &str) -> Result<Self, String> {
+        let mime = mime.trim().to_lowercase();
+        if !Self::ALLOWED_TYPES.contains(&mime.as_str()) {
+            return Err(format!("Unsupported avatar type: {}", mime));
+        }
+        if bytes.is_empty() {
+            return Err("Avatar is empty".to_string());
         }
+        if bytes.len() > Self::MAX_BYTES {
+            return Err(format!(
+                "Avatar is {} bytes, limit is {} bytes",
+                bytes.len(),
+                Self::MAX_BYTES
+            ));
+        }
+        Ok(Avatar { mime, bytes })
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum UserRole {
     Admin,
+    Editor,
     User,
     Guest,
 }
@@ -46,15 +197,19 @@ impl UserRole {
     pub fn as_str(&self) -> &'static str {
         match self {
             UserRole::Admin => "Admin",
+            UserRole::Editor => "Editor",
             UserRole::User => "User",
             UserRole::Guest => "Guest",
         }
     }
 
+    /// Case-insensitive, so form values like `"editor"` map the same as the
+    /// stored `"Editor"`. Anything unrecognised is a plain user.
     pub fn from_str(s: &str) -> Self {
-        match s {
-            "Admin" => UserRole::Admin,
-            "Guest" => UserRole::Guest,
+        match s.to_ascii_lowercase().as_str() {
+            "admin" => UserRole::Admin,
+            "editor" => UserRole::Editor,
+            "guest" => UserRole::Guest,
             _ => UserRole::User,
         }
     }
@@ -87,13 +242,53 @@ impl UserStatus {
 
 impl User {
     pub fn create(new_user: NewUser) -> Result<Self, String> {
+        let now = Utc::now();
         Ok(User {
             id: UserId(0),
             name: new_user.name,
             email: new_user.email,
             role: new_user.role,
             status: UserStatus::Active,
-            created_at: Utc::now(),
+            created_at: now,
+            updated_at: now,
+            external_id: new_user.external_id,
+            deleted_at: None,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_well_formed_addresses() {
+        for email in ["a@b.co", "ada.lovelace+tag@mail.example.com", "x@sub.domain.org"] {
+            assert_eq!(validate_email(email), Ok(()), "{}", email);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_addresses_with_a_typed_error() {
+        let cases = [
+            ("", EmailError::Empty),
+            ("ada @example.com", EmailError::ContainsWhitespace),
+            ("ada.example.com", EmailError::MissingAt),
+            ("a@@b.com", EmailError::MultipleAt),
+            ("@example.com", EmailError::EmptyLocalPart),
+            ("a@b", EmailError::InvalidDomain),
+            ("a@b.", EmailError::InvalidDomain),
+            ("a@.com", EmailError::InvalidDomain),
+        ];
+        for (email, expected) in cases {
+            assert_eq!(validate_email(email), Err(expected), "{}", email);
+        }
+    }
+
+    #[test]
+    fn both_email_types_share_the_check() {
+        assert!(Email::new("a@b").is_err());
+        assert!(crate::features::user::model::Email::new("a@b").is_err());
+        assert_eq!(Email::new("ada@example.com").unwrap().domain(), "example.com");
+    }
+}
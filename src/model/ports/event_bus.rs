@@ -1,9 +1,28 @@
 // core/ports/event_bus.rs
 use crate::core::domain::errors::DomainError;
-use crate::core::domain::events::DomainEvent;
+use crate::core::domain::events::{DomainEvent, EventMetadata};
 
 pub trait EventBus: Send + Sync {
+    /// Publishes with fresh metadata that carries the current correlation id.
     fn publish(&self, event: Box<dyn DomainEvent>) -> Result<(), DomainError>;
+
+    /// Publishes with caller-supplied metadata, e.g. to keep an event id or
+    /// set causation. Buses that cannot carry metadata ignore it.
+    fn publish_with_metadata(
+        &self,
+        event: Box<dyn DomainEvent>,
+        metadata: EventMetadata,
+    ) -> Result<(), DomainError> {
+        let _ = metadata;
+        self.publish(event)
+    }
+}
+
+/// Durable record of published events. The bus appends each event before
+/// running its handlers and acknowledges it once they all succeeded.
+pub trait EventJournal: Send + Sync {
+    fn append(&self, event: &dyn DomainEvent) -> Result<i64, DomainError>;
+    fn acknowledge(&self, id: i64) -> Result<bool, DomainError>;
 }
 
 pub trait EventHandler<E: DomainEvent>: Send + Sync {
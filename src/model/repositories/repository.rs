@@ -1,13 +1,31 @@
 // core/ports/repository.rs
 use async_trait::async_trait;
-use crate::core::domain::{user::{User, UserId, NewUser}, errors::DomainError};
+use crate::core::domain::{user::{Avatar, User, UserId, NewUser}, errors::DomainError};
+
+/// Edits a user in place for `UserRepository::modify`, returning whether
+/// anything changed.
+pub type UserEdit<'a> = Box<dyn FnOnce(&mut User) -> Result<bool, DomainError> + Send + 'a>;
 
 #[async_trait]
 pub trait UserRepository: Send + Sync {
+    /// Users that have not been soft-deleted.
     async fn get_all(&self) -> Result<Vec<User>, DomainError>;
+    /// Every user, soft-deleted ones included, for admin views.
+    async fn get_all_including_deleted(&self) -> Result<Vec<User>, DomainError>;
     async fn get_by_id(&self, id: UserId) -> Result<User, DomainError>;
     async fn create(&self, user: &NewUser) -> Result<UserId, DomainError>;
+    /// Inserts every user or none of them; ids are in input order.
+    async fn create_many(&self, users: &[NewUser]) -> Result<Vec<UserId>, DomainError>;
     async fn update(&self, user: &User) -> Result<(), DomainError>;
+    /// Reads the user, applies `edit` and writes it back as one transaction,
+    /// returning the stored row. Nothing is written if `edit` fails or
+    /// reports no change.
+    async fn modify(&self, id: UserId, edit: UserEdit<'_>) -> Result<User, DomainError>;
+    /// Soft-deletes: the row is kept, marked deleted and deactivated.
     async fn delete(&self, id: UserId) -> Result<(), DomainError>;
+    /// Undoes a soft delete; `NotFound` if the user is not deleted.
+    async fn restore(&self, id: UserId) -> Result<(), DomainError>;
     async fn count(&self) -> Result<i64, DomainError>;
+    async fn set_avatar(&self, id: UserId, bytes: &[u8], mime: &str) -> Result<(), DomainError>;
+    async fn get_avatar(&self, id: UserId) -> Result<Option<Avatar>, DomainError>;
 }
@@ -0,0 +1,234 @@
+// infrastructure/persistence/sqlite/audit_log.rs
+use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::core::domain::errors::DomainError;
+use crate::infrastructure::event_bus::InMemoryEventBus;
+use super::connection::SqliteDatabase;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditRecord {
+    pub id: i64,
+    pub occurred_at: String,
+    pub actor: String,
+    pub action: String,
+    pub target_id: Option<String>,
+    pub details: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AuditFilter {
+    pub actor: Option<String>,
+    pub action: Option<String>,
+    pub target_id: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub page: Option<usize>,
+    pub page_size: Option<usize>,
+}
+
+pub struct AuditLog {
+    db: Arc<SqliteDatabase>,
+}
+
+impl AuditLog {
+    pub const DEFAULT_PAGE_SIZE: usize = 50;
+    pub const MAX_PAGE_SIZE: usize = 500;
+    /// The user events that leave an audit record.
+    pub const EVENTS: [&'static str; 3] = ["user.created", "user.updated", "user.deleted"];
+
+    pub fn new(db: Arc<SqliteDatabase>) -> Self {
+        Self { db }
+    }
+
+    pub fn init_schema(&self) -> Result<(), DomainError> {
+        let conn = self.db.writer();
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS audit_log (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                occurred_at TEXT NOT NULL,
+                actor TEXT NOT NULL,
+                action TEXT NOT NULL,
+                target_id TEXT,
+                details TEXT
+            );
+            CREATE INDEX IF NOT EXISTS idx_audit_log_occurred_at ON audit_log (occurred_at);",
+        ).map_err(DomainError::from)?;
+        Ok(())
+    }
+
+    pub fn record(
+        &self,
+        actor: &str,
+        action: &str,
+        target_id: Option<&str>,
+        details: Option<&str>,
+    ) -> Result<i64, DomainError> {
+        let conn = self.db.writer();
+        conn.execute(
+            "INSERT INTO audit_log (occurred_at, actor, action, target_id, details)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![Utc::now().to_rfc3339(), actor, action, target_id, details],
+        ).map_err(DomainError::from)?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Returns matching records newest-first. Timestamps are stored as UTC
+    /// RFC 3339 strings, so range filters compare lexically.
+    pub fn query(&self, filter: &AuditFilter) -> Result<Vec<AuditRecord>, DomainError> {
+        let mut clauses = Vec::new();
+        let mut values: Vec<String> = Vec::new();
+
+        if let Some(actor) = &filter.actor {
+            values.push(actor.clone());
+            clauses.push(format!("actor = ?{}", values.len()));
+        }
+        if let Some(action) = &filter.action {
+            values.push(action.clone());
+            clauses.push(format!("action = ?{}", values.len()));
+        }
+        if let Some(target_id) = &filter.target_id {
+            values.push(target_id.clone());
+            clauses.push(format!("target_id = ?{}", values.len()));
+        }
+        if let Some(since) = &filter.since {
+            values.push(since.to_rfc3339());
+            clauses.push(format!("occurred_at >= ?{}", values.len()));
+        }
+        if let Some(until) = &filter.until {
+            values.push(until.to_rfc3339());
+            clauses.push(format!("occurred_at <= ?{}", values.len()));
+        }
+
+        let where_sql = if clauses.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", clauses.join(" AND "))
+        };
+
+        let page_size = filter
+            .page_size
+            .unwrap_or(Self::DEFAULT_PAGE_SIZE)
+            .clamp(1, Self::MAX_PAGE_SIZE);
+        let offset = filter.page.unwrap_or(1).max(1).saturating_sub(1) * page_size;
+
+        let sql = format!(
+            "SELECT id, occurred_at, actor, action, target_id, details FROM audit_log {}
+             ORDER BY occurred_at DESC, id DESC LIMIT {} OFFSET {}",
+            where_sql, page_size, offset
+        );
+
+        let conn = self.db.reader();
+        let mut stmt = conn.prepare(&sql).map_err(DomainError::from)?;
+        let records = stmt.query_map(rusqlite::params_from_iter(values.iter()), |row| {
+            Ok(AuditRecord {
+                id: row.get(0)?,
+                occurred_at: row.get(1)?,
+                actor: row.get(2)?,
+                action: row.get(3)?,
+                target_id: row.get(4)?,
+                details: row.get(5)?,
+            })
+        }).map_err(DomainError::from)?;
+
+        records.collect::<Result<Vec<_>, _>>()
+            .map_err(DomainError::from)
+    }
+
+    /// Records every user event published on `bus`, with the publisher's
+    /// source as the actor and the event payload as the details.
+    pub async fn attach(self: Arc<Self>, bus: &InMemoryEventBus) -> Vec<String> {
+        let mut subscription_ids = Vec::new();
+        for event_type in Self::EVENTS {
+            let audit_log = self.clone();
+            let id = bus
+                .subscribe_with_metadata(event_type, move |metadata, event_type, payload| {
+                    let target_id = payload.get("user_id").map(|id| match id {
+                        serde_json::Value::String(id) => id.clone(),
+                        id => id.to_string(),
+                    });
+                    let details = payload.to_string();
+                    if let Err(e) = audit_log.record(
+                        &metadata.source,
+                        &event_type,
+                        target_id.as_deref(),
+                        Some(&details),
+                    ) {
+                        log::warn!("Audit log failed to record {}: {}", event_type, e);
+                    }
+                })
+                .await;
+            subscription_ids.push(id);
+        }
+        subscription_ids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::domain::events::{EventMetadata, UserCreatedEvent, UserDeletedEvent};
+    use crate::core::domain::user::UserId;
+    use crate::core::ports::event_bus::EventBus;
+    use crate::core::ports::logger::LogLevel;
+    use crate::core::event_bus::EventBusConfig;
+    use crate::core::logging::StructuredLogger;
+
+    fn audit_log() -> Arc<AuditLog> {
+        let audit_log = AuditLog::new(Arc::new(SqliteDatabase::open(":memory:", 0).unwrap()));
+        audit_log.init_schema().unwrap();
+        Arc::new(audit_log)
+    }
+
+    fn filter(action: Option<&str>, target_id: Option<&str>) -> AuditFilter {
+        AuditFilter {
+            action: action.map(String::from),
+            target_id: target_id.map(String::from),
+            ..AuditFilter::default()
+        }
+    }
+
+    #[test]
+    fn query_filters_by_action_and_target() {
+        let audit_log = audit_log();
+        audit_log.record("admin", "user.created", Some("1"), None).unwrap();
+        audit_log.record("admin", "user.created", Some("2"), None).unwrap();
+        audit_log.record("admin", "user.deleted", Some("1"), None).unwrap();
+        audit_log.record("system", "config.reloaded", None, None).unwrap();
+
+        let created = audit_log.query(&filter(Some("user.created"), None)).unwrap();
+        assert_eq!(created.len(), 2);
+        assert!(created.iter().all(|r| r.action == "user.created"));
+
+        let user_one = audit_log.query(&filter(None, Some("1"))).unwrap();
+        let actions: Vec<_> = user_one.iter().map(|r| r.action.as_str()).collect();
+        assert_eq!(actions, ["user.deleted", "user.created"]);
+
+        let both = audit_log.query(&filter(Some("user.deleted"), Some("2"))).unwrap();
+        assert!(both.is_empty());
+    }
+
+    #[test]
+    fn attached_log_records_user_events() {
+        let audit_log = audit_log();
+        let bus = InMemoryEventBus::new(
+            EventBusConfig::new().with_logging(false),
+            StructuredLogger::new(LogLevel::Error, "audit_log_test"),
+        );
+        futures::executor::block_on(audit_log.clone().attach(&bus));
+
+        bus.publish_with_metadata(
+            Box::new(UserCreatedEvent::new(UserId(7), "Ada".into(), "ada@example.com".into())),
+            EventMetadata::new("user_service"),
+        )
+        .unwrap();
+        bus.publish(Box::new(UserDeletedEvent::new(UserId(7)))).unwrap();
+
+        let records = audit_log.query(&filter(None, Some("7"))).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].action, "user.created");
+        assert_eq!(records[1].actor, "user_service");
+        assert!(records[1].details.as_deref().unwrap().contains("ada@example.com"));
+        assert_eq!(records[0].action, "user.deleted");
+    }
+}
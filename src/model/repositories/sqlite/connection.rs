@@ -0,0 +1,516 @@
+// infrastructure/persistence/sqlite/connection.rs
+use std::ops::Deref;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, ThreadId};
+use r2d2::{Pool, PooledConnection};
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{Connection, OpenFlags};
+use crate::core::domain::errors::DomainError;
+
+pub type ReadPool = Pool<SqliteConnectionManager>;
+
+/// A single writer connection plus an r2d2 pool of read-only connections.
+///
+/// The database runs in WAL mode so readers never wait on the writer lock,
+/// and each read checks out its own pooled connection. In-memory databases
+/// cannot share state across connections, so they only ever get the writer.
+///
+/// A transaction belongs to the thread that opened it. While it is open,
+/// `writer()` callers on other threads wait for it to finish so their
+/// statements cannot leak into it; on the owning thread `writer()` hands out
+/// the connection, so repository calls and synchronous event handlers made
+/// inside the transaction join it. Opening a transaction on the owning thread
+/// nests it as a savepoint.
+///
+/// A scoped transaction (`begin_scoped_transaction`) belongs to a token
+/// instead, so it can stay open across `.await` points: only `writer_for`
+/// callers holding that token get the connection, on whichever thread they
+/// run.
+pub struct SqliteDatabase {
+    writer: Arc<Mutex<Connection>>,
+    readers: Option<ReadPool>,
+    tx: Mutex<TransactionState>,
+    tx_released: Condvar,
+    next_token: AtomicU64,
+}
+
+/// Identifies a scoped transaction; see `SqliteDatabase::begin_scoped_transaction`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransactionToken(u64);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TransactionOwner {
+    Thread(ThreadId),
+    Token(TransactionToken),
+}
+
+#[derive(Default)]
+struct TransactionState {
+    owner: Option<TransactionOwner>,
+    depth: usize,
+}
+
+impl TransactionState {
+    /// Whether a transaction this caller does not belong to is in progress:
+    /// one opened by another thread, or a scoped one whose token it lacks.
+    fn held_elsewhere(&self, token: Option<TransactionToken>) -> bool {
+        match self.owner {
+            None => false,
+            Some(TransactionOwner::Thread(owner)) => owner != thread::current().id(),
+            Some(TransactionOwner::Token(owner)) => Some(owner) != token,
+        }
+    }
+}
+
+impl SqliteDatabase {
+    /// Opens `path` with up to `read_pool_size` pooled read connections; zero
+    /// routes every read through the writer.
+    pub fn open(path: &str, read_pool_size: usize) -> Result<Self, DomainError> {
+        let pool = (read_pool_size > 0).then(|| Pool::builder().max_size(read_pool_size as u32));
+        Self::open_with(path, pool)
+    }
+
+    /// Like `open`, but builds the read pool from a caller-tuned r2d2 builder.
+    pub fn open_with(
+        path: &str,
+        read_pool: Option<r2d2::Builder<SqliteConnectionManager>>,
+    ) -> Result<Self, DomainError> {
+        let writer = Connection::open(path).map_err(DomainError::from)?;
+
+        let in_memory = path.is_empty() || path == ":memory:";
+        if in_memory {
+            return Ok(Self::from_connection(Arc::new(Mutex::new(writer))));
+        }
+
+        writer
+            .pragma_update(None, "journal_mode", "WAL")
+            .map_err(DomainError::from)?;
+
+        let readers = match read_pool {
+            Some(builder) => {
+                let manager = SqliteConnectionManager::file(path).with_flags(
+                    OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+                );
+                let pool = builder
+                    .build(manager)
+                    .map_err(|e| DomainError::InvalidOperation(e.to_string()))?;
+                Some(pool)
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            writer: Arc::new(Mutex::new(writer)),
+            readers,
+            tx: Mutex::new(TransactionState::default()),
+            tx_released: Condvar::new(),
+            next_token: AtomicU64::new(0),
+        })
+    }
+
+    /// Wraps an existing connection; every read and write goes through it.
+    pub fn from_connection(conn: Arc<Mutex<Connection>>) -> Self {
+        Self {
+            writer: conn,
+            readers: None,
+            tx: Mutex::new(TransactionState::default()),
+            tx_released: Condvar::new(),
+            next_token: AtomicU64::new(0),
+        }
+    }
+
+    pub fn writer(&self) -> MutexGuard<'_, Connection> {
+        self.writer_for(None)
+    }
+
+    /// The writer connection for a caller holding `token`, which gets it
+    /// while that scoped transaction is open. Everyone else waits, as with
+    /// `writer()`.
+    pub fn writer_for(&self, token: Option<TransactionToken>) -> MutexGuard<'_, Connection> {
+        let state = self.tx.lock().unwrap();
+        let state = self
+            .tx_released
+            .wait_while(state, |state| state.held_elsewhere(token))
+            .unwrap();
+        let conn = self.writer.lock().unwrap();
+        drop(state);
+        conn
+    }
+
+    /// Whether the calling thread has a transaction open.
+    pub fn in_transaction(&self) -> bool {
+        self.tx.lock().unwrap().owner == Some(TransactionOwner::Thread(thread::current().id()))
+    }
+
+    /// The writer connection as seen from inside an open transaction.
+    pub fn transaction_writer(&self) -> MutexGuard<'_, Connection> {
+        self.writer.lock().unwrap()
+    }
+
+    /// Opens a write transaction, waiting for other threads' transactions to
+    /// finish first. On a thread that already has one open, this opens a
+    /// savepoint inside it instead. Dropping the returned guard without
+    /// committing rolls it back.
+    pub fn begin_transaction(&self) -> Result<TransactionGuard<'_>, DomainError> {
+        self.begin(TransactionOwner::Thread(thread::current().id()))
+    }
+
+    /// Opens a write transaction owned by the guard's token rather than the
+    /// calling thread, for work that awaits while it is open. Only
+    /// `writer_for` callers passing `guard.token()` write inside it; every
+    /// other writer waits, even on this thread. On a thread that already has
+    /// a transaction open this nests as a savepoint and the token is `None`.
+    pub fn begin_scoped_transaction(&self) -> Result<TransactionGuard<'_>, DomainError> {
+        let token = TransactionToken(self.next_token.fetch_add(1, Ordering::Relaxed));
+        self.begin(TransactionOwner::Token(token))
+    }
+
+    fn begin(&self, owner: TransactionOwner) -> Result<TransactionGuard<'_>, DomainError> {
+        let (depth, token) = {
+            let state = self.tx.lock().unwrap();
+            let mut state = self
+                .tx_released
+                .wait_while(state, |state| state.held_elsewhere(None))
+                .unwrap();
+            if state.owner.is_some() {
+                state.depth += 1;
+            } else {
+                state.owner = Some(owner);
+            }
+            let token = match state.owner {
+                Some(TransactionOwner::Token(token)) => Some(token),
+                _ => None,
+            };
+            (state.depth, token)
+        };
+
+        let begin = match depth {
+            0 => "BEGIN IMMEDIATE".to_string(),
+            depth => format!("SAVEPOINT tx_{}", depth),
+        };
+        let begun = self
+            .transaction_writer()
+            .execute_batch(&begin)
+            .map_err(DomainError::from);
+        if let Err(e) = begun {
+            self.release_transaction(depth);
+            return Err(e);
+        }
+
+        Ok(TransactionGuard {
+            db: self,
+            depth,
+            token,
+            finished: false,
+        })
+    }
+
+    fn release_transaction(&self, depth: usize) {
+        let mut state = self.tx.lock().unwrap();
+        if depth == 0 {
+            *state = TransactionState::default();
+            self.tx_released.notify_all();
+        } else {
+            state.depth = depth - 1;
+        }
+    }
+
+    /// Checks out a read-only connection from the pool, waiting while all of
+    /// them are busy. Falls back to the writer when no read pool is configured
+    /// or the checkout times out, and inside a transaction on this thread so
+    /// reads see its uncommitted writes.
+    pub fn reader(&self) -> ReadConnection<'_> {
+        let pool = match &self.readers {
+            Some(pool) if !self.in_transaction() => pool,
+            _ => return ReadConnection::Writer(self.writer()),
+        };
+
+        match pool.get() {
+            Ok(conn) => ReadConnection::Pooled(conn),
+            Err(e) => {
+                log::warn!("Read pool checkout failed, using the writer: {}", e);
+                ReadConnection::Writer(self.writer())
+            }
+        }
+    }
+
+    pub fn read_pool_size(&self) -> usize {
+        self.readers.as_ref().map_or(0, |pool| pool.max_size() as usize)
+    }
+
+    pub fn ping(&self) -> Result<(), DomainError> {
+        self.reader()
+            .query_row("SELECT 1", [], |_| Ok(()))
+            .map_err(DomainError::from)
+    }
+
+    pub fn has_column(&self, table: &str, column: &str) -> Result<bool, DomainError> {
+        let conn = self.writer();
+        let mut stmt = conn
+            .prepare(&format!("PRAGMA table_info({})", table))
+            .map_err(DomainError::from)?;
+        let names = stmt
+            .query_map([], |row| row.get::<_, String>(1))
+            .map_err(DomainError::from)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(DomainError::from)?;
+        Ok(names.iter().any(|name| name == column))
+    }
+
+    pub fn has_table(&self, name: &str) -> Result<bool, DomainError> {
+        let count: i64 = self
+            .reader()
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+                [name],
+                |row| row.get(0),
+            )
+            .map_err(DomainError::from)?;
+        Ok(count > 0)
+    }
+}
+
+/// A connection handed out by `SqliteDatabase::reader`.
+pub enum ReadConnection<'a> {
+    Pooled(PooledConnection<SqliteConnectionManager>),
+    Writer(MutexGuard<'a, Connection>),
+}
+
+impl Deref for ReadConnection<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        match self {
+            ReadConnection::Pooled(conn) => conn,
+            ReadConnection::Writer(conn) => conn,
+        }
+    }
+}
+
+impl<'a> From<MutexGuard<'a, Connection>> for ReadConnection<'a> {
+    fn from(conn: MutexGuard<'a, Connection>) -> Self {
+        ReadConnection::Writer(conn)
+    }
+}
+
+/// An open transaction, or a savepoint when `depth > 0`.
+pub struct TransactionGuard<'a> {
+    db: &'a SqliteDatabase,
+    depth: usize,
+    token: Option<TransactionToken>,
+    finished: bool,
+}
+
+impl TransactionGuard<'_> {
+    /// The token a scoped transaction's writers pass to `writer_for`.
+    pub fn token(&self) -> Option<TransactionToken> {
+        self.token
+    }
+
+    fn execute(&self, sql: &str) -> Result<(), DomainError> {
+        self.db
+            .transaction_writer()
+            .execute_batch(sql)
+            .map_err(DomainError::from)
+    }
+
+    fn rollback_sql(&self) -> String {
+        match self.depth {
+            0 => "ROLLBACK".to_string(),
+            depth => format!("ROLLBACK TO tx_{0}; RELEASE tx_{0}", depth),
+        }
+    }
+
+    pub fn commit(mut self) -> Result<(), DomainError> {
+        self.finished = true;
+        let commit = match self.depth {
+            0 => "COMMIT".to_string(),
+            depth => format!("RELEASE tx_{}", depth),
+        };
+        let result = self.execute(&commit);
+        if result.is_err() {
+            let _ = self.execute(&self.rollback_sql());
+        }
+        self.db.release_transaction(self.depth);
+        result
+    }
+
+    pub fn rollback(mut self) -> Result<(), DomainError> {
+        self.finished = true;
+        let result = self.execute(&self.rollback_sql());
+        self.db.release_transaction(self.depth);
+        result
+    }
+}
+
+impl Drop for TransactionGuard<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            let _ = self.execute(&self.rollback_sql());
+            self.db.release_transaction(self.depth);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    fn temp_database(read_pool_size: usize) -> (SqliteDatabase, std::path::PathBuf) {
+        let path = std::env::temp_dir().join(format!("connection-test-{}.db", uuid::Uuid::new_v4()));
+        let db = SqliteDatabase::open(path.to_str().unwrap(), read_pool_size).unwrap();
+        db.writer()
+            .execute_batch("CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT NOT NULL)")
+            .unwrap();
+        (db, path)
+    }
+
+    fn remove_database(path: &std::path::Path) {
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    fn count(conn: &Connection) -> i64 {
+        conn.query_row("SELECT COUNT(*) FROM items", [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn reads_proceed_while_the_writer_is_held() {
+        let (db, path) = temp_database(2);
+        let db = Arc::new(db);
+        db.writer().execute("INSERT INTO items (name) VALUES ('a')", []).unwrap();
+
+        let writer = db.writer();
+        let (done_tx, done_rx) = mpsc::channel();
+        for _ in 0..2 {
+            let db = db.clone();
+            let done_tx = done_tx.clone();
+            thread::spawn(move || {
+                done_tx.send(count(&db.reader())).unwrap();
+            });
+        }
+        for _ in 0..2 {
+            let seen = done_rx.recv_timeout(Duration::from_secs(5)).expect("read blocked on the writer");
+            assert_eq!(seen, 1);
+        }
+        drop(writer);
+
+        remove_database(&path);
+    }
+
+    #[test]
+    fn writer_on_the_owning_thread_joins_the_transaction() {
+        let db = SqliteDatabase::open(":memory:", 0).unwrap();
+        db.writer()
+            .execute_batch("CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT NOT NULL)")
+            .unwrap();
+
+        let tx = db.begin_transaction().unwrap();
+        assert!(db.in_transaction());
+        db.writer().execute("INSERT INTO items (name) VALUES ('a')", []).unwrap();
+        assert_eq!(count(&db.reader()), 1);
+        tx.rollback().unwrap();
+
+        assert!(!db.in_transaction());
+        assert_eq!(count(&db.reader()), 0);
+    }
+
+    #[test]
+    fn reads_inside_a_transaction_see_its_writes() {
+        let (db, path) = temp_database(2);
+
+        let tx = db.begin_transaction().unwrap();
+        db.writer().execute("INSERT INTO items (name) VALUES ('a')", []).unwrap();
+        assert_eq!(count(&db.reader()), 1);
+        tx.rollback().unwrap();
+
+        assert_eq!(count(&db.reader()), 0);
+        remove_database(&path);
+    }
+
+    #[test]
+    fn other_threads_wait_for_the_transaction() {
+        let (db, path) = temp_database(0);
+        let db = Arc::new(db);
+
+        let tx = db.begin_transaction().unwrap();
+        db.writer().execute("INSERT INTO items (name) VALUES ('a')", []).unwrap();
+
+        let (done_tx, done_rx) = mpsc::channel();
+        let other = db.clone();
+        let handle = thread::spawn(move || {
+            other.writer().execute("INSERT INTO items (name) VALUES ('b')", []).unwrap();
+            done_tx.send(()).unwrap();
+        });
+        assert!(done_rx.recv_timeout(Duration::from_millis(100)).is_err());
+
+        tx.rollback().unwrap();
+        handle.join().unwrap();
+        assert_eq!(count(&db.writer()), 1);
+
+        remove_database(&path);
+    }
+
+    #[test]
+    fn scoped_transactions_admit_only_their_token() {
+        let (db, path) = temp_database(0);
+        let db = Arc::new(db);
+
+        let tx = db.begin_scoped_transaction().unwrap();
+        let token = tx.token();
+        assert!(token.is_some());
+        assert!(!db.in_transaction());
+
+        let holder = db.clone();
+        thread::spawn(move || {
+            holder.writer_for(token).execute("INSERT INTO items (name) VALUES ('a')", []).unwrap();
+        })
+        .join()
+        .unwrap();
+
+        let (done_tx, done_rx) = mpsc::channel();
+        let other = db.clone();
+        let handle = thread::spawn(move || {
+            other.writer().execute("INSERT INTO items (name) VALUES ('b')", []).unwrap();
+            done_tx.send(()).unwrap();
+        });
+        assert!(done_rx.recv_timeout(Duration::from_millis(100)).is_err());
+
+        tx.rollback().unwrap();
+        handle.join().unwrap();
+        assert_eq!(count(&db.writer()), 1);
+
+        remove_database(&path);
+    }
+
+    #[test]
+    fn nested_transactions_roll_back_to_their_savepoint() {
+        let db = SqliteDatabase::open(":memory:", 0).unwrap();
+        db.writer()
+            .execute_batch("CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT NOT NULL)")
+            .unwrap();
+
+        let outer = db.begin_transaction().unwrap();
+        db.writer().execute("INSERT INTO items (name) VALUES ('kept')", []).unwrap();
+
+        let inner = db.begin_transaction().unwrap();
+        db.writer().execute("INSERT INTO items (name) VALUES ('dropped')", []).unwrap();
+        inner.rollback().unwrap();
+        assert!(db.in_transaction());
+
+        outer.commit().unwrap();
+        let names: Vec<String> = db
+            .reader()
+            .prepare("SELECT name FROM items")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(names, vec!["kept".to_string()]);
+    }
+}
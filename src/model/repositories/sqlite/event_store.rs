@@ -0,0 +1,230 @@
+// infrastructure/persistence/sqlite/event_store.rs
+use std::sync::Arc;
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use serde::Serialize;
+use crate::core::domain::errors::DomainError;
+use crate::core::domain::events::DomainEvent;
+use crate::core::ports::event_bus::EventJournal;
+use super::connection::SqliteDatabase;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StoredEvent {
+    pub id: i64,
+    pub event_type: String,
+    pub aggregate_id: String,
+    pub payload: serde_json::Value,
+    pub occurred_at: String,
+    pub acknowledged: bool,
+}
+
+/// Limits applied by `EventStore::prune`. `None` disables that limit.
+#[derive(Debug, Clone, Default)]
+pub struct RetentionPolicy {
+    pub max_age_days: Option<u32>,
+    pub max_rows: Option<usize>,
+}
+
+/// Append-only log of published domain events. Consumers acknowledge events
+/// once processed; only acknowledged events are ever pruned.
+pub struct EventStore {
+    db: Arc<SqliteDatabase>,
+}
+
+fn timestamp(at: DateTime<Utc>) -> String {
+    // Fixed-width UTC so timestamps compare correctly as text.
+    at.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+impl EventStore {
+    pub fn new(db: Arc<SqliteDatabase>) -> Self {
+        Self { db }
+    }
+
+    pub fn init_schema(&self) -> Result<(), DomainError> {
+        let conn = self.db.writer();
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                event_type TEXT NOT NULL,
+                aggregate_id TEXT NOT NULL,
+                payload TEXT NOT NULL,
+                occurred_at TEXT NOT NULL,
+                acknowledged INTEGER NOT NULL DEFAULT 0
+            );
+            CREATE INDEX IF NOT EXISTS idx_events_occurred_at ON events(occurred_at);",
+        ).map_err(DomainError::from)?;
+        Ok(())
+    }
+
+    pub fn append(&self, event: &dyn DomainEvent) -> Result<i64, DomainError> {
+        let conn = self.db.writer();
+        conn.execute(
+            "INSERT INTO events (event_type, aggregate_id, payload, occurred_at) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![
+                event.event_type(),
+                event.aggregate_id(),
+                event.payload().to_string(),
+                timestamp(event.occurred_at()),
+            ],
+        ).map_err(DomainError::from)?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    pub fn acknowledge(&self, id: i64) -> Result<bool, DomainError> {
+        let conn = self.db.writer();
+        let changed = conn
+            .execute("UPDATE events SET acknowledged = 1 WHERE id = ?1", [id])
+            .map_err(DomainError::from)?;
+        Ok(changed > 0)
+    }
+
+    pub fn count(&self) -> Result<i64, DomainError> {
+        let conn = self.db.reader();
+        conn.query_row("SELECT COUNT(*) FROM events", [], |row| row.get(0))
+            .map_err(DomainError::from)
+    }
+
+    pub fn list(&self, limit: usize) -> Result<Vec<StoredEvent>, DomainError> {
+        let conn = self.db.reader();
+        let mut stmt = conn.prepare(
+            "SELECT id, event_type, aggregate_id, payload, occurred_at, acknowledged
+             FROM events ORDER BY id ASC LIMIT ?1"
+        ).map_err(DomainError::from)?;
+
+        let rows = stmt.query_map([limit as i64], |row| {
+            let payload: String = row.get(3)?;
+            Ok(StoredEvent {
+                id: row.get(0)?,
+                event_type: row.get(1)?,
+                aggregate_id: row.get(2)?,
+                payload: serde_json::from_str(&payload).unwrap_or(serde_json::Value::Null),
+                occurred_at: row.get(4)?,
+                acknowledged: row.get::<_, i64>(5)? != 0,
+            })
+        }).map_err(DomainError::from)?;
+
+        rows.collect::<Result<Vec<_>, _>>().map_err(DomainError::from)
+    }
+
+    /// Deletes acknowledged events older than `max_age_days`, then the oldest
+    /// acknowledged events while the table holds more than `max_rows`.
+    /// Returns how many rows were removed.
+    pub fn prune(&self, policy: &RetentionPolicy, now: DateTime<Utc>) -> Result<usize, DomainError> {
+        let tx = self.db.begin_transaction()?;
+        let mut pruned = 0;
+        {
+            let conn = self.db.transaction_writer();
+
+            if let Some(days) = policy.max_age_days {
+                let cutoff = timestamp(now - Duration::days(days as i64));
+                pruned += conn.execute(
+                    "DELETE FROM events WHERE acknowledged = 1 AND occurred_at < ?1",
+                    [cutoff],
+                ).map_err(DomainError::from)?;
+            }
+
+            if let Some(max_rows) = policy.max_rows {
+                let total: i64 = conn
+                    .query_row("SELECT COUNT(*) FROM events", [], |row| row.get(0))
+                    .map_err(DomainError::from)?;
+                let excess = total - max_rows as i64;
+                if excess > 0 {
+                    pruned += conn.execute(
+                        "DELETE FROM events WHERE id IN (
+                            SELECT id FROM events WHERE acknowledged = 1
+                            ORDER BY occurred_at ASC, id ASC LIMIT ?1
+                        )",
+                        [excess],
+                    ).map_err(DomainError::from)?;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(pruned)
+    }
+}
+
+impl EventJournal for EventStore {
+    fn append(&self, event: &dyn DomainEvent) -> Result<i64, DomainError> {
+        EventStore::append(self, event)
+    }
+
+    fn acknowledge(&self, id: i64) -> Result<bool, DomainError> {
+        EventStore::acknowledge(self, id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::domain::events::{UserCreatedEvent, UserDeletedEvent};
+    use crate::core::domain::user::UserId;
+    use crate::core::ports::event_bus::EventBus;
+    use crate::core::ports::logger::LogLevel;
+    use crate::core::event_bus::{EventBusConfig, InMemoryEventBus};
+    use crate::core::logging::StructuredLogger;
+
+    fn event_store() -> Arc<EventStore> {
+        let store = EventStore::new(Arc::new(SqliteDatabase::open(":memory:", 0).unwrap()));
+        store.init_schema().unwrap();
+        Arc::new(store)
+    }
+
+    fn deleted_at(user_id: i64, at: DateTime<Utc>) -> UserDeletedEvent {
+        UserDeletedEvent { user_id: UserId(user_id), occurred_at: at }
+    }
+
+    fn remaining(store: &EventStore) -> Vec<String> {
+        store.list(100).unwrap().into_iter().map(|e| e.aggregate_id).collect()
+    }
+
+    #[test]
+    fn prune_drops_old_acknowledged_events_only() {
+        let store = event_store();
+        let now = Utc::now();
+        let old = store.append(&deleted_at(1, now - Duration::days(40))).unwrap();
+        store.append(&deleted_at(2, now - Duration::days(40))).unwrap();
+        let recent = store.append(&deleted_at(3, now - Duration::days(1))).unwrap();
+        store.acknowledge(old).unwrap();
+        store.acknowledge(recent).unwrap();
+
+        let policy = RetentionPolicy { max_age_days: Some(30), max_rows: None };
+        assert_eq!(store.prune(&policy, now).unwrap(), 1);
+        assert_eq!(remaining(&store), ["2", "3"]);
+    }
+
+    #[test]
+    fn prune_trims_the_oldest_acknowledged_events_beyond_the_cap() {
+        let store = event_store();
+        let now = Utc::now();
+        for user_id in 1..=5 {
+            let id = store.append(&deleted_at(user_id, now - Duration::days(10 - user_id))).unwrap();
+            if user_id != 1 {
+                store.acknowledge(id).unwrap();
+            }
+        }
+
+        let policy = RetentionPolicy { max_age_days: None, max_rows: Some(3) };
+        assert_eq!(store.prune(&policy, now).unwrap(), 2);
+        assert_eq!(remaining(&store), ["1", "4", "5"]);
+    }
+
+    #[test]
+    fn journaled_events_are_acknowledged_once_handled() {
+        let store = event_store();
+        let bus = InMemoryEventBus::new(
+            EventBusConfig::new().with_logging(false),
+            StructuredLogger::new(LogLevel::Error, "event_store_test"),
+        );
+        bus.set_journal(store.clone());
+        futures::executor::block_on(bus.subscribe("user.deleted", |_, _| panic!("handler failed")));
+
+        bus.publish(Box::new(UserCreatedEvent::new(UserId(1), "Ada".into(), "ada@example.com".into())))
+            .unwrap();
+        bus.publish(Box::new(UserDeletedEvent::new(UserId(1)))).unwrap();
+
+        let events = store.list(10).unwrap();
+        let acknowledged: Vec<_> = events.iter().map(|e| (e.event_type.as_str(), e.acknowledged)).collect();
+        assert_eq!(acknowledged, [("user.created", true), ("user.deleted", false)]);
+    }
+}
@@ -0,0 +1,155 @@
+// infrastructure/persistence/sqlite/kv_store.rs
+use std::sync::Arc;
+use rusqlite::OptionalExtension;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use crate::core::domain::errors::DomainError;
+use super::connection::SqliteDatabase;
+
+/// Small persistent key-value store for application state such as first-run
+/// flags, window geometry, or idempotency keys.
+pub struct KvStore {
+    db: Arc<SqliteDatabase>,
+}
+
+impl KvStore {
+    pub fn new(db: Arc<SqliteDatabase>) -> Self {
+        Self { db }
+    }
+
+    pub fn init_schema(&self) -> Result<(), DomainError> {
+        let conn = self.db.writer();
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS kv (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )", [],
+        ).map_err(DomainError::from)?;
+        Ok(())
+    }
+
+    pub fn get(&self, key: &str) -> Result<Option<String>, DomainError> {
+        let conn = self.db.reader();
+        conn.query_row("SELECT value FROM kv WHERE key = ?1", [key], |row| row.get(0))
+            .optional()
+            .map_err(DomainError::from)
+    }
+
+    pub fn get_typed<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, DomainError> {
+        match self.get(key)? {
+            Some(raw) => serde_json::from_str(&raw)
+                .map(Some)
+                .map_err(|e| DomainError::InvalidOperation(format!(
+                    "Stored value for '{}' has unexpected shape: {}",
+                    key, e
+                ))),
+            None => Ok(None),
+        }
+    }
+
+    pub fn set(&self, key: &str, value: &str) -> Result<(), DomainError> {
+        let conn = self.db.writer();
+        let updated_at = chrono::Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO kv (key, value, updated_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+            [key, value, updated_at.as_str()],
+        ).map_err(DomainError::from)?;
+        Ok(())
+    }
+
+    pub fn set_typed<T: Serialize>(&self, key: &str, value: &T) -> Result<(), DomainError> {
+        let raw = serde_json::to_string(value)
+            .map_err(|e| DomainError::InvalidOperation(e.to_string()))?;
+        self.set(key, &raw)
+    }
+
+    pub fn delete(&self, key: &str) -> Result<bool, DomainError> {
+        let conn = self.db.writer();
+        let affected = conn.execute("DELETE FROM kv WHERE key = ?1", [key])
+            .map_err(DomainError::from)?;
+        Ok(affected > 0)
+    }
+
+    pub fn updated_at(&self, key: &str) -> Result<Option<chrono::DateTime<chrono::Utc>>, DomainError> {
+        let conn = self.db.reader();
+        let raw: Option<String> = conn
+            .query_row("SELECT updated_at FROM kv WHERE key = ?1", [key], |row| row.get(0))
+            .optional()
+            .map_err(DomainError::from)?;
+
+        raw.map(|s| {
+            chrono::DateTime::parse_from_rfc3339(&s)
+                .map(|dt| dt.with_timezone(&chrono::Utc))
+                .map_err(|e| DomainError::InvalidOperation(e.to_string()))
+        })
+        .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Geometry {
+        width: u32,
+        height: u32,
+    }
+
+    fn store() -> KvStore {
+        let store = KvStore::new(Arc::new(SqliteDatabase::open(":memory:", 0).unwrap()));
+        store.init_schema().unwrap();
+        store
+    }
+
+    #[test]
+    fn set_and_get_round_trip() {
+        let store = store();
+        assert_eq!(store.get("first_run").unwrap(), None);
+
+        store.set("first_run", "false").unwrap();
+        assert_eq!(store.get("first_run").unwrap().as_deref(), Some("false"));
+    }
+
+    #[test]
+    fn typed_values_round_trip_and_reject_wrong_shape() {
+        let store = store();
+        let geometry = Geometry { width: 1280, height: 800 };
+
+        store.set_typed("window", &geometry).unwrap();
+        assert_eq!(store.get_typed::<Geometry>("window").unwrap(), Some(geometry));
+
+        store.set("window", "\"not an object\"").unwrap();
+        assert!(matches!(
+            store.get_typed::<Geometry>("window"),
+            Err(DomainError::InvalidOperation(_))
+        ));
+    }
+
+    #[test]
+    fn overwrite_replaces_value_and_bumps_updated_at() {
+        let store = store();
+        store.set("theme", "light").unwrap();
+        let first = store.updated_at("theme").unwrap().unwrap();
+
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        store.set("theme", "dark").unwrap();
+
+        assert_eq!(store.get("theme").unwrap().as_deref(), Some("dark"));
+        assert!(store.updated_at("theme").unwrap().unwrap() > first);
+    }
+
+    #[test]
+    fn delete_reports_whether_the_key_existed() {
+        let store = store();
+        store.set("token", "abc").unwrap();
+
+        assert!(store.delete("token").unwrap());
+        assert!(!store.delete("token").unwrap());
+        assert_eq!(store.get("token").unwrap(), None);
+        assert_eq!(store.updated_at("token").unwrap(), None);
+    }
+}
@@ -0,0 +1,185 @@
+// infrastructure/persistence/sqlite/migrations.rs
+use std::path::Path;
+
+use rusqlite::Connection;
+use crate::core::domain::errors::DomainError;
+
+/// Built-in schema steps for the main database, applied in order. Append new
+/// steps; never edit or reorder ones that have shipped.
+pub const MIGRATIONS: &[(&str, &str)] = &[
+    (
+        "0001_create_users",
+        "CREATE TABLE IF NOT EXISTS users (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            email TEXT NOT NULL,
+            role TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'Active',
+            created_at TEXT NOT NULL,
+            external_id TEXT
+        );
+        CREATE TABLE IF NOT EXISTS avatar_blobs (
+            user_id INTEGER PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+            mime TEXT NOT NULL,
+            data BLOB NOT NULL,
+            updated_at TEXT NOT NULL
+        );",
+    ),
+    (
+        "0002_add_users_updated_at",
+        "ALTER TABLE users ADD COLUMN updated_at TEXT;
+        UPDATE users SET updated_at = created_at WHERE updated_at IS NULL;",
+    ),
+    (
+        "0003_add_users_deleted_at",
+        "ALTER TABLE users ADD COLUMN deleted_at TEXT;",
+    ),
+];
+
+fn ensure_tracking_table(conn: &Connection) -> Result<(), DomainError> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS schema_migrations (
+            version TEXT PRIMARY KEY,
+            applied_at TEXT NOT NULL
+        )",
+    ).map_err(DomainError::from)
+}
+
+fn is_applied(conn: &Connection, version: &str) -> Result<bool, DomainError> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM schema_migrations WHERE version = ?1)",
+        [version],
+        |row| row.get(0),
+    ).map_err(DomainError::from)
+}
+
+fn apply(conn: &Connection, version: &str, sql: &str) -> Result<(), DomainError> {
+    let tx = conn.unchecked_transaction().map_err(DomainError::from)?;
+    tx.execute_batch(sql).map_err(DomainError::from)?;
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?1, ?2)",
+        rusqlite::params![version, chrono::Utc::now().to_rfc3339()],
+    ).map_err(DomainError::from)?;
+    tx.commit().map_err(DomainError::from)
+}
+
+/// Applies each of `MIGRATIONS` that has not run yet, each in its own
+/// transaction, and returns how many were applied.
+pub fn run_migrations(conn: &Connection) -> Result<usize, DomainError> {
+    ensure_tracking_table(conn)?;
+
+    let mut applied = 0;
+    for (version, sql) in MIGRATIONS {
+        if is_applied(conn, version)? {
+            continue;
+        }
+        apply(conn, version, sql)?;
+        log::info!("Applied schema migration {}", version);
+        applied += 1;
+    }
+
+    Ok(applied)
+}
+
+/// Versions in `MIGRATIONS` that `conn` has not recorded as applied, in order.
+pub fn pending_migrations(conn: &Connection) -> Result<Vec<&'static str>, DomainError> {
+    let tracked: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'schema_migrations')",
+        [],
+        |row| row.get(0),
+    ).map_err(DomainError::from)?;
+    if !tracked {
+        return Ok(MIGRATIONS.iter().map(|(version, _)| *version).collect());
+    }
+
+    let mut pending = Vec::new();
+    for (version, _) in MIGRATIONS {
+        if !is_applied(conn, version)? {
+            pending.push(*version);
+        }
+    }
+    Ok(pending)
+}
+
+/// Applies every `*.sql` file in `dir` that has not run yet, in file name order.
+/// The file name (without extension) is recorded as the migration version.
+pub fn run_dir_migrations(conn: &Connection, dir: &Path) -> Result<usize, DomainError> {
+    ensure_tracking_table(conn)?;
+
+    let entries = std::fs::read_dir(dir).map_err(|e| {
+        DomainError::InvalidOperation(format!("Cannot read migrations in {}: {}", dir.display(), e))
+    })?;
+    let mut files: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "sql"))
+        .collect();
+    files.sort();
+
+    let mut applied = 0;
+    for path in files {
+        let version = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if is_applied(conn, &version)? {
+            continue;
+        }
+
+        let sql = std::fs::read_to_string(&path).map_err(|e| {
+            DomainError::InvalidOperation(format!("Cannot read {}: {}", path.display(), e))
+        })?;
+        apply(conn, &version, &sql)?;
+        applied += 1;
+    }
+
+    Ok(applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns(conn: &Connection, table: &str) -> Vec<String> {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table)).unwrap();
+        stmt.query_map([], |row| row.get(1))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn running_migrations_twice_applies_nothing_the_second_time() {
+        let conn = Connection::open_in_memory().unwrap();
+
+        assert_eq!(run_migrations(&conn).unwrap(), MIGRATIONS.len());
+        assert_eq!(run_migrations(&conn).unwrap(), 0);
+        assert!(pending_migrations(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn updated_at_migration_backfills_a_baseline_users_table() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE users (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                email TEXT NOT NULL,
+                role TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'Active',
+                created_at TEXT NOT NULL,
+                external_id TEXT
+            );
+            INSERT INTO users (name, email, role, created_at)
+            VALUES ('Ada', 'ada@example.com', 'User', '2024-01-02T03:04:05+00:00');",
+        )
+        .unwrap();
+
+        run_migrations(&conn).unwrap();
+
+        assert!(columns(&conn, "users").contains(&String::from("updated_at")));
+        let updated_at: String = conn
+            .query_row("SELECT updated_at FROM users WHERE name = 'Ada'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(updated_at, "2024-01-02T03:04:05+00:00");
+    }
+}
@@ -1,2 +1,9 @@
 // infrastructure/persistence/sqlite/mod.rs
+pub mod audit_log;
+pub mod connection;
+pub mod event_store;
+pub mod kv_store;
+pub mod migrations;
+pub mod state_bundle;
+pub mod user_projection;
 pub mod user_repository;
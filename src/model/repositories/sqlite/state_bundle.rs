@@ -0,0 +1,338 @@
+// infrastructure/persistence/sqlite/state_bundle.rs
+use std::path::Path;
+use std::sync::Arc;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use crate::core::domain::errors::DomainError;
+use super::connection::SqliteDatabase;
+
+/// Bumped whenever the bundle layout changes incompatibly.
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// kv keys containing any of these are treated as secrets and never exported.
+const SECRET_KEY_MARKERS: &[&str] = &["secret", "token", "password", "api_key", "private_key"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserRecord {
+    pub id: i64,
+    pub name: String,
+    pub email: String,
+    pub role: String,
+    pub status: String,
+    pub created_at: String,
+    /// Absent in bundles exported before the column existed.
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    pub external_id: Option<String>,
+    #[serde(default)]
+    pub deleted_at: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AvatarRecord {
+    pub user_id: i64,
+    pub mime: String,
+    /// Base64-encoded image bytes.
+    pub data: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KvRecord {
+    pub key: String,
+    pub value: String,
+    pub updated_at: String,
+}
+
+/// Portable snapshot of users, avatars and kv state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateBundle {
+    pub version: u32,
+    pub exported_at: String,
+    pub users: Vec<UserRecord>,
+    #[serde(default)]
+    pub avatars: Vec<AvatarRecord>,
+    #[serde(default)]
+    pub kv: Vec<KvRecord>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportMode {
+    /// Upsert bundle rows, keeping rows the bundle doesn't mention.
+    Merge,
+    /// Clear existing state first so the result matches the bundle exactly.
+    Replace,
+}
+
+pub fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    SECRET_KEY_MARKERS.iter().any(|marker| key.contains(marker))
+}
+
+pub struct StateTransfer {
+    db: Arc<SqliteDatabase>,
+}
+
+impl StateTransfer {
+    pub fn new(db: Arc<SqliteDatabase>) -> Self {
+        Self { db }
+    }
+
+    pub fn snapshot(&self) -> Result<StateBundle, DomainError> {
+        let conn = self.db.reader();
+
+        let users = conn
+            .prepare("SELECT id, name, email, role, status, created_at, updated_at, external_id, deleted_at FROM users ORDER BY id")
+            .and_then(|mut stmt| {
+                stmt.query_map([], |row| {
+                    Ok(UserRecord {
+                        id: row.get(0)?,
+                        name: row.get(1)?,
+                        email: row.get(2)?,
+                        role: row.get(3)?,
+                        status: row.get(4)?,
+                        created_at: row.get(5)?,
+                        updated_at: row.get(6)?,
+                        external_id: row.get(7)?,
+                        deleted_at: row.get(8)?,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(DomainError::from)?;
+
+        let avatars = conn
+            .prepare("SELECT user_id, mime, data, updated_at FROM avatar_blobs ORDER BY user_id")
+            .and_then(|mut stmt| {
+                stmt.query_map([], |row| {
+                    let bytes: Vec<u8> = row.get(2)?;
+                    Ok(AvatarRecord {
+                        user_id: row.get(0)?,
+                        mime: row.get(1)?,
+                        data: base64::engine::general_purpose::STANDARD.encode(bytes),
+                        updated_at: row.get(3)?,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(DomainError::from)?;
+
+        let kv = conn
+            .prepare("SELECT key, value, updated_at FROM kv ORDER BY key")
+            .and_then(|mut stmt| {
+                stmt.query_map([], |row| {
+                    Ok(KvRecord {
+                        key: row.get(0)?,
+                        value: row.get(1)?,
+                        updated_at: row.get(2)?,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(DomainError::from)?
+            .into_iter()
+            .filter(|record| !is_secret_key(&record.key))
+            .collect();
+
+        Ok(StateBundle {
+            version: BUNDLE_VERSION,
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            users,
+            avatars,
+            kv,
+        })
+    }
+
+    pub fn export_state(&self, path: &Path) -> Result<StateBundle, DomainError> {
+        let bundle = self.snapshot()?;
+        let json = serde_json::to_string_pretty(&bundle)
+            .map_err(|e| DomainError::InvalidOperation(e.to_string()))?;
+        std::fs::write(path, json).map_err(|e| {
+            DomainError::InvalidOperation(format!("Cannot write {}: {}", path.display(), e))
+        })?;
+        Ok(bundle)
+    }
+
+    pub fn import_state(&self, path: &Path, mode: ImportMode) -> Result<StateBundle, DomainError> {
+        let raw = std::fs::read_to_string(path).map_err(|e| {
+            DomainError::InvalidOperation(format!("Cannot read {}: {}", path.display(), e))
+        })?;
+        let version = serde_json::from_str::<serde_json::Value>(&raw)
+            .map_err(|e| DomainError::ValidationError(format!("Invalid state bundle: {}", e)))?
+            .get("version")
+            .and_then(|v| v.as_u64());
+        if version != Some(BUNDLE_VERSION as u64) {
+            return Err(DomainError::ValidationError(format!(
+                "Unsupported state bundle version {:?}; expected {}",
+                version, BUNDLE_VERSION
+            )));
+        }
+        let bundle: StateBundle = serde_json::from_str(&raw)
+            .map_err(|e| DomainError::ValidationError(format!("Invalid state bundle: {}", e)))?;
+        self.restore(&bundle, mode)?;
+        Ok(bundle)
+    }
+
+    /// Applies a bundle in one transaction; nothing changes if any row fails.
+    pub fn restore(&self, bundle: &StateBundle, mode: ImportMode) -> Result<(), DomainError> {
+        let tx = self.db.begin_transaction()?;
+        {
+            let conn = self.db.transaction_writer();
+
+            if mode == ImportMode::Replace {
+                conn.execute_batch("DELETE FROM avatar_blobs; DELETE FROM users; DELETE FROM kv;")
+                    .map_err(DomainError::from)?;
+            }
+
+            for user in &bundle.users {
+                conn.execute(
+                    // Upsert on id only: OR REPLACE would also delete whichever
+                    // row holds a clashing email instead of failing the import.
+                    "INSERT INTO users (id, name, email, role, status, created_at, updated_at, external_id, deleted_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, COALESCE(?7, ?6), ?8, ?9)
+                     ON CONFLICT(id) DO UPDATE SET
+                        name = excluded.name,
+                        email = excluded.email,
+                        role = excluded.role,
+                        status = excluded.status,
+                        created_at = excluded.created_at,
+                        updated_at = excluded.updated_at,
+                        external_id = excluded.external_id,
+                        deleted_at = excluded.deleted_at",
+                    rusqlite::params![
+                        user.id,
+                        user.name,
+                        user.email,
+                        user.role,
+                        user.status,
+                        user.created_at,
+                        user.updated_at,
+                        user.external_id,
+                        user.deleted_at,
+                    ],
+                ).map_err(DomainError::from)?;
+            }
+
+            for avatar in &bundle.avatars {
+                let bytes = base64::engine::general_purpose::STANDARD
+                    .decode(&avatar.data)
+                    .map_err(|e| DomainError::ValidationError(format!(
+                        "Invalid avatar data for user {}: {}",
+                        avatar.user_id, e
+                    )))?;
+                conn.execute(
+                    "INSERT OR REPLACE INTO avatar_blobs (user_id, mime, data, updated_at)
+                     VALUES (?1, ?2, ?3, ?4)",
+                    rusqlite::params![avatar.user_id, avatar.mime, bytes, avatar.updated_at],
+                ).map_err(DomainError::from)?;
+            }
+
+            for record in &bundle.kv {
+                conn.execute(
+                    "INSERT OR REPLACE INTO kv (key, value, updated_at) VALUES (?1, ?2, ?3)",
+                    [&record.key, &record.value, &record.updated_at],
+                ).map_err(DomainError::from)?;
+            }
+        }
+        tx.commit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use crate::core::domain::user::{Email, NewUser, UniqueBy, UserRole};
+    use crate::core::ports::repository::UserRepository;
+    use super::super::kv_store::KvStore;
+    use super::super::user_repository::SqliteUserRepository;
+
+    fn database() -> Arc<SqliteDatabase> {
+        let db = Arc::new(SqliteDatabase::open(":memory:", 0).unwrap());
+        let repo = SqliteUserRepository::with_database(db.clone());
+        repo.init_schema().unwrap();
+        repo.apply_unique_by(UniqueBy::Email).unwrap();
+        KvStore::new(db.clone()).init_schema().unwrap();
+        db
+    }
+
+    fn add_user(db: &Arc<SqliteDatabase>, name: &str) -> i64 {
+        let repo = SqliteUserRepository::with_database(db.clone());
+        let id = block_on(repo.create(&NewUser {
+            name: name.to_string(),
+            email: Email::new(&format!("{}@example.com", name.to_lowercase())).unwrap(),
+            role: UserRole::User,
+            external_id: None,
+        }))
+        .unwrap();
+        id.0
+    }
+
+    fn temp_path() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("state-bundle-test-{}.json", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn export_then_import_into_a_fresh_database_reproduces_the_state() {
+        let source = database();
+        let ada = add_user(&source, "Ada");
+        add_user(&source, "Grace");
+        source
+            .writer()
+            .execute(
+                "INSERT INTO avatar_blobs (user_id, mime, data, updated_at) VALUES (?1, 'image/png', ?2, '2024-01-01T00:00:00+00:00')",
+                rusqlite::params![ada, vec![1u8, 2, 3]],
+            )
+            .unwrap();
+        let kv = KvStore::new(source.clone());
+        kv.set("first_run", "false").unwrap();
+        kv.set("api_token", "hidden").unwrap();
+
+        let path = temp_path();
+        let exported = StateTransfer::new(source).export_state(&path).unwrap();
+        let target = database();
+        let imported = StateTransfer::new(target.clone()).import_state(&path, ImportMode::Replace);
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(imported.unwrap(), exported);
+        let restored = StateTransfer::new(target).snapshot().unwrap();
+        assert_eq!(restored.users, exported.users);
+        assert_eq!(restored.avatars, exported.avatars);
+        assert_eq!(restored.kv, exported.kv);
+        assert_eq!(restored.users.len(), 2);
+        assert_eq!(restored.avatars.len(), 1);
+        assert!(restored.kv.iter().all(|record| record.key != "api_token"));
+    }
+
+    #[test]
+    fn bundle_with_another_version_is_rejected() {
+        let db = database();
+        let mut bundle = StateTransfer::new(db.clone()).snapshot().unwrap();
+        bundle.version = BUNDLE_VERSION + 1;
+        let path = temp_path();
+        std::fs::write(&path, serde_json::to_string(&bundle).unwrap()).unwrap();
+
+        let result = StateTransfer::new(db).import_state(&path, ImportMode::Merge);
+        let _ = std::fs::remove_file(&path);
+
+        assert!(matches!(result, Err(DomainError::ValidationError(_))));
+    }
+
+    #[test]
+    fn merge_fails_on_an_email_held_by_another_user() {
+        let db = database();
+        add_user(&db, "Ada");
+        let grace = add_user(&db, "Grace");
+        let transfer = StateTransfer::new(db.clone());
+        let mut bundle = transfer.snapshot().unwrap();
+        let clash = bundle.users.iter_mut().find(|user| user.id == grace).unwrap();
+        clash.email = "ada@example.com".to_string();
+
+        let result = transfer.restore(&bundle, ImportMode::Merge);
+
+        assert!(result.is_err());
+        let emails: Vec<String> = transfer.snapshot().unwrap().users.into_iter().map(|u| u.email).collect();
+        assert_eq!(emails, ["ada@example.com", "grace@example.com"]);
+        assert!(!db.in_transaction());
+    }
+}
@@ -0,0 +1,206 @@
+// infrastructure/persistence/sqlite/user_projection.rs
+use std::sync::Arc;
+use serde::Serialize;
+use crate::core::domain::errors::DomainError;
+use crate::infrastructure::event_bus::InMemoryEventBus;
+use super::connection::SqliteDatabase;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UserListItem {
+    pub id: i64,
+    pub name: String,
+    pub email: String,
+    pub role: String,
+    pub status: String,
+    pub created_at: String,
+    pub external_id: Option<String>,
+}
+
+/// Denormalized copy of the user list, kept current from `user.*` events so
+/// listing users never touches the `users` table.
+pub struct UserListProjection {
+    db: Arc<SqliteDatabase>,
+}
+
+impl UserListProjection {
+    pub const EVENTS: [&'static str; 3] = ["user.created", "user.updated", "user.deleted"];
+
+    pub fn new(db: Arc<SqliteDatabase>) -> Self {
+        Self { db }
+    }
+
+    pub fn init_schema(&self) -> Result<(), DomainError> {
+        // Derived data: an outdated layout is dropped and rebuilt from `users`.
+        if self.db.has_table("user_list_projection")?
+            && !self.db.has_column("user_list_projection", "external_id")?
+        {
+            self.db
+                .writer()
+                .execute("DROP TABLE user_list_projection", [])
+                .map_err(DomainError::from)?;
+        }
+
+        let conn = self.db.writer();
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS user_list_projection (
+                id INTEGER PRIMARY KEY,
+                name TEXT NOT NULL,
+                email TEXT NOT NULL,
+                role TEXT NOT NULL,
+                status TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                external_id TEXT
+            )", [],
+        ).map_err(DomainError::from)?;
+        Ok(())
+    }
+
+    /// Replaces the projection with the current contents of `users`.
+    pub fn rebuild(&self) -> Result<usize, DomainError> {
+        let tx = self.db.begin_transaction()?;
+        let rows = {
+            let conn = self.db.transaction_writer();
+            conn.execute("DELETE FROM user_list_projection", [])
+                .map_err(DomainError::from)?;
+            conn.execute(
+                "INSERT INTO user_list_projection (id, name, email, role, status, created_at, external_id)
+                 SELECT id, name, email, role, status, created_at, external_id FROM users
+                 WHERE deleted_at IS NULL",
+                [],
+            ).map_err(DomainError::from)?
+        };
+        tx.commit()?;
+        Ok(rows)
+    }
+
+    pub fn apply(&self, event_type: &str, payload: &serde_json::Value) -> Result<(), DomainError> {
+        let user_id = payload
+            .get("user_id")
+            .and_then(|v| v.as_i64())
+            .ok_or_else(|| DomainError::ValidationError(format!("{} payload missing user_id", event_type)))?;
+        let conn = self.db.writer();
+
+        match event_type {
+            "user.created" => {
+                let copied = conn.execute(
+                    "INSERT OR REPLACE INTO user_list_projection (id, name, email, role, status, created_at, external_id)
+                     SELECT id, name, email, role, status, created_at, external_id FROM users WHERE id = ?1",
+                    [user_id],
+                ).map_err(DomainError::from)?;
+                if copied == 0 {
+                    conn.execute(
+                        "INSERT OR REPLACE INTO user_list_projection (id, name, email, role, status, created_at)
+                         VALUES (?1, ?2, ?3, 'User', 'Active', ?4)",
+                        rusqlite::params![
+                            user_id,
+                            payload.get("name").and_then(|v| v.as_str()).unwrap_or_default(),
+                            payload.get("email").and_then(|v| v.as_str()).unwrap_or_default(),
+                            chrono::Utc::now().to_rfc3339(),
+                        ],
+                    ).map_err(DomainError::from)?;
+                }
+            }
+            "user.updated" => {
+                conn.execute(
+                    "UPDATE user_list_projection
+                     SET name = COALESCE(?2, name), email = COALESCE(?3, email),
+                         role = COALESCE(?4, role), status = COALESCE(?5, status)
+                     WHERE id = ?1",
+                    rusqlite::params![
+                        user_id,
+                        payload.get("name").and_then(|v| v.as_str()),
+                        payload.get("email").and_then(|v| v.as_str()),
+                        payload.get("role").and_then(|v| v.as_str()),
+                        payload.get("status").and_then(|v| v.as_str()),
+                    ],
+                ).map_err(DomainError::from)?;
+            }
+            "user.deleted" => {
+                conn.execute("DELETE FROM user_list_projection WHERE id = ?1", [user_id])
+                    .map_err(DomainError::from)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    pub fn list(&self) -> Result<Vec<UserListItem>, DomainError> {
+        let conn = self.db.reader();
+        let mut stmt = conn.prepare(
+            "SELECT id, name, email, role, status, created_at, external_id
+             FROM user_list_projection ORDER BY id"
+        ).map_err(DomainError::from)?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok(UserListItem {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                email: row.get(2)?,
+                role: row.get(3)?,
+                status: row.get(4)?,
+                created_at: row.get(5)?,
+                external_id: row.get(6)?,
+            })
+        }).map_err(DomainError::from)?;
+
+        rows.collect::<Result<Vec<_>, _>>().map_err(DomainError::from)
+    }
+
+    /// Subscribes the projection to the user events it tracks.
+    pub async fn attach(self: Arc<Self>, bus: &InMemoryEventBus) -> Vec<String> {
+        let mut subscription_ids = Vec::new();
+        for event_type in Self::EVENTS {
+            let projection = self.clone();
+            let id = bus
+                .subscribe(event_type, move |event_type, payload| {
+                    if let Err(e) = projection.apply(&event_type, &payload) {
+                        log::warn!("User list projection failed to apply {}: {}", event_type, e);
+                    }
+                })
+                .await;
+            subscription_ids.push(id);
+        }
+        subscription_ids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::domain::user::{Email, NewUser, UserRole};
+    use crate::features::user::testing::persisted;
+    use futures::executor::block_on;
+
+    fn new_user(name: &str, email: &str) -> NewUser {
+        NewUser {
+            name: name.to_string(),
+            email: Email(email.to_string()),
+            role: UserRole::User,
+            external_id: None,
+        }
+    }
+
+    #[test]
+    fn user_created_updates_the_projection() {
+        let persisted = persisted();
+        assert!(persisted.projection.list().unwrap().is_empty());
+
+        let id = block_on(persisted.service.create_user(new_user("Ada", "ada@example.com"))).unwrap();
+
+        let items = persisted.projection.list().unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!((items[0].id, items[0].name.as_str()), (id.0, "Ada"));
+        assert_eq!(items[0].email, "ada@example.com");
+    }
+
+    #[test]
+    fn listing_reads_the_projection_not_the_base_table() {
+        let persisted = persisted();
+        block_on(persisted.service.create_user(new_user("Ada", "ada@example.com"))).unwrap();
+
+        persisted.db.writer().execute("DELETE FROM users", []).unwrap();
+        assert_eq!(persisted.projection.list().unwrap().len(), 1);
+
+        assert_eq!(persisted.projection.rebuild().unwrap(), 0);
+        assert!(persisted.projection.list().unwrap().is_empty());
+    }
+}
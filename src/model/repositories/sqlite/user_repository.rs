@@ -1,115 +1,861 @@
 // infrastructure/persistence/sqlite/user_repository.rs
-use std::sync::{Arc, Mutex};
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
 use async_trait::async_trait;
 use rusqlite::Connection;
-use crate::core::domain::{user::{User, UserId, NewUser, Email, UserRole, UserStatus}, errors::DomainError};
-use crate::core::ports::repository::UserRepository;
+use crate::core::domain::{user::{Avatar, User, UserId, NewUser, Email, UniqueBy, UserRole, UserStatus}, errors::DomainError};
+use rusqlite::OptionalExtension;
+use crate::core::ports::repository::{UserEdit, UserRepository};
+use crate::core::trace;
+use super::connection::{ReadConnection, SqliteDatabase, TransactionToken};
+use super::migrations::run_migrations;
 
 pub struct SqliteUserRepository {
-    conn: Arc<Mutex<Connection>>,
+    db: Arc<SqliteDatabase>,
+    pin_to_writer: bool,
+    in_transaction: bool,
+    token: Option<TransactionToken>,
 }
 
 impl SqliteUserRepository {
     pub fn new(conn: Arc<Mutex<Connection>>) -> Self {
-        Self { conn }
+        Self::with_database(Arc::new(SqliteDatabase::from_connection(conn)))
+    }
+
+    pub fn with_database(db: Arc<SqliteDatabase>) -> Self {
+        Self {
+            db,
+            pin_to_writer: false,
+            in_transaction: false,
+            token: None,
+        }
+    }
+
+    /// Returns a view of this repository that also reads through the writer
+    /// connection, for callers that must see their own writes immediately.
+    pub fn on_writer(&self) -> Self {
+        Self {
+            db: self.db.clone(),
+            pin_to_writer: true,
+            in_transaction: self.in_transaction,
+            token: self.token,
+        }
+    }
+
+    /// Runs `work` inside a single write transaction, committing if it returns
+    /// `Ok` and rolling back otherwise. The transaction belongs to the
+    /// repository handed to `work`, not the thread, so it may be awaited on any
+    /// executor; only that repository writes while it is open, and anything
+    /// else writing on this thread in the meantime blocks, so publish events
+    /// after this returns. Calling this again on the handed-out repository
+    /// reuses the same transaction instead of opening a new one.
+    pub async fn with_unit_of_work<F, Fut, R>(&self, work: F) -> Result<R, DomainError>
+    where
+        F: FnOnce(SqliteUserRepository) -> Fut,
+        Fut: Future<Output = Result<R, DomainError>>,
+    {
+        if self.in_transaction {
+            return work(Self {
+                db: self.db.clone(),
+                pin_to_writer: true,
+                in_transaction: true,
+                token: self.token,
+            })
+            .await;
+        }
+
+        let tx = self.db.begin_scoped_transaction()?;
+        let tx_repo = Self {
+            db: self.db.clone(),
+            pin_to_writer: true,
+            in_transaction: true,
+            token: tx.token(),
+        };
+        match work(tx_repo).await {
+            Ok(value) => {
+                tx.commit()?;
+                Ok(value)
+            }
+            Err(e) => {
+                if let Err(rollback_err) = tx.rollback() {
+                    log::warn!("Rollback after failed unit of work also failed: {}", rollback_err);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Runs `work` against the writer connection inside one transaction,
+    /// committing if it returns `Ok` and rolling back otherwise. For
+    /// multi-statement SQL; `with_unit_of_work` composes repository calls.
+    /// Inside an open unit of work the enclosing transaction is reused.
+    pub fn with_transaction<F, R>(&self, work: F) -> Result<R, DomainError>
+    where
+        F: FnOnce(&Connection) -> Result<R, DomainError>,
+    {
+        if self.in_transaction {
+            return work(&self.db.writer_for(self.token));
+        }
+
+        let tx = self.db.begin_transaction()?;
+        let result = work(&self.db.transaction_writer());
+        match result {
+            Ok(value) => {
+                tx.commit()?;
+                Ok(value)
+            }
+            Err(e) => {
+                if let Err(rollback_err) = tx.rollback() {
+                    log::warn!("Rollback after failed transaction also failed: {}", rollback_err);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// A pooled read connection per call, or the writer when this view is
+    /// pinned to it or scoped to a transaction.
+    fn read_conn(&self) -> ReadConnection<'_> {
+        if self.in_transaction {
+            self.db.writer_for(self.token).into()
+        } else if self.pin_to_writer {
+            self.db.writer().into()
+        } else {
+            self.db.reader()
+        }
+    }
+
+    fn write_conn(&self) -> MutexGuard<'_, Connection> {
+        if self.in_transaction {
+            self.db.writer_for(self.token)
+        } else {
+            self.db.writer()
+        }
     }
     
+    /// Brings the users table up to date: runs pending migrations, then
+    /// upgrades databases created before migrations existed. Uniqueness is
+    /// left to `apply_unique_by`, which callers run next.
     pub fn init_schema(&self) -> Result<(), DomainError> {
-        let conn = self.conn.lock().unwrap();
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS users (
+        run_migrations(&self.db.writer())?;
+
+        if !self.db.has_column("users", "external_id")? {
+            self.db
+                .writer()
+                .execute("ALTER TABLE users ADD COLUMN external_id TEXT", [])
+                .map_err(DomainError::from)?;
+        }
+        self.drop_inline_email_constraint()
+    }
+
+    /// Older databases declared `email ... UNIQUE` inline, which cannot be
+    /// dropped in place; rebuild the table so uniqueness is index-driven. The
+    /// rebuild recreates that guarantee as the email index, so emails stay
+    /// unique until `apply_unique_by` says otherwise.
+    fn drop_inline_email_constraint(&self) -> Result<(), DomainError> {
+        let sql: String = self.db.writer().query_row(
+            "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = 'users'",
+            [],
+            |row| row.get(0),
+        ).map_err(DomainError::from)?;
+        if !sql.contains("UNIQUE") {
+            return Ok(());
+        }
+
+        let tx = self.db.begin_transaction()?;
+        self.db.transaction_writer().execute_batch(
+            "CREATE TABLE users_rebuild (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
                 name TEXT NOT NULL,
-                email TEXT NOT NULL UNIQUE,
+                email TEXT NOT NULL,
                 role TEXT NOT NULL,
                 status TEXT NOT NULL DEFAULT 'Active',
-                created_at TEXT NOT NULL
-            )", [],
-        ).map_err(|e| DomainError::from(e))?;
-        Ok(())
+                created_at TEXT NOT NULL,
+                external_id TEXT,
+                updated_at TEXT,
+                deleted_at TEXT
+            );
+            INSERT INTO users_rebuild (id, name, email, role, status, created_at, external_id, updated_at, deleted_at)
+                SELECT id, name, email, role, status, created_at, external_id, updated_at, deleted_at FROM users;
+            DROP TABLE users;
+            ALTER TABLE users_rebuild RENAME TO users;
+            CREATE UNIQUE INDEX idx_users_email_unique ON users(email);",
+        ).map_err(DomainError::from)?;
+        tx.commit()
+    }
+
+    fn select_users(&self, filter: &str) -> Result<Vec<User>, DomainError> {
+        let conn = self.read_conn();
+        let mut stmt = conn.prepare(
+            &format!("SELECT {} FROM users {} ORDER BY id", USER_COLUMNS, filter)
+        ).map_err(DomainError::from)?;
+
+        let users = stmt.query_map([], user_from_row).map_err(DomainError::from)?;
+
+        users.collect::<Result<Vec<_>, _>>()
+            .map_err(DomainError::from)
     }
+
+    /// Creates or drops the unique indexes so exactly the columns selected by
+    /// `unique_by` are enforced. Fails if existing rows already collide.
+    pub fn apply_unique_by(&self, unique_by: UniqueBy) -> Result<(), DomainError> {
+        let conn = self.db.writer();
+        let email_index = if unique_by.email() {
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_users_email_unique ON users(email);"
+        } else {
+            "DROP INDEX IF EXISTS idx_users_email_unique;"
+        };
+        let external_id_index = if unique_by.external_id() {
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_users_external_id_unique ON users(external_id);"
+        } else {
+            "DROP INDEX IF EXISTS idx_users_external_id_unique;"
+        };
+        conn.execute_batch(&format!("{}\n{}", email_index, external_id_index))
+            .map_err(DomainError::from)
+    }
+}
+
+const USER_COLUMNS: &str =
+    "id, name, email, role, status, created_at, external_id, updated_at, deleted_at";
+
+fn timestamp_at(row: &rusqlite::Row<'_>, idx: usize) -> rusqlite::Result<chrono::DateTime<chrono::Utc>> {
+    let raw: String = row.get(idx)?;
+    chrono::DateTime::parse_from_rfc3339(&raw)
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+        .map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e))
+        })
+}
+
+/// Maps a `SELECT {USER_COLUMNS}` row. A corrupt timestamp fails the row as a
+/// conversion error, which surfaces as `DomainError::InvalidOperation`
+/// instead of panicking. Rows never updated fall back to `created_at`.
+fn user_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<User> {
+    let created_at = timestamp_at(row, 5)?;
+    let updated_at = match row.get::<_, Option<String>>(7)? {
+        Some(_) => timestamp_at(row, 7)?,
+        None => created_at,
+    };
+    let deleted_at = match row.get::<_, Option<String>>(8)? {
+        Some(_) => Some(timestamp_at(row, 8)?),
+        None => None,
+    };
+
+    Ok(User {
+        id: UserId(row.get(0)?),
+        name: row.get(1)?,
+        email: Email(row.get(2)?),
+        role: UserRole::from_str(&row.get::<_, String>(3)?),
+        status: UserStatus::from_str(&row.get::<_, String>(4)?),
+        created_at,
+        updated_at,
+        external_id: row.get(6)?,
+        deleted_at,
+    })
+}
+
+/// Inserts an active user stamped `created_at`. The statement is cached on
+/// the connection, so bulk inserts prepare it once.
+fn insert_user(conn: &Connection, user: &NewUser, created_at: &str) -> Result<UserId, DomainError> {
+    conn.prepare_cached(
+        "INSERT INTO users (name, email, role, status, created_at, updated_at, external_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?5, ?6)",
+    )?
+    .execute(rusqlite::params![
+        user.name,
+        user.email.0,
+        user.role.as_str(),
+        UserStatus::Active.as_str(),
+        created_at,
+        user.external_id,
+    ])?;
+
+    Ok(UserId(conn.last_insert_rowid()))
 }
 
 #[async_trait]
 impl UserRepository for SqliteUserRepository {
     async fn get_all(&self) -> Result<Vec<User>, DomainError> {
-        let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare(
-            "SELECT id, name, email, role, status, created_at FROM users ORDER BY id"
-        ).map_err(|e| DomainError::from(e))?;
-        
-        let users = stmt.query_map([], |row| {
-            let email_str: String = row.get(2)?;
-            Ok(User {
-                id: UserId(row.get(0)?),
-                name: row.get(1)?,
-                email: Email(email_str),
-                role: UserRole::from_str(&row.get::<_, String>(3)?),
-                status: UserStatus::from_str(&row.get::<_, String>(4)?),
-                created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(5)?)
-                    .unwrap()
-                    .with_timezone(&chrono::Utc),
-            })
-        }).map_err(|e| DomainError::from(e))?;
-        
-        users.collect::<Result<Vec<_>, _>>()
-            .map_err(|e| DomainError::from(e))
+        self.select_users("WHERE deleted_at IS NULL")
+    }
+
+    async fn get_all_including_deleted(&self) -> Result<Vec<User>, DomainError> {
+        self.select_users("")
     }
     
     async fn get_by_id(&self, id: UserId) -> Result<User, DomainError> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.read_conn();
         let mut stmt = conn.prepare(
-            "SELECT id, name, email, role, status, created_at FROM users WHERE id = ?1"
-        ).map_err(|e| DomainError::from(e))?;
+            &format!("SELECT {} FROM users WHERE id = ?1 AND deleted_at IS NULL", USER_COLUMNS)
+        ).map_err(DomainError::from)?;
         
-        stmt.query_row([id.0], |row| {
-            let email_str: String = row.get(2)?;
-            Ok(User {
-                id: UserId(row.get(0)?),
-                name: row.get(1)?,
-                email: Email(email_str),
-                role: UserRole::from_str(&row.get::<_, String>(3)?),
-                status: UserStatus::from_str(&row.get::<_, String>(4)?),
-                created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(5)?)
-                    .unwrap()
-                    .with_timezone(&chrono::Utc),
-            })
-        }).map_err(|e| DomainError::from(e))
+        stmt.query_row([id.0], user_from_row).map_err(DomainError::from)
     }
     
     async fn create(&self, user: &NewUser) -> Result<UserId, DomainError> {
-        let conn = self.conn.lock().unwrap();
+        let _span = trace::enter("db.insert");
+        let conn = self.write_conn();
+        insert_user(&conn, user, &chrono::Utc::now().to_rfc3339())
+    }
+    
+    async fn create_many(&self, users: &[NewUser]) -> Result<Vec<UserId>, DomainError> {
+        let _span = trace::enter("db.insert_many");
         let created_at = chrono::Utc::now().to_rfc3339();
-        
-        conn.execute(
-            "INSERT INTO users (name, email, role, status, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
-            [
-                &user.name,
-                &user.email.0,
+        self.with_transaction(|conn| {
+            users
+                .iter()
+                .map(|user| insert_user(conn, user, &created_at))
+                .collect()
+        })
+    }
+
+    async fn update(&self, user: &User) -> Result<(), DomainError> {
+        let _span = trace::enter("db.update");
+        let conn = self.write_conn();
+        // A UNIQUE email clash surfaces as `AlreadyExists` through `From`.
+        let updated = conn.execute(
+            "UPDATE users SET name = ?1, email = ?2, role = ?3, status = ?4, updated_at = ?5
+             WHERE id = ?6",
+            rusqlite::params![
+                user.name,
+                user.email.0,
                 user.role.as_str(),
-                UserStatus::Active.as_str(),
-                &created_at,
+                user.status.as_str(),
+                chrono::Utc::now().to_rfc3339(),
+                user.id.0,
             ],
-        ).map_err(|e| DomainError::from(e))?;
-        
-        Ok(UserId(conn.last_insert_rowid()))
-    }
-    
-    async fn update(&self, _user: &User) -> Result<(), DomainError> {
-        // Implementation omitted for brevity
+        ).map_err(DomainError::from)?;
+
+        if updated == 0 {
+            return Err(DomainError::NotFound(format!("User {}", user.id.0)));
+        }
         Ok(())
     }
     
+    async fn modify(&self, id: UserId, edit: UserEdit<'_>) -> Result<User, DomainError> {
+        self.with_unit_of_work(|tx_repo| async move {
+            let mut user = tx_repo.get_by_id(id).await?;
+            if !edit(&mut user)? {
+                return Ok(user);
+            }
+            tx_repo.update(&user).await?;
+            // Re-read for the stored updated_at.
+            tx_repo.get_by_id(id).await
+        })
+        .await
+    }
+
     async fn delete(&self, id: UserId) -> Result<(), DomainError> {
-        let conn = self.conn.lock().unwrap();
-        conn.execute("DELETE FROM users WHERE id = ?1", [id.0])
-            .map_err(|e| DomainError::from(e))?;
+        let _span = trace::enter("db.delete");
+        let conn = self.write_conn();
+        let deleted = conn.execute(
+            "UPDATE users SET deleted_at = ?1, updated_at = ?1, status = ?2
+             WHERE id = ?3 AND deleted_at IS NULL",
+            rusqlite::params![chrono::Utc::now().to_rfc3339(), UserStatus::Inactive.as_str(), id.0],
+        ).map_err(DomainError::from)?;
+
+        if deleted == 0 {
+            return Err(DomainError::NotFound(format!("User {}", id.0)));
+        }
+        Ok(())
+    }
+
+    async fn restore(&self, id: UserId) -> Result<(), DomainError> {
+        let conn = self.write_conn();
+        let restored = conn.execute(
+            "UPDATE users SET deleted_at = NULL, updated_at = ?1, status = ?2
+             WHERE id = ?3 AND deleted_at IS NOT NULL",
+            rusqlite::params![chrono::Utc::now().to_rfc3339(), UserStatus::Active.as_str(), id.0],
+        ).map_err(DomainError::from)?;
+
+        if restored == 0 {
+            return Err(DomainError::NotFound(format!("Deleted user {}", id.0)));
+        }
         Ok(())
     }
     
     async fn count(&self) -> Result<i64, DomainError> {
-        let conn = self.conn.lock().unwrap();
-        conn.query_row("SELECT COUNT(*) FROM users", [], |row| row.get(0))
+        let conn = self.read_conn();
+        conn.query_row("SELECT COUNT(*) FROM users WHERE deleted_at IS NULL", [], |row| row.get(0))
             .map_err(|e| DomainError::from(e))
     }
+    
+    async fn set_avatar(&self, id: UserId, bytes: &[u8], mime: &str) -> Result<(), DomainError> {
+        let avatar = Avatar::new(bytes.to_vec(), mime)
+            .map_err(DomainError::ValidationError)?;
+
+        let conn = self.write_conn();
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM users WHERE id = ?1)", [id.0], |row| row.get(0)
+        ).map_err(DomainError::from)?;
+        if !exists {
+            return Err(DomainError::NotFound(format!("User {}", id.0)));
+        }
+
+        conn.execute(
+            "INSERT INTO avatar_blobs (user_id, mime, data, updated_at) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(user_id) DO UPDATE SET mime = excluded.mime, data = excluded.data, updated_at = excluded.updated_at",
+            rusqlite::params![id.0, avatar.mime, avatar.bytes, chrono::Utc::now().to_rfc3339()],
+        ).map_err(DomainError::from)?;
+        Ok(())
+    }
+    
+    async fn get_avatar(&self, id: UserId) -> Result<Option<Avatar>, DomainError> {
+        let conn = self.read_conn();
+        conn.query_row(
+            "SELECT mime, data FROM avatar_blobs WHERE user_id = ?1", [id.0],
+            |row| Ok(Avatar { mime: row.get(0)?, bytes: row.get(1)? }),
+        ).optional().map_err(DomainError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{mpsc, Barrier};
+    use std::thread;
+    use std::time::Duration;
+
+    fn repository() -> SqliteUserRepository {
+        let repo = SqliteUserRepository::with_database(Arc::new(SqliteDatabase::open(":memory:", 0).unwrap()));
+        repo.init_schema().unwrap();
+        repo
+    }
+
+    fn new_user(name: &str) -> NewUser {
+        NewUser {
+            name: name.to_string(),
+            email: Email::new(&format!("{}@example.com", name.to_lowercase())).unwrap(),
+            role: UserRole::User,
+            external_id: None,
+        }
+    }
+
+    #[test]
+    fn unit_of_work_commits_every_step_on_success() {
+        let repo = repository();
+
+        let id = block_on(repo.with_unit_of_work(|tx_repo| async move {
+            let id = tx_repo.create(&new_user("Ada")).await?;
+            let mut user = tx_repo.get_by_id(id).await?;
+            user.role = UserRole::Admin;
+            tx_repo.update(&user).await?;
+            Ok(id)
+        }))
+        .unwrap();
+
+        let stored = block_on(repo.get_by_id(id)).unwrap();
+        assert_eq!(stored.role, UserRole::Admin);
+    }
+
+    #[test]
+    fn unit_of_work_failing_midway_persists_nothing() {
+        let repo = repository();
+
+        let result: Result<(), DomainError> = block_on(repo.with_unit_of_work(|tx_repo| async move {
+            let id = tx_repo.create(&new_user("Ada")).await?;
+            let mut user = tx_repo.get_by_id(id).await?;
+            user.role = UserRole::Admin;
+            tx_repo.update(&user).await?;
+            tx_repo.create(&new_user("Grace")).await?;
+            Err(DomainError::InvalidOperation("tagging failed".to_string()))
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(block_on(repo.count()).unwrap(), 0);
+        assert!(!repo.db.in_transaction());
+    }
+
+    #[test]
+    fn nested_unit_of_work_reuses_the_outer_transaction() {
+        let repo = repository();
+
+        let result: Result<(), DomainError> = block_on(repo.with_unit_of_work(|tx_repo| async move {
+            tx_repo
+                .with_unit_of_work(|inner| async move { inner.create(&new_user("Ada")).await })
+                .await?;
+            assert_eq!(tx_repo.count().await?, 1);
+            Err(DomainError::InvalidOperation("outer step failed".to_string()))
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(block_on(repo.count()).unwrap(), 0);
+    }
+
+    #[test]
+    fn unit_of_work_belongs_to_its_repository_not_the_thread() {
+        let repo = Arc::new(repository());
+        let (done_tx, done_rx) = mpsc::channel();
+        let done_rx = &done_rx;
+
+        let outsider = {
+            let repo = repo.clone();
+            block_on(repo.clone().with_unit_of_work(|tx_repo| async move {
+                let tx_repo = thread::spawn(move || {
+                    block_on(tx_repo.create(&new_user("Ada"))).unwrap();
+                    tx_repo
+                })
+                .join()
+                .unwrap();
+
+                let outsider = thread::spawn(move || {
+                    block_on(repo.create(&new_user("Grace"))).unwrap();
+                    done_tx.send(()).unwrap();
+                });
+                assert!(done_rx.recv_timeout(Duration::from_millis(100)).is_err());
+                assert_eq!(tx_repo.count().await?, 1);
+                Ok(outsider)
+            }))
+            .unwrap()
+        };
+
+        outsider.join().unwrap();
+        assert_eq!(block_on(repo.count()).unwrap(), 2);
+    }
+
+    #[test]
+    fn modify_writes_nothing_when_the_edit_fails() {
+        let repo = repository();
+        let id = block_on(repo.create(&new_user("Ada"))).unwrap();
+
+        let result = block_on(repo.modify(
+            id,
+            Box::new(|user| {
+                user.name = "Grace".to_string();
+                Err(DomainError::ValidationError("rejected".to_string()))
+            }),
+        ));
+
+        assert!(result.is_err());
+        assert_eq!(block_on(repo.get_by_id(id)).unwrap().name, "Ada");
+        let renamed = block_on(repo.modify(
+            id,
+            Box::new(|user| {
+                user.name = "Grace".to_string();
+                Ok(true)
+            }),
+        ))
+        .unwrap();
+        assert_eq!(renamed.name, "Grace");
+    }
+
+    /// Smallest valid PNG: signature plus a 1x1 IHDR, IDAT and IEND.
+    const PNG: &[u8] = &[
+        0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44, 0x52,
+        0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00, 0x1f, 0x15, 0xc4,
+        0x89, 0x00, 0x00, 0x00, 0x0a, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9c, 0x63, 0x00, 0x01, 0x00, 0x00,
+        0x05, 0x00, 0x01, 0x0d, 0x0a, 0x2d, 0xb4, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4e, 0x44, 0xae,
+        0x42, 0x60, 0x82,
+    ];
+
+    #[test]
+    fn avatar_round_trips_a_small_png() {
+        let repo = repository();
+        let id = block_on(repo.create(&new_user("Ada"))).unwrap();
+        assert!(block_on(repo.get_avatar(id)).unwrap().is_none());
+
+        block_on(repo.set_avatar(id, PNG, "Image/PNG")).unwrap();
+
+        let avatar = block_on(repo.get_avatar(id)).unwrap().unwrap();
+        assert_eq!(avatar.mime, "image/png");
+        assert_eq!(avatar.bytes, PNG);
+    }
+
+    #[test]
+    fn avatar_rejects_oversized_or_disallowed_uploads() {
+        let repo = repository();
+        let id = block_on(repo.create(&new_user("Ada"))).unwrap();
+
+        let oversized = vec![0u8; Avatar::MAX_BYTES + 1];
+        assert!(matches!(
+            block_on(repo.set_avatar(id, &oversized, "image/png")),
+            Err(DomainError::ValidationError(_))
+        ));
+        assert!(matches!(
+            block_on(repo.set_avatar(id, PNG, "image/svg+xml")),
+            Err(DomainError::ValidationError(_))
+        ));
+        assert!(matches!(
+            block_on(repo.set_avatar(UserId(999), PNG, "image/png")),
+            Err(DomainError::NotFound(_))
+        ));
+        assert!(block_on(repo.get_avatar(id)).unwrap().is_none());
+    }
+
+    fn user_with(name: &str, external_id: &str) -> NewUser {
+        NewUser {
+            external_id: Some(external_id.to_string()),
+            ..new_user(name)
+        }
+    }
+
+    /// Whether a second user with the same email, then one with the same
+    /// external id, is rejected under `unique_by` (`None` = never applied).
+    fn rejected_duplicates(unique_by: Option<UniqueBy>) -> (bool, bool) {
+        let repo = repository();
+        if let Some(unique_by) = unique_by {
+            repo.apply_unique_by(unique_by).unwrap();
+        }
+        block_on(repo.create(&user_with("Ada", "ext-1"))).unwrap();
+
+        let same_email = NewUser {
+            name: "Other Ada".to_string(),
+            ..user_with("Ada", "ext-2")
+        };
+        let same_external_id = user_with("Grace", "ext-1");
+        let rejected = |user: &NewUser| match block_on(repo.create(user)) {
+            Ok(_) => false,
+            Err(DomainError::AlreadyExists(_)) => true,
+            Err(e) => panic!("unexpected error: {}", e),
+        };
+        (rejected(&same_email), rejected(&same_external_id))
+    }
+
+    #[test]
+    fn unique_by_enforces_exactly_the_selected_columns() {
+        assert_eq!(rejected_duplicates(Some(UniqueBy::Email)), (true, false));
+        assert_eq!(rejected_duplicates(Some(UniqueBy::ExternalId)), (false, true));
+        assert_eq!(rejected_duplicates(Some(UniqueBy::Both)), (true, true));
+        assert_eq!(rejected_duplicates(None), (false, false));
+    }
+
+    #[test]
+    fn switching_unique_by_drops_the_old_index() {
+        let repo = repository();
+        repo.apply_unique_by(UniqueBy::Email).unwrap();
+        repo.apply_unique_by(UniqueBy::ExternalId).unwrap();
+
+        block_on(repo.create(&user_with("Ada", "ext-1"))).unwrap();
+        block_on(repo.create(&user_with("Ada", "ext-2"))).unwrap();
+        assert!(matches!(
+            block_on(repo.create(&user_with("Grace", "ext-1"))),
+            Err(DomainError::AlreadyExists(_))
+        ));
+    }
+
+    #[test]
+    fn rebuilding_an_inline_unique_email_keeps_emails_unique() {
+        let db = Arc::new(SqliteDatabase::open(":memory:", 0).unwrap());
+        db.writer()
+            .execute_batch(
+                "CREATE TABLE users (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    name TEXT NOT NULL,
+                    email TEXT NOT NULL UNIQUE,
+                    role TEXT NOT NULL,
+                    status TEXT NOT NULL DEFAULT 'Active',
+                    created_at TEXT NOT NULL
+                )",
+            )
+            .unwrap();
+        let repo = SqliteUserRepository::with_database(db.clone());
+        repo.init_schema().unwrap();
+
+        let sql: String = db
+            .reader()
+            .query_row("SELECT sql FROM sqlite_master WHERE name = 'users'", [], |row| row.get(0))
+            .unwrap();
+        assert!(!sql.contains("UNIQUE"));
+        block_on(repo.create(&new_user("Ada"))).unwrap();
+        assert!(matches!(
+            block_on(repo.create(&new_user("Ada"))),
+            Err(DomainError::AlreadyExists(_))
+        ));
+
+        repo.apply_unique_by(UniqueBy::ExternalId).unwrap();
+        block_on(repo.create(&new_user("Ada"))).unwrap();
+    }
+
+    #[test]
+    fn update_persists_name_and_email() {
+        let repo = repository();
+        let id = block_on(repo.create(&new_user("Ada"))).unwrap();
+
+        let mut user = block_on(repo.get_by_id(id)).unwrap();
+        let (created_at, updated_at) = (user.created_at, user.updated_at);
+        user.name = "Ada Lovelace".to_string();
+        user.email = Email("lovelace@example.com".to_string());
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        block_on(repo.update(&user)).unwrap();
+
+        let stored = block_on(repo.get_by_id(id)).unwrap();
+        assert_eq!(stored.name, "Ada Lovelace");
+        assert_eq!(stored.email.0, "lovelace@example.com");
+        assert_eq!(stored.created_at, created_at);
+        assert!(stored.updated_at > updated_at);
+    }
+
+    #[test]
+    fn editor_role_round_trips() {
+        let repo = repository();
+        let id = block_on(repo.create(&NewUser {
+            role: UserRole::Editor,
+            ..new_user("Ada")
+        }))
+        .unwrap();
+
+        assert_eq!(block_on(repo.get_by_id(id)).unwrap().role, UserRole::Editor);
+        let stored: String = repo
+            .db
+            .writer()
+            .query_row("SELECT role FROM users WHERE id = ?1", [id.0], |row| row.get(0))
+            .unwrap();
+        assert_eq!(stored, "Editor");
+    }
+
+    #[test]
+    fn update_reports_missing_users_and_email_clashes() {
+        let repo = repository();
+        repo.apply_unique_by(UniqueBy::Email).unwrap();
+        block_on(repo.create(&new_user("Ada"))).unwrap();
+        let grace = block_on(repo.create(&new_user("Grace"))).unwrap();
+
+        let mut user = block_on(repo.get_by_id(grace)).unwrap();
+        user.email = Email("ada@example.com".to_string());
+        assert!(matches!(block_on(repo.update(&user)), Err(DomainError::AlreadyExists(_))));
+
+        user.id = UserId(999);
+        assert!(matches!(block_on(repo.update(&user)), Err(DomainError::NotFound(_))));
+    }
+
+    #[test]
+    fn malformed_created_at_is_an_error_not_a_panic() {
+        let repo = repository();
+        let id = block_on(repo.create(&new_user("Ada"))).unwrap();
+        repo.db
+            .writer()
+            .execute("UPDATE users SET created_at = 'not a timestamp' WHERE id = ?1", [id.0])
+            .unwrap();
+
+        assert!(matches!(block_on(repo.get_by_id(id)), Err(DomainError::InvalidOperation(_))));
+        assert!(matches!(block_on(repo.get_all()), Err(DomainError::InvalidOperation(_))));
+    }
+
+    #[test]
+    fn soft_delete_hides_users_until_restored() {
+        let repo = repository();
+        let ada = block_on(repo.create(&new_user("Ada"))).unwrap();
+        block_on(repo.create(&new_user("Grace"))).unwrap();
+
+        block_on(repo.delete(ada)).unwrap();
+        let visible: Vec<String> = block_on(repo.get_all()).unwrap().into_iter().map(|u| u.name).collect();
+        assert_eq!(visible, ["Grace"]);
+        assert!(matches!(block_on(repo.get_by_id(ada)), Err(DomainError::NotFound(_))));
+        assert_eq!(block_on(repo.count()).unwrap(), 1);
+
+        let deleted = block_on(repo.get_all_including_deleted())
+            .unwrap()
+            .into_iter()
+            .find(|u| u.id == ada)
+            .unwrap();
+        assert!(deleted.deleted_at.is_some());
+        assert_eq!(deleted.status, UserStatus::Inactive);
+
+        block_on(repo.restore(ada)).unwrap();
+        let restored = block_on(repo.get_by_id(ada)).unwrap();
+        assert!(restored.deleted_at.is_none());
+        assert_eq!(restored.status, UserStatus::Active);
+        assert!(matches!(block_on(repo.restore(ada)), Err(DomainError::NotFound(_))));
+    }
+
+    #[test]
+    fn deleting_a_missing_or_already_deleted_user_is_not_found() {
+        let repo = repository();
+        let ada = block_on(repo.create(&new_user("Ada"))).unwrap();
+
+        assert!(matches!(block_on(repo.delete(UserId(ada.0 + 1))), Err(DomainError::NotFound(_))));
+        block_on(repo.delete(ada)).unwrap();
+        assert!(matches!(block_on(repo.delete(ada)), Err(DomainError::NotFound(_))));
+    }
+
+    /// Once armed, parks every read checkout until `barrier` fills, so reads
+    /// only get through while they hold pooled connections at the same time.
+    #[derive(Debug)]
+    struct RendezvousOnCheckout {
+        armed: Arc<AtomicBool>,
+        barrier: Arc<Barrier>,
+    }
+
+    impl r2d2::HandleEvent for RendezvousOnCheckout {
+        fn handle_checkout(&self, _event: r2d2::event::CheckoutEvent) {
+            if self.armed.load(Ordering::SeqCst) {
+                self.barrier.wait();
+            }
+        }
+    }
+
+    #[test]
+    fn concurrent_get_all_calls_do_not_serialize() {
+        const READERS: usize = 4;
+        let path = std::env::temp_dir().join(format!("user-repo-pool-{}.db", uuid::Uuid::new_v4()));
+        let armed = Arc::new(AtomicBool::new(false));
+        let pool = r2d2::Pool::builder()
+            .max_size(READERS as u32)
+            .event_handler(Box::new(RendezvousOnCheckout {
+                armed: armed.clone(),
+                barrier: Arc::new(Barrier::new(READERS)),
+            }));
+        let db = Arc::new(SqliteDatabase::open_with(path.to_str().unwrap(), Some(pool)).unwrap());
+        let repo = Arc::new(SqliteUserRepository::with_database(db.clone()));
+        repo.init_schema().unwrap();
+        block_on(repo.create_many(&[new_user("Ada"), new_user("Grace")])).unwrap();
+
+        armed.store(true, Ordering::SeqCst);
+        let writer = db.writer();
+        let (done_tx, done_rx) = mpsc::channel();
+        for _ in 0..READERS {
+            let (repo, done_tx) = (repo.clone(), done_tx.clone());
+            thread::spawn(move || {
+                let _ = done_tx.send(block_on(repo.get_all()).map(|users| users.len()));
+            });
+        }
+        for _ in 0..READERS {
+            let seen = done_rx
+                .recv_timeout(Duration::from_secs(5))
+                .expect("get_all calls serialized instead of running together");
+            assert_eq!(seen.unwrap(), 2);
+        }
+        drop(writer);
+
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    #[test]
+    fn create_many_is_all_or_nothing() {
+        let repo = repository();
+        repo.apply_unique_by(UniqueBy::Email).unwrap();
+        let batch = [new_user("Ada"), new_user("Grace"), new_user("Ada")];
+
+        assert!(matches!(block_on(repo.create_many(&batch)), Err(DomainError::AlreadyExists(_))));
+        assert_eq!(block_on(repo.count()).unwrap(), 0);
+
+        let ids = block_on(repo.create_many(&batch[..2])).unwrap();
+        assert_eq!(ids.len(), 2);
+        assert_eq!(block_on(repo.count()).unwrap(), 2);
+    }
+
+    #[test]
+    fn with_transaction_commits_nothing_when_the_closure_fails() {
+        let repo = repository();
+        block_on(repo.create(&new_user("Ada"))).unwrap();
+
+        let result: Result<(), DomainError> = repo.with_transaction(|conn| {
+            conn.execute("UPDATE users SET name = 'Renamed'", [])?;
+            conn.execute("DELETE FROM users", [])?;
+            Err(DomainError::InvalidOperation("reassignment failed".to_string()))
+        });
+
+        assert!(result.is_err());
+        let users = block_on(repo.get_all()).unwrap();
+        assert_eq!(users.len(), 1);
+        assert_eq!(users[0].name, "Ada");
+
+        let renamed = repo.with_transaction(|conn| Ok(conn.execute("UPDATE users SET name = 'Renamed'", [])?));
+        assert_eq!(renamed.unwrap(), 1);
+        assert_eq!(block_on(repo.get_all()).unwrap()[0].name, "Renamed");
+    }
 }
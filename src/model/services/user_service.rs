@@ -1,29 +1,130 @@
 // core/services/user_service.rs
 use std::sync::Arc;
 use async_trait::async_trait;
-use crate::core::domain::{user::{User, UserId, NewUser}, errors::DomainError};
+use crate::core::domain::{user::{validate_email, Email, EmailDomainAllowlist, User, UserId, UserRole, UserStatus, NewUser}, errors::DomainError};
+use crate::core::domain::events::{DomainEvent, EventMetadata, UserCreatedEvent, UserDeletedEvent, UserUpdatedEvent};
 use crate::core::ports::repository::UserRepository;
 use crate::core::ports::event_bus::EventBus;
+use crate::core::trace;
 
 #[async_trait]
 pub trait UserService: Send + Sync {
     async fn get_all_users(&self) -> Result<Vec<User>, DomainError>;
     async fn get_user(&self, id: UserId) -> Result<User, DomainError>;
     async fn create_user(&self, new_user: NewUser) -> Result<UserId, DomainError>;
+    /// Checks `new_user` against the rules `create_user` applies, without
+    /// writing anything.
+    fn validate_user(&self, new_user: &NewUser) -> Result<(), DomainError>;
+    /// Validates every user before inserting any; the insert is all-or-nothing.
+    async fn create_users(&self, new_users: Vec<NewUser>) -> Result<Vec<UserId>, DomainError>;
+    /// Changes the given fields and publishes `user.updated` carrying only
+    /// those that actually changed; a no-op change publishes nothing.
+    async fn change_user(&self, id: UserId, changes: UserChanges) -> Result<User, DomainError>;
+    async fn update_user(
+        &self,
+        id: UserId,
+        name: Option<String>,
+        email: Option<String>,
+    ) -> Result<User, DomainError> {
+        self.change_user(id, UserChanges { name, email, ..UserChanges::default() }).await
+    }
     async fn delete_user(&self, id: UserId) -> Result<(), DomainError>;
 }
 
+/// Fields for `UserService::change_user`; `None` leaves a field as it is.
+#[derive(Debug, Clone, Default)]
+pub struct UserChanges {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub role: Option<UserRole>,
+    pub status: Option<UserStatus>,
+}
+
+impl UserChanges {
+    fn is_empty(&self) -> bool {
+        self.name.is_none() && self.email.is_none() && self.role.is_none() && self.status.is_none()
+    }
+}
+
 pub struct UserServiceImpl {
     repository: Arc<dyn UserRepository>,
+    event_bus: Arc<dyn EventBus>,
+    email_allowlist: EmailDomainAllowlist,
 }
 
 impl UserServiceImpl {
+    /// The `source` stamped on published events; the audit log records it as
+    /// the actor.
+    pub const SOURCE: &'static str = "user_service";
+
     pub fn new(
         repository: Arc<dyn UserRepository>,
-        _event_bus: Arc<dyn EventBus>,
+        event_bus: Arc<dyn EventBus>,
     ) -> Self {
         Self {
             repository,
+            event_bus,
+            email_allowlist: EmailDomainAllowlist::default(),
+        }
+    }
+
+    pub fn with_email_allowlist(mut self, allowlist: EmailDomainAllowlist) -> Self {
+        self.email_allowlist = allowlist;
+        self
+    }
+
+    fn validate(&self, new_user: &NewUser) -> Result<(), DomainError> {
+        validate_email(&new_user.email.0)?;
+        self.email_allowlist.check(&new_user.email.0)?;
+        User::create(new_user.clone())?;
+        Ok(())
+    }
+
+    /// Applies `changes` to `user`, returning only the fields that differ.
+    fn apply_changes(&self, user: &mut User, changes: UserChanges) -> Result<UserChanges, DomainError> {
+        let name = changes
+            .name
+            .map(|name| name.trim().to_string())
+            .filter(|name| *name != user.name);
+        let email = changes
+            .email
+            .map(|email| email.trim().to_string())
+            .filter(|email| *email != user.email.0);
+        let role = changes.role.filter(|role| *role != user.role);
+        let status = changes.status.filter(|status| *status != user.status);
+
+        if let Some(name) = &name {
+            if name.is_empty() {
+                return Err(DomainError::ValidationError("Name cannot be empty".to_string()));
+            }
+            user.name = name.clone();
+        }
+        if let Some(email) = &email {
+            trace::span("validation", || -> Result<(), DomainError> {
+                validate_email(email)?;
+                self.email_allowlist.check(email)?;
+                Ok(())
+            })?;
+            user.email = Email(email.clone());
+        }
+
+        if let Some(role) = &role {
+            user.role = *role;
+        }
+        if let Some(status) = &status {
+            user.status = *status;
+        }
+
+        Ok(UserChanges { name, email, role, status })
+    }
+
+    /// The write has already happened, so a failed publish is logged rather
+    /// than reported to the caller.
+    fn publish(&self, event: Box<dyn DomainEvent>) {
+        let event_type = event.event_type();
+        let metadata = EventMetadata::current(Self::SOURCE);
+        if let Err(e) = self.event_bus.publish_with_metadata(event, metadata) {
+            log::warn!("Failed to publish {}: {}", event_type, e);
         }
     }
 }
@@ -38,17 +139,227 @@ impl UserService for UserServiceImpl {
         self.repository.get_by_id(id).await
     }
     
+    fn validate_user(&self, new_user: &NewUser) -> Result<(), DomainError> {
+        self.validate(new_user)
+    }
+
     async fn create_user(&self, new_user: NewUser) -> Result<UserId, DomainError> {
         // Validate
-        User::create(new_user.clone())?;
+        trace::span("validation", || self.validate(&new_user))?;
         
         // Create in repository
         let id = self.repository.create(&new_user).await?;
-        
+
+        self.publish(Box::new(UserCreatedEvent::new(
+            id,
+            new_user.name.clone(),
+            new_user.email.0.clone(),
+        )));
+
         Ok(id)
     }
     
+    async fn create_users(&self, new_users: Vec<NewUser>) -> Result<Vec<UserId>, DomainError> {
+        trace::span("validation", || {
+            new_users.iter().enumerate().try_for_each(|(index, new_user)| {
+                self.validate(new_user).map_err(|e| {
+                    DomainError::ValidationError(format!("user {} ({}): {}", index, new_user.email.0, e))
+                })
+            })
+        })?;
+
+        let ids = self.repository.create_many(&new_users).await?;
+
+        for (id, new_user) in ids.iter().zip(&new_users) {
+            self.publish(Box::new(UserCreatedEvent::new(
+                *id,
+                new_user.name.clone(),
+                new_user.email.0.clone(),
+            )));
+        }
+
+        Ok(ids)
+    }
+
+    async fn change_user(&self, id: UserId, changes: UserChanges) -> Result<User, DomainError> {
+        let mut applied = UserChanges::default();
+        let user = self
+            .repository
+            .modify(
+                id,
+                Box::new(|user| {
+                    applied = self.apply_changes(user, changes)?;
+                    Ok(!applied.is_empty())
+                }),
+            )
+            .await?;
+
+        if applied.is_empty() {
+            return Ok(user);
+        }
+
+        let UserChanges { name, email, role, status } = applied;
+        self.publish(Box::new(
+            UserUpdatedEvent::new(id, name, email)
+                .with_role(role.map(|role| role.as_str().to_string()))
+                .with_status(status.map(|status| status.as_str().to_string())),
+        ));
+
+        Ok(user)
+    }
+
     async fn delete_user(&self, id: UserId) -> Result<(), DomainError> {
-        self.repository.delete(id).await
+        self.repository.delete(id).await?;
+        self.publish(Box::new(UserDeletedEvent::new(id)));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use crate::core::domain::user::{Email, UserRole};
+    use crate::core::ports::logger::LogLevel;
+    use crate::core::event_bus::{EventBusConfig, InMemoryEventBus};
+    use crate::core::logging::StructuredLogger;
+    use crate::model::repositories::sqlite::connection::SqliteDatabase;
+    use crate::model::repositories::sqlite::user_repository::SqliteUserRepository;
+
+    fn bus() -> Arc<InMemoryEventBus> {
+        Arc::new(InMemoryEventBus::new(
+            EventBusConfig::new().with_logging(false),
+            StructuredLogger::new(LogLevel::Error, "user_service_test"),
+        ))
+    }
+
+    fn service_on(bus: Arc<InMemoryEventBus>, domains: &[&str]) -> UserServiceImpl {
+        let db = Arc::new(SqliteDatabase::open(":memory:", 0).unwrap());
+        let repository = SqliteUserRepository::with_database(db);
+        repository.init_schema().unwrap();
+        let domains: Vec<String> = domains.iter().map(|d| d.to_string()).collect();
+        UserServiceImpl::new(Arc::new(repository), bus)
+            .with_email_allowlist(EmailDomainAllowlist::new(&domains))
+    }
+
+    fn service(domains: &[&str]) -> UserServiceImpl {
+        service_on(bus(), domains)
+    }
+
+    fn new_user(email: &str) -> NewUser {
+        NewUser {
+            name: "Ada".to_string(),
+            email: Email(email.to_string()),
+            role: UserRole::User,
+            external_id: None,
+        }
+    }
+
+    #[test]
+    fn allowed_domain_is_accepted_case_insensitively() {
+        let service = service(&["Example.com"]);
+
+        assert!(block_on(service.create_user(new_user("ada@EXAMPLE.com"))).is_ok());
+    }
+
+    #[test]
+    fn disallowed_domain_is_rejected_on_create_and_update() {
+        let service = service(&["example.com"]);
+
+        match block_on(service.create_user(new_user("ada@other.org"))) {
+            Err(DomainError::ValidationError(msg)) => assert!(msg.contains("other.org"), "{}", msg),
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+
+        let id = block_on(service.create_user(new_user("ada@example.com"))).unwrap();
+        let update = block_on(service.update_user(id, None, Some("ada@other.org".to_string())));
+        assert!(matches!(update, Err(DomainError::ValidationError(_))));
+        assert_eq!(block_on(service.get_user(id)).unwrap().email.0, "ada@example.com");
+    }
+
+    #[test]
+    fn empty_allowlist_allows_every_domain() {
+        let service = service(&[]);
+
+        assert!(block_on(service.create_user(new_user("ada@anywhere.io"))).is_ok());
+    }
+
+    #[test]
+    fn invalid_email_is_rejected_before_the_database() {
+        let service = service(&[]);
+
+        for email in ["a@b", "a@@b.com"] {
+            let result = block_on(service.create_user(new_user(email)));
+            assert!(matches!(result, Err(DomainError::ValidationError(_))), "{}", email);
+        }
+        assert!(block_on(service.get_all_users()).unwrap().is_empty());
+    }
+
+    fn numbered_users(count: usize) -> Vec<NewUser> {
+        (0..count).map(|i| new_user(&format!("user{}@example.com", i))).collect()
+    }
+
+    #[test]
+    fn create_users_inserts_a_hundred_in_one_call() {
+        let service = service(&[]);
+
+        let ids = block_on(service.create_users(numbered_users(100))).unwrap();
+
+        assert_eq!(ids.len(), 100);
+        assert_eq!(block_on(service.get_all_users()).unwrap().len(), 100);
+    }
+
+    #[test]
+    fn one_bad_email_rejects_the_whole_batch() {
+        let service = service(&[]);
+        let mut batch = numbered_users(10);
+        batch[7].email = Email("broken@".to_string());
+
+        match block_on(service.create_users(batch)) {
+            Err(DomainError::ValidationError(msg)) => assert!(msg.contains("user 7"), "{}", msg),
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+        assert!(block_on(service.get_all_users()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn email_change_publishes_only_the_new_email() {
+        let bus = bus();
+        let updates = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = updates.clone();
+        block_on(bus.subscribe("user.updated", move |_, payload| seen.lock().unwrap().push(payload)));
+        let service = service_on(bus, &[]);
+        let id = block_on(service.create_user(new_user("ada@example.com"))).unwrap();
+
+        block_on(service.update_user(id, Some("Ada".to_string()), Some("lovelace@example.com".to_string())))
+            .unwrap();
+        block_on(service.update_user(id, None, Some("lovelace@example.com".to_string()))).unwrap();
+
+        let updates = updates.lock().unwrap();
+        assert_eq!(updates.len(), 1, "unchanged fields must not publish: {:?}", updates);
+        assert_eq!(updates[0]["user_id"], id.0);
+        assert!(updates[0]["name"].is_null());
+        assert_eq!(updates[0]["email"], "lovelace@example.com");
+    }
+
+    #[test]
+    fn deleting_an_unknown_user_publishes_nothing() {
+        let bus = bus();
+        let deletions = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = deletions.clone();
+        block_on(bus.subscribe("user.deleted", move |_, payload| seen.lock().unwrap().push(payload)));
+        let service = service_on(bus, &[]);
+        let id = block_on(service.create_user(new_user("ada@example.com"))).unwrap();
+
+        let missing = block_on(service.delete_user(UserId(id.0 + 1)));
+        assert!(matches!(missing, Err(DomainError::NotFound(_))));
+        assert!(deletions.lock().unwrap().is_empty());
+
+        block_on(service.delete_user(id)).unwrap();
+        assert!(matches!(block_on(service.delete_user(id)), Err(DomainError::NotFound(_))));
+
+        let deletions = deletions.lock().unwrap();
+        assert_eq!(deletions.len(), 1, "only the real deletion publishes: {:?}", deletions);
+        assert_eq!(deletions[0]["user_id"], id.0);
     }
 }
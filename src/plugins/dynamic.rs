@@ -0,0 +1,244 @@
+// src/plugins/dynamic.rs
+// Loading plugins from shared libraries
+//
+// A dynamic plugin is a `cdylib` built against this crate's `Plugin` trait
+// with the same compiler, exporting two unmangled symbols:
+//
+//     #[no_mangle]
+//     pub extern "C" fn _plugin_abi_version() -> u32 {
+//         PLUGIN_ABI_VERSION
+//     }
+//
+//     #[no_mangle]
+//     pub extern "C" fn _plugin_create() -> *mut Box<dyn Plugin> {
+//         Box::into_raw(Box::new(Box::new(MyPlugin::default())))
+//     }
+//
+// The trait object is boxed twice so the exported pointer is thin. Rust trait
+// objects have no stable layout, so the version check only guards against
+// plugins built for an older trait shape; the compiler must match as well.
+
+use std::path::Path;
+
+use libloading::{Library, Symbol};
+
+use super::plugin_trait::{Plugin, PluginError};
+
+/// Bumped whenever the `Plugin` trait changes shape.
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+pub const ABI_VERSION_SYMBOL: &[u8] = b"_plugin_abi_version";
+pub const CREATE_SYMBOL: &[u8] = b"_plugin_create";
+
+type AbiVersionFn = unsafe extern "C" fn() -> u32;
+type CreateFn = unsafe extern "C" fn() -> *mut Box<dyn Plugin>;
+
+/// A plugin together with the library its code lives in. The library must
+/// outlive the plugin, so callers keep it for as long as the plugin is
+/// reachable.
+pub struct LoadedPlugin {
+    pub plugin: Box<dyn Plugin>,
+    pub library: Library,
+}
+
+/// Whether `path` has this platform's shared library extension.
+pub fn is_shared_library(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext == std::env::consts::DLL_EXTENSION)
+}
+
+pub fn load(path: &Path) -> Result<LoadedPlugin, PluginError> {
+    let display = path.display().to_string();
+
+    // SAFETY: loading runs the library's initialisers; plugins are trusted
+    // code placed in the plugin directory by the operator.
+    let library = unsafe { Library::new(path) }
+        .map_err(|e| PluginError::LoadFailed(format!("{}: {}", display, e)))?;
+
+    let plugin = unsafe {
+        let version: Symbol<AbiVersionFn> = library
+            .get(ABI_VERSION_SYMBOL)
+            .map_err(|_| symbol_missing(&display, ABI_VERSION_SYMBOL))?;
+        let found = version();
+        if found != PLUGIN_ABI_VERSION {
+            return Err(PluginError::AbiMismatch {
+                expected: PLUGIN_ABI_VERSION,
+                found,
+            });
+        }
+
+        let create: Symbol<CreateFn> = library
+            .get(CREATE_SYMBOL)
+            .map_err(|_| symbol_missing(&display, CREATE_SYMBOL))?;
+        let raw = create();
+        if raw.is_null() {
+            return Err(PluginError::LoadFailed(format!(
+                "{}: _plugin_create returned null",
+                display
+            )));
+        }
+        *Box::from_raw(raw)
+    };
+
+    Ok(LoadedPlugin { plugin, library })
+}
+
+fn symbol_missing(path: &str, symbol: &[u8]) -> PluginError {
+    PluginError::SymbolMissing(format!("{} in {}", String::from_utf8_lossy(symbol), path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::process::Command;
+
+    use crate::plugins::registry::PluginRegistry;
+
+    /// Compiles `source` into a cdylib named `name` under a per-test directory.
+    fn build_fixture(dir: &Path, name: &str, source: &str) -> PathBuf {
+        std::fs::create_dir_all(dir).unwrap();
+        let src = dir.join(format!("{}.rs", name));
+        std::fs::write(&src, source).unwrap();
+        let out = dir.join(format!(
+            "{}{}{}",
+            std::env::consts::DLL_PREFIX,
+            name,
+            std::env::consts::DLL_SUFFIX
+        ));
+
+        let status = Command::new(std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string()))
+            .args(["--edition", "2021", "--crate-type", "cdylib", "-o"])
+            .arg(&out)
+            .arg(&src)
+            .status()
+            .unwrap();
+        assert!(status.success(), "failed to build fixture {}", name);
+        out
+    }
+
+    fn fixture_dir(test: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("plugin-fixtures-{}-{}", std::process::id(), test))
+    }
+
+    const VERSION_1: &str = "#[no_mangle] pub extern \"C\" fn _plugin_abi_version() -> u32 { 1 }\n";
+
+    /// This crate's `plugin_trait.rs` as a standalone module. `AppConfig` and
+    /// `Logger` only appear behind `PluginContext`, which fixtures never read,
+    /// and serde attributes do not change layout, so stubs stand in for them.
+    fn standalone_plugin_trait() -> String {
+        let source: String = include_str!("plugin_trait.rs")
+            .lines()
+            .filter(|line| !line.starts_with("use serde") && !line.starts_with("use crate::"))
+            .filter(|line| !line.trim_start().starts_with("#[serde("))
+            .map(|line| format!("{}\n", line.replace(", Serialize)]", ")]")))
+            .collect();
+        format!("pub struct AppConfig;\npub trait Logger {{}}\n{}", source)
+    }
+
+    /// A cdylib exporting a working `greeter` plugin with a `greet` command,
+    /// built against the current `Plugin` trait.
+    fn greeter_source() -> String {
+        format!(
+            r#"#![allow(dead_code, improper_ctypes_definitions)]
+{trait_source}
+struct Greeter;
+
+impl Plugin for Greeter {{
+    fn name(&self) -> &str {{ "greeter" }}
+    fn version(&self) -> &str {{ "1.0.0" }}
+    fn initialize(&self) -> Result<(), PluginError> {{ Ok(()) }}
+    fn shutdown(&self) -> Result<(), PluginError> {{ Ok(()) }}
+    fn as_any(&self) -> &dyn Any {{ self }}
+    fn as_command(&self) -> Option<&dyn CommandPlugin> {{ Some(self) }}
+}}
+
+impl CommandPlugin for Greeter {{
+    fn execute(&self, command: &str, args: &[String]) -> Result<String, PluginError> {{
+        match command {{
+            "greet" => Ok(format!("hello, {{}}", args.join(" "))),
+            other => Err(PluginError::NotFound(other.to_string())),
+        }}
+    }}
+}}
+
+#[no_mangle] pub extern "C" fn _plugin_abi_version() -> u32 {{ {version} }}
+#[no_mangle] pub extern "C" fn _plugin_create() -> *mut Box<dyn Plugin> {{
+    Box::into_raw(Box::new(Box::new(Greeter)))
+}}
+"#,
+            trait_source = standalone_plugin_trait(),
+            version = PLUGIN_ABI_VERSION,
+        )
+    }
+
+    #[test]
+    fn a_built_plugin_is_registered_and_answers_commands() {
+        let dir = fixture_dir("working");
+        let path = build_fixture(&dir, "greeter", &greeter_source());
+
+        let registry = PluginRegistry::new();
+        assert_eq!(registry.load_dynamic(&path).unwrap(), "greeter");
+
+        assert_eq!(registry.list(), ["greeter"]);
+        let plugin = registry.get("greeter").unwrap();
+        assert_eq!(plugin.version(), "1.0.0");
+        assert!(plugin.health().is_ok());
+        assert_eq!(
+            registry.execute_command("greeter", "greet", &["plugin".to_string()]).unwrap(),
+            "hello, plugin"
+        );
+        assert!(matches!(
+            registry.execute_command("greeter", "wave", &[]),
+            Err(PluginError::NotFound(command)) if command == "wave"
+        ));
+        assert!(matches!(registry.load_dynamic(&path), Err(PluginError::AlreadyLoaded(_))));
+
+        drop((plugin, registry));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn broken_libraries_are_reported_not_fatal() {
+        let dir = fixture_dir("broken");
+        let no_exports = build_fixture(&dir, "no_exports", "pub fn unrelated() {}\n");
+        let old_abi = build_fixture(
+            &dir,
+            "old_abi",
+            "#[no_mangle] pub extern \"C\" fn _plugin_abi_version() -> u32 { 0 }\n",
+        );
+        let no_create = build_fixture(&dir, "no_create", VERSION_1);
+        let null_create = build_fixture(
+            &dir,
+            "null_create",
+            &format!(
+                "{}#[no_mangle] pub extern \"C\" fn _plugin_create() -> *mut u8 {{ std::ptr::null_mut() }}\n",
+                VERSION_1
+            ),
+        );
+
+        let error = |path: &Path| load(path).err().expect("fixture must not load");
+        assert!(matches!(error(&no_exports), PluginError::SymbolMissing(msg) if msg.contains("_plugin_abi_version")));
+        assert!(matches!(error(&old_abi), PluginError::AbiMismatch { expected: PLUGIN_ABI_VERSION, found: 0 }));
+        assert!(matches!(error(&no_create), PluginError::SymbolMissing(msg) if msg.contains("_plugin_create")));
+        assert!(matches!(error(&null_create), PluginError::LoadFailed(_)));
+        assert!(matches!(error(&dir.join("missing.so")), PluginError::LoadFailed(_)));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn load_directory_only_tries_shared_libraries() {
+        let dir = fixture_dir("directory");
+        build_fixture(&dir, "no_create", VERSION_1);
+        std::fs::write(dir.join("README.txt"), "not a plugin").unwrap();
+
+        let registry = PluginRegistry::new();
+        let results = registry.load_directory(&dir);
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0], Err(PluginError::SymbolMissing(_))));
+        assert!(registry.load_directory(&dir.join("absent")).is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
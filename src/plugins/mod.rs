@@ -1,10 +1,13 @@
 // src/plugins/mod.rs
 // Plugin system for backend
 
+pub mod dynamic;
 pub mod plugin_trait;
 pub mod registry;
+pub mod sandbox;
 
-pub use plugin_trait::{Plugin, CommandPlugin, QueryPlugin, EventHandlerPlugin, PluginError};
+pub use plugin_trait::{Plugin, CommandPlugin, QueryPlugin, EventHandlerPlugin, PluginContext, PluginError, PluginHealth};
+pub use dynamic::PLUGIN_ABI_VERSION;
 pub use registry::PluginRegistry;
 
 use std::sync::Arc;
@@ -2,13 +2,60 @@
 // Plugin trait definition for backend
 
 use std::any::Any;
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::core::config::AppConfig;
+use crate::core::ports::logger::Logger;
+
+/// What the registry hands a plugin when it is initialized.
+#[derive(Clone)]
+pub struct PluginContext {
+    pub config: Arc<AppConfig>,
+    pub logger: Arc<dyn Logger>,
+}
+
+impl PluginContext {
+    pub fn new(config: Arc<AppConfig>, logger: Arc<dyn Logger>) -> Self {
+        Self { config, logger }
+    }
+}
 
 pub trait Plugin: Send + Sync {
     fn name(&self) -> &str;
     fn version(&self) -> &str;
     fn initialize(&self) -> Result<(), PluginError>;
+
+    /// Called instead of `initialize` when the registry has a context.
+    /// Plugins that need no configuration can ignore it.
+    fn initialize_with(&self, _ctx: &PluginContext) -> Result<(), PluginError> {
+        self.initialize()
+    }
     fn shutdown(&self) -> Result<(), PluginError>;
     fn as_any(&self) -> &dyn Any;
+
+    /// Current health as seen by the plugin itself. Should be cheap; it is
+    /// polled by operator-facing health reports.
+    fn health(&self) -> PluginHealth {
+        PluginHealth::Ok
+    }
+
+    /// The plugin's command capability. `as_any` can only recover concrete
+    /// types, so plugins implementing `CommandPlugin` return `Some(self)` here.
+    fn as_command(&self) -> Option<&dyn CommandPlugin> {
+        None
+    }
+
+    /// The plugin's query capability; see `as_command`.
+    fn as_query(&self) -> Option<&dyn QueryPlugin> {
+        None
+    }
+
+    /// The plugin's event capability; see `as_command`.
+    fn as_event_handler(&self) -> Option<&dyn EventHandlerPlugin> {
+        None
+    }
 }
 
 pub trait CommandPlugin: Plugin {
@@ -20,15 +67,43 @@ pub trait QueryPlugin: Plugin {
 }
 
 pub trait EventHandlerPlugin: Plugin {
+    /// Event types, or patterns such as `user.*`, the registry subscribes
+    /// `on_event` to when an event bus is attached.
+    fn subscribed_events(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// `payload` is the event's JSON body.
     fn on_event(&self, event: &str, payload: &str) -> Result<(), PluginError>;
 }
 
+/// Serialized as `{ "status": "degraded", "detail": "..." }`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", content = "detail", rename_all = "lowercase")]
+pub enum PluginHealth {
+    Ok,
+    Degraded(String),
+    Failed(String),
+}
+
+impl PluginHealth {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, PluginHealth::Ok)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum PluginError {
     InitializationFailed(String),
     ExecutionFailed(String),
     NotFound(String),
     AlreadyLoaded(String),
+    /// A shared library could not be opened or produced no plugin.
+    LoadFailed(String),
+    /// A shared library lacks a required export.
+    SymbolMissing(String),
+    /// A shared library was built against a different plugin ABI.
+    AbiMismatch { expected: u32, found: u32 },
 }
 
 impl std::fmt::Display for PluginError {
@@ -38,6 +113,13 @@ impl std::fmt::Display for PluginError {
             PluginError::ExecutionFailed(msg) => write!(f, "Execution failed: {}", msg),
             PluginError::NotFound(msg) => write!(f, "Not found: {}", msg),
             PluginError::AlreadyLoaded(msg) => write!(f, "Already loaded: {}", msg),
+            PluginError::LoadFailed(msg) => write!(f, "Load failed: {}", msg),
+            PluginError::SymbolMissing(msg) => write!(f, "Missing symbol: {}", msg),
+            PluginError::AbiMismatch { expected, found } => write!(
+                f,
+                "Plugin ABI mismatch: expected version {}, found {}",
+                expected, found
+            ),
         }
     }
 }
@@ -1,22 +1,55 @@
 // src/plugins/registry.rs
 // Plugin registry for backend
 
-use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
 
-use super::plugin_trait::{Plugin, PluginError};
+use libloading::Library;
+use log::warn;
+
+use crate::core::event_bus::InMemoryEventBus;
+
+use super::dynamic;
+use super::plugin_trait::{Plugin, PluginContext, PluginError, PluginHealth};
+use super::sandbox::{run_with_timeout, DEFAULT_CALL_TIMEOUT};
 
 pub struct PluginRegistry {
     plugins: RwLock<HashMap<String, Arc<dyn Plugin>>>,
+    // Shared with event handlers so a disabled plugin also stops receiving events.
+    disabled: Arc<RwLock<HashSet<String>>>,
+    event_bus: RwLock<Option<Arc<InMemoryEventBus>>>,
+    // Plugin name -> event bus subscription ids.
+    event_subscriptions: Mutex<HashMap<String, Vec<String>>>,
+    context: RwLock<Option<PluginContext>>,
+    initialized: AtomicBool,
+    call_timeout_ms: AtomicU64,
+    // Declared after `plugins` so loaded plugins drop before their code is
+    // unmapped. Libraries are never closed while the registry lives.
+    libraries: Mutex<Vec<Library>>,
 }
 
 impl PluginRegistry {
     pub fn new() -> Self {
         Self {
             plugins: RwLock::new(HashMap::new()),
+            disabled: Arc::new(RwLock::new(HashSet::new())),
+            event_bus: RwLock::new(None),
+            event_subscriptions: Mutex::new(HashMap::new()),
+            context: RwLock::new(None),
+            initialized: AtomicBool::new(false),
+            call_timeout_ms: AtomicU64::new(DEFAULT_CALL_TIMEOUT.as_millis() as u64),
+            libraries: Mutex::new(Vec::new()),
         }
     }
 
+    /// Context passed to `initialize_with` for plugins registered from now on.
+    pub fn set_context(&self, ctx: PluginContext) {
+        *self.context.write().unwrap() = Some(ctx);
+    }
+
     pub fn register(&self, plugin: Arc<dyn Plugin>) -> Result<(), PluginError> {
         let name = plugin.name().to_string();
 
@@ -27,21 +60,121 @@ impl PluginRegistry {
             }
         }
 
-        plugin
-            .initialize()
-            .map_err(|e| PluginError::InitializationFailed(e.to_string()))?;
+        let ctx = self.context.read().unwrap().clone();
+        match &ctx {
+            Some(ctx) => plugin.initialize_with(ctx),
+            None => plugin.initialize(),
+        }
+        .map_err(|e| PluginError::InitializationFailed(e.to_string()))?;
 
-        let mut plugins = self.plugins.write().unwrap();
-        plugins.insert(name, plugin);
+        {
+            let mut plugins = self.plugins.write().unwrap();
+            plugins.insert(name, plugin.clone());
+        }
+
+        let bus = self.event_bus.read().unwrap().clone();
+        if let Some(bus) = bus {
+            self.subscribe_plugin(&bus, plugin);
+        }
 
         Ok(())
     }
 
+    /// Attaches the event bus. Registered plugins that handle events, and any
+    /// registered later, are subscribed to their `subscribed_events`.
+    pub fn set_event_bus(&self, bus: Arc<InMemoryEventBus>) {
+        *self.event_bus.write().unwrap() = Some(bus.clone());
+
+        let plugins: Vec<Arc<dyn Plugin>> =
+            self.plugins.read().unwrap().values().cloned().collect();
+        for plugin in plugins {
+            self.subscribe_plugin(&bus, plugin);
+        }
+    }
+
+    fn subscribe_plugin(&self, bus: &InMemoryEventBus, plugin: Arc<dyn Plugin>) {
+        let Some(event_types) = plugin.as_event_handler().map(|h| h.subscribed_events()) else {
+            return;
+        };
+
+        let name = plugin.name().to_string();
+        let mut ids = Vec::with_capacity(event_types.len());
+        for event_type in event_types {
+            let plugin = plugin.clone();
+            let disabled = self.disabled.clone();
+            let handler = move |event_type: String, payload: serde_json::Value| {
+                if disabled.read().unwrap().contains(plugin.name()) {
+                    return;
+                }
+                let Some(handler) = plugin.as_event_handler() else {
+                    return;
+                };
+                if let Err(e) = handler.on_event(&event_type, &payload.to_string()) {
+                    warn!("Plugin {} failed to handle {}: {}", plugin.name(), event_type, e);
+                }
+            };
+            ids.push(futures::executor::block_on(bus.subscribe(&event_type, handler)));
+        }
+
+        self.event_subscriptions
+            .lock()
+            .unwrap()
+            .entry(name)
+            .or_default()
+            .extend(ids);
+    }
+
+    fn unsubscribe_plugin(&self, name: &str) {
+        let ids = self.event_subscriptions.lock().unwrap().remove(name);
+        let bus = self.event_bus.read().unwrap().clone();
+        if let (Some(ids), Some(bus)) = (ids, bus) {
+            for id in ids {
+                if let Err(e) = futures::executor::block_on(bus.unsubscribe(&id)) {
+                    warn!("Failed to unsubscribe plugin {}: {}", name, e);
+                }
+            }
+        }
+    }
+
+    /// Loads a plugin from a shared library and registers it; see
+    /// `plugins::dynamic` for the exports it must provide. Returns the
+    /// plugin's name.
+    pub fn load_dynamic(&self, path: &Path) -> Result<String, PluginError> {
+        let loaded = dynamic::load(path)?;
+        let plugin: Arc<dyn Plugin> = Arc::from(loaded.plugin);
+        let name = plugin.name().to_string();
+
+        // Keep the library even if registration fails: the rejected plugin
+        // is still dropped after this call and its drop glue lives there.
+        let result = self.register(plugin);
+        self.libraries.lock().unwrap().push(loaded.library);
+        result.map(|_| name)
+    }
+
+    /// Loads every shared library in `dir`. A missing directory loads
+    /// nothing; a library that fails is reported and skipped.
+    pub fn load_directory(&self, dir: &Path) -> Vec<Result<String, PluginError>> {
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut paths: Vec<_> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| dynamic::is_shared_library(path))
+            .collect();
+        paths.sort();
+
+        paths.iter().map(|path| self.load_dynamic(path)).collect()
+    }
+
     pub fn unregister(&self, name: &str) -> Result<(), PluginError> {
         let plugin = {
             let mut plugins = self.plugins.write().unwrap();
             plugins.remove(name)
         };
+        self.disabled.write().unwrap().remove(name);
+        self.unsubscribe_plugin(name);
 
         if let Some(p) = plugin {
             p.shutdown()
@@ -52,6 +185,21 @@ impl PluginRegistry {
         }
     }
 
+    /// Unregisters every plugin, calling each `shutdown`. Returns the plugins
+    /// whose shutdown failed; a second call finds nothing left to stop.
+    pub fn shutdown_all(&self) -> Vec<(String, PluginError)> {
+        let mut names = self.list();
+        names.sort();
+
+        names
+            .into_iter()
+            .filter_map(|name| match self.unregister(&name) {
+                Ok(()) | Err(PluginError::NotFound(_)) => None,
+                Err(e) => Some((name, e)),
+            })
+            .collect()
+    }
+
     pub fn get(&self, name: &str) -> Option<Arc<dyn Plugin>> {
         let plugins = self.plugins.read().unwrap();
         plugins.get(name).cloned()
@@ -62,10 +210,135 @@ impl PluginRegistry {
         plugins.keys().cloned().collect()
     }
 
+    /// Every plugin name with whether it is enabled.
+    pub fn list_with_status(&self) -> Vec<(String, bool)> {
+        let plugins = self.plugins.read().unwrap();
+        let disabled = self.disabled.read().unwrap();
+        plugins
+            .keys()
+            .map(|name| (name.clone(), !disabled.contains(name)))
+            .collect()
+    }
+
+    /// Enables or disables a plugin without unloading it. Calls to a
+    /// disabled plugin fail, but its instance and state are kept.
+    pub fn set_enabled(&self, name: &str, enabled: bool) -> Result<(), PluginError> {
+        if !self.plugins.read().unwrap().contains_key(name) {
+            return Err(PluginError::NotFound(name.to_string()));
+        }
+
+        let mut disabled = self.disabled.write().unwrap();
+        if enabled {
+            disabled.remove(name);
+        } else {
+            disabled.insert(name.to_string());
+        }
+        Ok(())
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.plugins.read().unwrap().contains_key(name)
+            && !self.disabled.read().unwrap().contains(name)
+    }
+
+    /// Every plugin's self-reported health, sorted by name. Plugins are
+    /// queried outside the registry lock.
+    pub fn health_report(&self) -> Vec<(String, PluginHealth)> {
+        let plugins: Vec<(String, Arc<dyn Plugin>)> = {
+            let plugins = self.plugins.read().unwrap();
+            plugins
+                .iter()
+                .map(|(name, plugin)| (name.clone(), plugin.clone()))
+                .collect()
+        };
+
+        let mut report: Vec<_> = plugins
+            .into_iter()
+            .map(|(name, plugin)| (name, plugin.health()))
+            .collect();
+        report.sort_by(|a, b| a.0.cmp(&b.0));
+        report
+    }
+
     pub fn len(&self) -> usize {
         let plugins = self.plugins.read().unwrap();
         plugins.len()
     }
+
+    pub fn set_call_timeout(&self, timeout: Duration) {
+        self.call_timeout_ms
+            .store(timeout.as_millis() as u64, Ordering::SeqCst);
+    }
+
+    pub fn call_timeout(&self) -> Duration {
+        Duration::from_millis(self.call_timeout_ms.load(Ordering::SeqCst))
+    }
+
+    /// Runs `call` against the named plugin under the configured timeout.
+    /// The registry lock is released before the call starts, so a plugin
+    /// that never returns does not block registration or lookups. Disabled
+    /// plugins are not called.
+    pub fn call_with_timeout<T, F>(&self, name: &str, call: F) -> Result<T, PluginError>
+    where
+        T: Send + 'static,
+        F: FnOnce(Arc<dyn Plugin>) -> Result<T, PluginError> + Send + 'static,
+    {
+        let plugin = self
+            .get(name)
+            .ok_or_else(|| PluginError::NotFound(name.to_string()))?;
+        if self.disabled.read().unwrap().contains(name) {
+            return Err(PluginError::ExecutionFailed("disabled".to_string()));
+        }
+        run_with_timeout(name, self.call_timeout(), move || call(plugin))
+    }
+
+    /// Routes `command` to the named plugin's `CommandPlugin::execute`, under
+    /// the call timeout. `NotFound` if the plugin is missing or has no
+    /// command capability.
+    pub fn execute_command(
+        &self,
+        plugin_name: &str,
+        command: &str,
+        args: &[String],
+    ) -> Result<String, PluginError> {
+        let name = plugin_name.to_string();
+        let command = command.to_string();
+        let args = args.to_vec();
+        self.call_with_timeout(plugin_name, move |plugin| {
+            plugin
+                .as_command()
+                .ok_or_else(|| PluginError::NotFound(format!("{} has no command handler", name)))?
+                .execute(&command, &args)
+        })
+    }
+
+    /// Routes `query` to the named plugin's `QueryPlugin::query`; see
+    /// `execute_command`.
+    pub fn execute_query(
+        &self,
+        plugin_name: &str,
+        query: &str,
+        params: &[String],
+    ) -> Result<String, PluginError> {
+        let name = plugin_name.to_string();
+        let query = query.to_string();
+        let params = params.to_vec();
+        self.call_with_timeout(plugin_name, move |plugin| {
+            plugin
+                .as_query()
+                .ok_or_else(|| PluginError::NotFound(format!("{} has no query handler", name)))?
+                .query(&query, &params)
+        })
+    }
+
+    /// Marks the end of startup registration; readiness depends on it.
+    pub fn mark_initialized(&self) {
+        self.initialized.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.initialized.load(Ordering::SeqCst)
+    }
 }
 
 impl Default for PluginRegistry {
@@ -73,3 +346,352 @@ impl Default for PluginRegistry {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::any::Any;
+
+    use super::super::plugin_trait::{CommandPlugin, EventHandlerPlugin};
+    use crate::core::config::AppConfig;
+    use crate::core::domain::events::UserCreatedEvent;
+    use crate::core::domain::user::UserId;
+    use crate::core::event_bus::EventBusConfig;
+    use crate::core::logging::StructuredLogger;
+    use crate::core::ports::event_bus::EventBus;
+    use crate::core::ports::logger::LogLevel;
+
+    /// A command plugin that echoes its command, or sleeps on `"slow"`.
+    struct Echo;
+
+    impl Plugin for Echo {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn version(&self) -> &str {
+            "1.0.0"
+        }
+
+        fn initialize(&self) -> Result<(), PluginError> {
+            Ok(())
+        }
+
+        fn shutdown(&self) -> Result<(), PluginError> {
+            Ok(())
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_command(&self) -> Option<&dyn CommandPlugin> {
+            Some(self)
+        }
+    }
+
+    impl CommandPlugin for Echo {
+        fn execute(&self, command: &str, _args: &[String]) -> Result<String, PluginError> {
+            if command == "slow" {
+                std::thread::sleep(Duration::from_secs(2));
+            }
+            Ok(command.to_string())
+        }
+    }
+
+    /// Records the type of every `user.*` event it receives.
+    struct Listener {
+        name: &'static str,
+        events: Mutex<Vec<String>>,
+    }
+
+    impl Listener {
+        fn new(name: &'static str) -> Arc<Self> {
+            Arc::new(Self {
+                name,
+                events: Mutex::new(Vec::new()),
+            })
+        }
+    }
+
+    impl Plugin for Listener {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn version(&self) -> &str {
+            "1.0.0"
+        }
+
+        fn initialize(&self) -> Result<(), PluginError> {
+            Ok(())
+        }
+
+        fn shutdown(&self) -> Result<(), PluginError> {
+            Ok(())
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_event_handler(&self) -> Option<&dyn EventHandlerPlugin> {
+            Some(self)
+        }
+    }
+
+    impl EventHandlerPlugin for Listener {
+        fn subscribed_events(&self) -> Vec<String> {
+            vec!["user.*".to_string()]
+        }
+
+        fn on_event(&self, event: &str, _payload: &str) -> Result<(), PluginError> {
+            self.events.lock().unwrap().push(event.to_string());
+            Ok(())
+        }
+    }
+
+    /// Counts `shutdown` calls; fails them when `fails` is set.
+    struct Stoppable {
+        name: &'static str,
+        fails: bool,
+        stopped: AtomicU64,
+    }
+
+    impl Stoppable {
+        fn new(name: &'static str, fails: bool) -> Arc<Self> {
+            Arc::new(Self {
+                name,
+                fails,
+                stopped: AtomicU64::new(0),
+            })
+        }
+    }
+
+    impl Plugin for Stoppable {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn version(&self) -> &str {
+            "1.0.0"
+        }
+
+        fn initialize(&self) -> Result<(), PluginError> {
+            Ok(())
+        }
+
+        fn shutdown(&self) -> Result<(), PluginError> {
+            self.stopped.fetch_add(1, Ordering::SeqCst);
+            if self.fails {
+                return Err(PluginError::ExecutionFailed("still busy".to_string()));
+            }
+            Ok(())
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    fn registry() -> PluginRegistry {
+        let registry = PluginRegistry::new();
+        registry.register(Arc::new(Echo)).unwrap();
+        registry
+    }
+
+    #[test]
+    fn a_disabled_plugin_is_skipped_until_re_enabled() {
+        let registry = registry();
+        assert_eq!(registry.execute_command("echo", "ping", &[]).unwrap(), "ping");
+
+        registry.set_enabled("echo", false).unwrap();
+        assert!(!registry.is_enabled("echo"));
+        assert!(matches!(
+            registry.execute_command("echo", "ping", &[]),
+            Err(PluginError::ExecutionFailed(msg)) if msg == "disabled"
+        ));
+        assert_eq!(registry.list_with_status(), [("echo".to_string(), false)]);
+
+        registry.set_enabled("echo", true).unwrap();
+        assert_eq!(registry.execute_command("echo", "ping", &[]).unwrap(), "ping");
+        assert!(matches!(
+            registry.set_enabled("missing", false),
+            Err(PluginError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn a_slow_command_returns_the_timeout_error() {
+        let registry = registry();
+        registry.set_call_timeout(Duration::from_millis(50));
+
+        assert!(matches!(
+            registry.execute_command("echo", "slow", &[]),
+            Err(PluginError::ExecutionFailed(msg)) if msg == "timeout"
+        ));
+    }
+
+    /// Reports itself degraded, and is otherwise inert.
+    struct Lagging;
+
+    impl Plugin for Lagging {
+        fn name(&self) -> &str {
+            "lagging"
+        }
+
+        fn version(&self) -> &str {
+            "1.0.0"
+        }
+
+        fn initialize(&self) -> Result<(), PluginError> {
+            Ok(())
+        }
+
+        fn shutdown(&self) -> Result<(), PluginError> {
+            Ok(())
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn health(&self) -> PluginHealth {
+            PluginHealth::Degraded("queue backed up".to_string())
+        }
+    }
+
+    #[test]
+    fn health_report_lists_every_plugin_by_name() {
+        let registry = registry();
+        registry.register(Arc::new(Lagging)).unwrap();
+
+        let lagging = PluginHealth::Degraded("queue backed up".to_string());
+        assert_eq!(
+            registry.health_report(),
+            [("echo".to_string(), PluginHealth::Ok), ("lagging".to_string(), lagging.clone())]
+        );
+        assert_eq!(
+            serde_json::to_value(&lagging).unwrap(),
+            serde_json::json!({ "status": "degraded", "detail": "queue backed up" })
+        );
+    }
+
+    /// Remembers the app name from the context it was initialized with.
+    struct Configured {
+        name: &'static str,
+        app_name: Mutex<Option<String>>,
+    }
+
+    impl Configured {
+        fn new(name: &'static str) -> Arc<Self> {
+            Arc::new(Self {
+                name,
+                app_name: Mutex::new(None),
+            })
+        }
+    }
+
+    impl Plugin for Configured {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn version(&self) -> &str {
+            "1.0.0"
+        }
+
+        fn initialize(&self) -> Result<(), PluginError> {
+            Ok(())
+        }
+
+        fn initialize_with(&self, ctx: &PluginContext) -> Result<(), PluginError> {
+            *self.app_name.lock().unwrap() = Some(ctx.config.app.name.clone());
+            Ok(())
+        }
+
+        fn shutdown(&self) -> Result<(), PluginError> {
+            Ok(())
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    #[test]
+    fn plugins_registered_after_set_context_receive_it() {
+        let registry = PluginRegistry::new();
+        let early = Configured::new("early");
+        registry.register(early.clone()).unwrap();
+
+        let mut config = AppConfig::default();
+        config.app.name = "context-test".to_string();
+        let logger = StructuredLogger::new(LogLevel::Error, "plugin_test");
+        registry.set_context(PluginContext::new(Arc::new(config), Arc::new(logger)));
+        let late = Configured::new("late");
+        registry.register(late.clone()).unwrap();
+
+        assert_eq!(*early.app_name.lock().unwrap(), None);
+        assert_eq!(late.app_name.lock().unwrap().as_deref(), Some("context-test"));
+    }
+
+    #[test]
+    fn event_plugins_receive_events_whether_registered_before_or_after_the_bus() {
+        let bus = Arc::new(InMemoryEventBus::new(
+            EventBusConfig::new().with_logging(false),
+            StructuredLogger::new(LogLevel::Error, "registry_test"),
+        ));
+        let (early, late) = (Listener::new("early"), Listener::new("late"));
+        let registry = PluginRegistry::new();
+
+        registry.register(early.clone()).unwrap();
+        registry.set_event_bus(bus.clone());
+        registry.register(late.clone()).unwrap();
+        bus.publish(Box::new(UserCreatedEvent::new(
+            UserId(1),
+            "Ada".into(),
+            "ada@example.com".into(),
+        )))
+        .unwrap();
+
+        assert_eq!(*early.events.lock().unwrap(), ["user.created"]);
+        assert_eq!(*late.events.lock().unwrap(), ["user.created"]);
+
+        registry.unregister("late").unwrap();
+        bus.publish(Box::new(UserCreatedEvent::new(
+            UserId(2),
+            "Grace".into(),
+            "grace@example.com".into(),
+        )))
+        .unwrap();
+        assert_eq!(early.events.lock().unwrap().len(), 2);
+        assert_eq!(late.events.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn shutdown_all_stops_every_plugin_once_and_reports_failures() {
+        let registry = PluginRegistry::new();
+        let plugins = [
+            Stoppable::new("a", false),
+            Stoppable::new("b", true),
+            Stoppable::new("c", false),
+        ];
+        for plugin in &plugins {
+            registry.register(plugin.clone()).unwrap();
+        }
+        registry.set_enabled("c", false).unwrap();
+
+        let failures = registry.shutdown_all();
+
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, "b");
+        for plugin in &plugins {
+            assert_eq!(plugin.stopped.load(Ordering::SeqCst), 1, "{}", plugin.name);
+        }
+        assert_eq!(registry.len(), 0);
+
+        assert!(registry.shutdown_all().is_empty());
+        assert!(plugins.iter().all(|p| p.stopped.load(Ordering::SeqCst) == 1));
+    }
+}
@@ -0,0 +1,74 @@
+// src/plugins/sandbox.rs
+// Runs synchronous plugin calls on their own thread so a hung plugin
+// cannot block the caller past a deadline
+
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use log::error;
+
+use super::plugin_trait::PluginError;
+
+pub const DEFAULT_CALL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Runs `call` on a worker thread and waits at most `timeout` for it.
+///
+/// On timeout the worker is detached rather than killed; whatever it returns
+/// later is dropped. Callers must not hand it guards they still need.
+pub fn run_with_timeout<T, F>(label: &str, timeout: Duration, call: F) -> Result<T, PluginError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, PluginError> + Send + 'static,
+{
+    let (tx, rx) = mpsc::sync_channel(1);
+    thread::Builder::new()
+        .name(format!("plugin-{}", label))
+        .spawn(move || {
+            let _ = tx.send(call());
+        })
+        .map_err(|e| PluginError::ExecutionFailed(e.to_string()))?;
+
+    match rx.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(mpsc::RecvTimeoutError::Timeout) => {
+            error!("Plugin call '{}' timed out after {}ms", label, timeout.as_millis());
+            Err(PluginError::ExecutionFailed("timeout".to_string()))
+        }
+        Err(mpsc::RecvTimeoutError::Disconnected) => Err(PluginError::ExecutionFailed(format!(
+            "plugin call '{}' panicked",
+            label
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn a_call_past_the_deadline_returns_the_timeout_error() {
+        let started = Instant::now();
+        let result = run_with_timeout("slow", Duration::from_millis(50), || {
+            thread::sleep(Duration::from_secs(2));
+            Ok("late")
+        });
+
+        assert!(matches!(result, Err(PluginError::ExecutionFailed(msg)) if msg == "timeout"));
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn a_call_within_the_deadline_returns_its_result() {
+        let result = run_with_timeout("quick", Duration::from_secs(5), || Ok(42));
+        assert_eq!(result.unwrap(), 42);
+    }
+
+    #[test]
+    fn a_panicking_call_is_reported_as_an_error() {
+        let result: Result<(), PluginError> =
+            run_with_timeout("broken", Duration::from_secs(5), || panic!("plugin bug"));
+        assert!(matches!(result, Err(PluginError::ExecutionFailed(msg)) if msg.contains("panicked")));
+    }
+}
@@ -0,0 +1,35 @@
+// infrastructure/web/handlers/audit_handlers.rs
+use std::sync::Arc;
+use log::warn;
+use webui_rs::webui;
+
+use super::response;
+use crate::model::repositories::sqlite::audit_log::{AuditFilter, AuditLog};
+
+pub fn setup_audit_handlers(window: &mut webui::Window, audit_log: Arc<AuditLog>) {
+    response::bind(window, "audit_query", move |event| {
+        let payload_raw = response::get_event_arg(&event, 0).unwrap_or_default();
+
+        let filter = if payload_raw.trim().is_empty() {
+            Ok(AuditFilter::default())
+        } else {
+            serde_json::from_str::<AuditFilter>(&payload_raw)
+        };
+
+        let result = match filter {
+            Ok(filter) => audit_log
+                .query(&filter)
+                .map(|records| serde_json::json!(records))
+                .map_err(|e| e.to_string()),
+            Err(err) => {
+                warn!(
+                    "Invalid audit_query payload from frontend: payload='{}' error={}",
+                    payload_raw, err
+                );
+                Err(format!("Invalid filter: {}", err))
+            }
+        };
+
+        response::respond(event.window, "audit_query_response", result);
+    });
+}
@@ -0,0 +1,91 @@
+// infrastructure/web/handlers/command_handlers.rs
+use std::sync::Arc;
+use log::warn;
+use webui_rs::webui;
+
+use super::response;
+use crate::core::logging::correlation;
+use crate::core::mvvm::viewmodel::ViewModelError;
+use crate::core::readiness::ReadinessGate;
+use crate::viewmodel::dispatcher::CommandDispatcher;
+
+fn dispatch_request(dispatcher: &CommandDispatcher, payload_raw: &str) -> serde_json::Value {
+    match serde_json::from_str::<serde_json::Value>(payload_raw) {
+        Ok(request) => {
+            let command = request
+                .get("command")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            let payload = request
+                .get("payload")
+                .cloned()
+                .unwrap_or(serde_json::Value::Null)
+                .to_string();
+            let traced = request
+                .get("trace")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+
+            let (result, span) = if traced {
+                let (result, span) = dispatcher.dispatch_traced(command, &payload);
+                (result, Some(span))
+            } else {
+                (dispatcher.dispatch(command, &payload), None)
+            };
+
+            let mut response = response::command_envelope(command, result);
+            if let Some(span) = span {
+                response["trace"] = span.to_json();
+            }
+            response
+        }
+        Err(err) => {
+            warn!(
+                "Invalid dispatch_command payload from frontend: payload='{}' error={}",
+                payload_raw, err
+            );
+            response::envelope(Err(ViewModelError::invalid_command(format!(
+                "malformed request: {}",
+                err
+            ))))
+        }
+    }
+}
+
+fn send_response(window: usize, detail: serde_json::Value) {
+    response::dispatch(window, "command_response", detail);
+}
+
+/// Binds `dispatch_command`, which takes `{ "command": "...", "payload": {...} }`
+/// and routes it through the dispatcher (aliases included). With `"trace": true`
+/// the response carries the execution's span tree under `trace`. Requests that
+/// arrive before the gate opens are queued, or rejected with `NOT_READY`
+/// once the queue is full.
+pub fn setup_command_handlers(
+    window: &mut webui::Window,
+    dispatcher: Arc<CommandDispatcher>,
+    gate: Arc<ReadinessGate>,
+) {
+    response::bind(window, "dispatch_command", move |event| {
+        let payload_raw = response::get_event_arg(&event, 0).unwrap_or_default();
+        let window_id = event.window;
+
+        let dispatcher = dispatcher.clone();
+        // Queued requests may run on another thread, so the scope is entered
+        // where the request is actually handled.
+        let correlation_id = correlation::from_payload(&payload_raw);
+        let queued = gate.submit(move || {
+            correlation::scope(correlation_id, || {
+                send_response(window_id, dispatch_request(&dispatcher, &payload_raw));
+            });
+        });
+
+        if let Err(not_ready) = queued {
+            warn!(
+                "dispatch_command rejected before startup completed; retry in {}ms",
+                not_ready.retry_after_ms
+            );
+            send_response(window_id, not_ready.to_json());
+        }
+    });
+}
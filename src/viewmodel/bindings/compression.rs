@@ -0,0 +1,123 @@
+// infrastructure/web/handlers/compression.rs
+// Optional compression of large CustomEvent payloads.
+//
+// When enabled and the serialized response exceeds the threshold, the event
+// detail becomes `{ "compressed": true, "encoding": "gzip" | "deflate", "data": "<base64>" }`
+// instead of the plain response object. The frontend restores it with:
+//
+// ```js
+// const bytes = Uint8Array.from(atob(detail.data), c => c.charCodeAt(0));
+// const format = detail.encoding === 'gzip' ? 'gzip' : 'deflate-raw';
+// const stream = new Blob([bytes]).stream().pipeThrough(new DecompressionStream(format));
+// const original = JSON.parse(await new Response(stream).text());
+// ```
+
+use base64::Engine;
+use flate2::write::{DeflateEncoder, GzEncoder};
+use flate2::Compression;
+use std::io::Write;
+use std::sync::RwLock;
+
+use crate::core::config::{CompressionAlgorithm, CompressionSettings};
+
+static SETTINGS: RwLock<Option<CompressionSettings>> = RwLock::new(None);
+
+pub fn configure(settings: CompressionSettings) {
+    *SETTINGS.write().unwrap() = Some(settings);
+}
+
+fn compress(algorithm: CompressionAlgorithm, bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    match algorithm {
+        CompressionAlgorithm::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(bytes)?;
+            encoder.finish()
+        }
+        CompressionAlgorithm::Deflate => {
+            let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(bytes)?;
+            encoder.finish()
+        }
+    }
+}
+
+/// Returns the event detail to send for `response`, compressed only when
+/// enabled and larger than the configured threshold.
+pub fn encode_with(settings: &CompressionSettings, response: serde_json::Value) -> serde_json::Value {
+    if !settings.enabled {
+        return response;
+    }
+
+    let serialized = response.to_string();
+    if serialized.len() < settings.threshold_bytes {
+        return response;
+    }
+
+    match compress(settings.algorithm, serialized.as_bytes()) {
+        Ok(bytes) => serde_json::json!({
+            "compressed": true,
+            "encoding": settings.algorithm.as_str(),
+            "data": base64::engine::general_purpose::STANDARD.encode(bytes),
+        }),
+        Err(e) => {
+            log::warn!("Response compression failed, sending uncompressed: {}", e);
+            response
+        }
+    }
+}
+
+pub fn encode_detail(response: serde_json::Value) -> serde_json::Value {
+    match SETTINGS.read().unwrap().as_ref() {
+        Some(settings) => encode_with(settings, response),
+        None => response,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::{DeflateDecoder, GzDecoder};
+    use std::io::Read;
+
+    fn settings(algorithm: CompressionAlgorithm) -> CompressionSettings {
+        CompressionSettings {
+            enabled: true,
+            algorithm,
+            threshold_bytes: 256,
+        }
+    }
+
+    fn decompress(detail: &serde_json::Value) -> serde_json::Value {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(detail["data"].as_str().unwrap())
+            .unwrap();
+        let mut text = String::new();
+        match detail["encoding"].as_str().unwrap() {
+            "gzip" => GzDecoder::new(bytes.as_slice()).read_to_string(&mut text),
+            _ => DeflateDecoder::new(bytes.as_slice()).read_to_string(&mut text),
+        }
+        .unwrap();
+        serde_json::from_str(&text).unwrap()
+    }
+
+    #[test]
+    fn large_responses_are_compressed_and_decompress_to_the_original() {
+        let response = serde_json::json!({ "success": true, "data": "x".repeat(1024) });
+
+        for algorithm in [CompressionAlgorithm::Gzip, CompressionAlgorithm::Deflate] {
+            let detail = encode_with(&settings(algorithm), response.clone());
+            assert_eq!(detail["compressed"], true);
+            assert_eq!(detail["encoding"], algorithm.as_str());
+            assert_eq!(decompress(&detail), response);
+        }
+    }
+
+    #[test]
+    fn responses_below_the_threshold_pass_through_unchanged() {
+        let response = serde_json::json!({ "success": true, "data": "small" });
+
+        let detail = encode_with(&settings(CompressionAlgorithm::Gzip), response.clone());
+
+        assert_eq!(detail, response);
+    }
+}
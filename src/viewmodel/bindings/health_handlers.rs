@@ -0,0 +1,53 @@
+// infrastructure/web/handlers/health_handlers.rs
+use std::sync::Arc;
+use webui_rs::webui;
+
+use super::response;
+
+use crate::core::health::{liveness, readiness, ProbeReport};
+use crate::di::ServiceProvider;
+use crate::plugins::SharedPluginRegistry;
+
+fn dispatch_probe(window_id: usize, event_name: &str, report: &ProbeReport) {
+    let detail = serde_json::json!({
+        "success": report.ok,
+        "data": report,
+    });
+
+    response::dispatch(window_id, event_name, detail);
+}
+
+pub fn setup_health_handlers(
+    window: &mut webui::Window,
+    provider: Arc<ServiceProvider>,
+    plugins: SharedPluginRegistry,
+) {
+    response::bind(window, "health_liveness", |event| {
+        dispatch_probe(event.window, "liveness_response", &liveness());
+    });
+
+    let readiness_plugins = plugins.clone();
+    response::bind(window, "health_readiness", move |event| {
+        let report = readiness(provider.main_database().as_deref(), &readiness_plugins);
+        dispatch_probe(event.window, "readiness_response", &report);
+    });
+
+    response::bind(window, "plugin_health", move |event| {
+        let report: Vec<_> = plugins
+            .health_report()
+            .into_iter()
+            .map(|(name, health)| {
+                serde_json::json!({
+                    "name": name,
+                    "enabled": plugins.is_enabled(&name),
+                    "health": health,
+                })
+            })
+            .collect();
+        response::respond::<String>(
+            event.window,
+            "plugin_health_response",
+            Ok(serde_json::json!(report)),
+        );
+    });
+}
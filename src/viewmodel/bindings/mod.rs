@@ -1,3 +1,9 @@
 // infrastructure/web/handlers/mod.rs
+pub mod audit_handlers;
+pub mod command_handlers;
+pub mod compression;
+pub mod health_handlers;
+pub mod response;
+pub mod serializer;
 pub mod system_handlers;
 pub mod user_handlers;
@@ -0,0 +1,278 @@
+// infrastructure/web/handlers/response.rs
+// Uniform response envelopes for WebUI handlers.
+//
+// Every handler answers with a CustomEvent whose detail is either
+// `{ "success": true, "data": ... }` or
+// `{ "success": false, "error": "<message>", "code": "<CODE>" }`.
+
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::panic::{self, AssertUnwindSafe};
+use webui_rs::webui;
+
+use super::serializer;
+use crate::core::logging::correlation;
+use crate::core::domain::errors::DomainError;
+use crate::core::mvvm::viewmodel::ViewModelError;
+use crate::infrastructure::web::logging::RequestLogger;
+
+thread_local! {
+    // The error of the last unsuccessful envelope dispatched on this thread,
+    // so `bind` can tell how the handler it wraps answered.
+    static LAST_FAILURE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Reads the string argument at `index` of a bound call, if one was passed.
+pub fn get_event_arg(event: &webui::Event, index: usize) -> Option<String> {
+    unsafe {
+        let size =
+            webui::bindgen::webui_interface_get_size_at(event.window, event.event_number, index);
+        if size == 0 {
+            return None;
+        }
+
+        let ptr =
+            webui::bindgen::webui_interface_get_string_at(event.window, event.event_number, index);
+        if ptr.is_null() {
+            return None;
+        }
+
+        Some(std::ffi::CStr::from_ptr(ptr).to_string_lossy().into_owned())
+    }
+}
+
+/// The id of the element that triggered the call, if any.
+pub fn get_event_element(event: &webui::Event) -> Option<String> {
+    if event.element.is_null() {
+        return None;
+    }
+
+    unsafe {
+        Some(
+            std::ffi::CStr::from_ptr(event.element)
+                .to_string_lossy()
+                .into_owned(),
+        )
+    }
+}
+
+/// An error that can be reported to the frontend with a stable code.
+pub trait ResponseError: Display {
+    fn code(&self) -> &'static str;
+}
+
+impl ResponseError for String {
+    fn code(&self) -> &'static str {
+        "ERROR"
+    }
+}
+
+impl ResponseError for DomainError {
+    fn code(&self) -> &'static str {
+        match self {
+            DomainError::ValidationError(_) => "VALIDATION_ERROR",
+            DomainError::NotFound(_) => "NOT_FOUND",
+            DomainError::AlreadyExists(_) => "ALREADY_EXISTS",
+            DomainError::InvalidOperation(_) => "INVALID_OPERATION",
+        }
+    }
+}
+
+impl ResponseError for ViewModelError {
+    fn code(&self) -> &'static str {
+        match self {
+            ViewModelError::InvalidCommand(_) => "INVALID_COMMAND",
+            ViewModelError::InvalidQuery(_) => "INVALID_QUERY",
+            ViewModelError::ExecutionFailed(_) => "EXECUTION_FAILED",
+            ViewModelError::NotFound(_) => "NOT_FOUND",
+            ViewModelError::ValidationError(_) => "VALIDATION_ERROR",
+            ViewModelError::Unauthorized(_) => "UNAUTHORIZED",
+            ViewModelError::Busy(_) => "BUSY",
+        }
+    }
+}
+
+pub fn envelope<E: ResponseError>(result: Result<serde_json::Value, E>) -> serde_json::Value {
+    match result {
+        Ok(data) => serde_json::json!({
+            "success": true,
+            "data": data,
+        }),
+        Err(e) => serde_json::json!({
+            "success": false,
+            "error": e.to_string(),
+            "code": e.code(),
+        }),
+    }
+}
+
+/// The envelope for one dispatched command, tagged with its name. Command
+/// output that is JSON is embedded as-is; anything else as a string.
+pub fn command_envelope(
+    command: &str,
+    result: Result<String, ViewModelError>,
+) -> serde_json::Value {
+    let mut detail = envelope(result.map(|output| {
+        serde_json::from_str(&output).unwrap_or(serde_json::Value::String(output))
+    }));
+    detail["command"] = serde_json::Value::from(command);
+    detail
+}
+
+/// The script that fires `event_name` with `detail`. Both are emitted as JSON
+/// literals, so quotes and backslashes in either stay inside their strings.
+pub fn dispatch_script(event_name: &str, detail: &serde_json::Value) -> String {
+    // U+2028/U+2029 are valid in JSON but end a line in older JS engines.
+    let detail = detail
+        .to_string()
+        .replace('\u{2028}', "\\u2028")
+        .replace('\u{2029}', "\\u2029");
+    format!(
+        "window.dispatchEvent(new CustomEvent({}, {{ detail: {} }}))",
+        serde_json::Value::from(event_name),
+        detail
+    )
+}
+
+/// Sends an already-built detail in the configured wire format, compressing
+/// it when configured. Inside a correlation scope the id is echoed as
+/// `correlation_id`.
+pub fn dispatch(window_id: usize, event_name: &str, mut detail: serde_json::Value) {
+    if detail["success"] == false {
+        let error = detail["error"].as_str().unwrap_or("unsuccessful response").to_string();
+        LAST_FAILURE.with(|last| *last.borrow_mut() = Some(error));
+    }
+    if let (Some(id), Some(object)) = (correlation::current(), detail.as_object_mut()) {
+        object
+            .entry("correlation_id")
+            .or_insert(serde_json::Value::String(id));
+    }
+    let js = dispatch_script(event_name, &serializer::encode_detail(detail));
+    webui::Window::from_id(window_id).run_js(&js);
+}
+
+pub fn respond<E: ResponseError>(
+    window_id: usize,
+    event_name: &str,
+    result: Result<serde_json::Value, E>,
+) {
+    dispatch(window_id, event_name, envelope(result));
+}
+
+/// Binds `handler` to `name` with a `RequestLogger` around every call, so
+/// its duration lands in `HandlerMetrics`.
+pub fn bind<F>(window: &mut webui::Window, name: &str, handler: F)
+where
+    F: FnMut(webui::Event) + 'static,
+{
+    window.bind(name, instrument(name, handler));
+}
+
+/// Wraps `handler` so each call is timed under `name`. A call counts as
+/// failed if it answers with an unsuccessful envelope or panics.
+fn instrument<E, F>(name: &str, mut handler: F) -> impl FnMut(E) + 'static
+where
+    F: FnMut(E) + 'static,
+{
+    let name = name.to_string();
+    move |event| {
+        let logger = RequestLogger::new(&name);
+        LAST_FAILURE.with(|last| last.borrow_mut().take());
+        let outcome = panic::catch_unwind(AssertUnwindSafe(|| handler(event)));
+        let failure = LAST_FAILURE.with(|last| last.borrow_mut().take());
+        match (outcome, failure) {
+            (Err(payload), _) => {
+                logger.failure("handler panicked");
+                panic::resume_unwind(payload);
+            }
+            (Ok(()), Some(error)) => logger.failure(&error),
+            (Ok(()), None) => logger.success("handled"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::web::logging::HandlerMetrics;
+    use std::time::Duration;
+
+    #[test]
+    fn success_envelope_wraps_the_data() {
+        let detail = envelope::<String>(Ok(serde_json::json!({ "id": 1 })));
+        assert_eq!(detail, serde_json::json!({ "success": true, "data": { "id": 1 } }));
+    }
+
+    #[test]
+    fn error_envelope_carries_message_and_code() {
+        let detail = envelope(Err(ViewModelError::not_found("user 7")));
+        assert_eq!(
+            detail,
+            serde_json::json!({ "success": false, "error": "Not found: user 7", "code": "NOT_FOUND" })
+        );
+    }
+
+    #[test]
+    fn command_envelope_embeds_json_output_and_tags_the_command() {
+        let ok = command_envelope("get_users", Ok("[1,2]".to_string()));
+        assert_eq!(ok["data"], serde_json::json!([1, 2]));
+        assert_eq!(ok["command"], "get_users");
+
+        let text = command_envelope("ping", Ok("pong".to_string()));
+        assert_eq!(text["data"], "pong");
+
+        let busy = command_envelope("create_user", Err(ViewModelError::Busy("queue full".to_string())));
+        assert_eq!(busy["code"], "BUSY");
+        assert_eq!(busy["error"], "Busy: queue full");
+        assert_eq!(busy["command"], "create_user");
+    }
+
+    #[test]
+    fn dispatch_script_keeps_awkward_characters_inside_the_string() {
+        let message = "say \"hi\" \\ then\u{2028}stop</script>";
+        let detail = envelope::<String>(Err(message.to_string()));
+
+        let script = dispatch_script("user_response", &detail);
+
+        assert!(script.starts_with("window.dispatchEvent(new CustomEvent(\"user_response\", { detail: "));
+        assert!(!script.contains('\u{2028}'));
+        assert!(script.contains("\\u2028"));
+        let literal = script
+            .strip_prefix("window.dispatchEvent(new CustomEvent(\"user_response\", { detail: ")
+            .and_then(|rest| rest.strip_suffix(" }))"))
+            .unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(literal).unwrap();
+        assert_eq!(parsed["error"], message);
+        assert_eq!(parsed["code"], "ERROR");
+    }
+
+    #[test]
+    fn instrumented_calls_are_counted_and_timed() {
+        let mut handler = instrument("test.counted", |_: ()| {
+            std::thread::sleep(Duration::from_millis(2));
+        });
+        handler(());
+        handler(());
+
+        let stats = HandlerMetrics::global().get("test.counted").unwrap();
+        assert_eq!((stats.count, stats.failures), (2, 0));
+        assert!(stats.avg_ms >= 2.0 && stats.avg_ms <= stats.max_ms);
+        assert!((stats.total_ms - stats.avg_ms * 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn error_envelopes_and_panics_count_as_failures() {
+        let mut handler = instrument("test.failing", |fail: bool| {
+            if fail {
+                LAST_FAILURE.with(|last| *last.borrow_mut() = Some("boom".to_string()));
+            }
+        });
+        handler(true);
+        handler(false);
+        let mut panicking = instrument("test.failing", |_: ()| panic!("handler bug"));
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| panicking(()))).is_err());
+
+        let stats = HandlerMetrics::global().get("test.failing").unwrap();
+        assert_eq!((stats.count, stats.failures), (3, 2));
+    }
+}
@@ -0,0 +1,136 @@
+// infrastructure/web/handlers/serializer.rs
+// Wire format of CustomEvent payloads.
+//
+// JSON responses are sent as plain objects, as before. Any other format
+// replaces the event detail with
+// `{ "content_type": "application/msgpack", "data": "<base64>" }`, which the
+// frontend decodes by content type, e.g. for MessagePack:
+//
+// ```js
+// const bytes = Uint8Array.from(atob(detail.data), c => c.charCodeAt(0));
+// const original = MessagePack.decode(bytes);
+// ```
+
+use base64::Engine;
+use std::sync::RwLock;
+
+use super::compression;
+use crate::core::serialization::{self, Format};
+
+pub trait Serializer: Send + Sync {
+    fn serialize(&self, value: &serde_json::Value) -> Vec<u8>;
+    fn content_type(&self) -> &'static str;
+}
+
+/// Serializes through `core::serialization`. Every JSON value has a
+/// representation in each supported format, so encoding cannot fail.
+struct FormatSerializer(Format);
+
+impl Serializer for FormatSerializer {
+    fn serialize(&self, value: &serde_json::Value) -> Vec<u8> {
+        serialization::serialize(self.0, value).expect("JSON values encode in every format")
+    }
+
+    fn content_type(&self) -> &'static str {
+        self.0.content_type()
+    }
+}
+
+pub struct JsonSerializer;
+
+impl Serializer for JsonSerializer {
+    fn serialize(&self, value: &serde_json::Value) -> Vec<u8> {
+        FormatSerializer(Format::Json).serialize(value)
+    }
+
+    fn content_type(&self) -> &'static str {
+        Format::Json.content_type()
+    }
+}
+
+pub struct MessagePackSerializer;
+
+impl Serializer for MessagePackSerializer {
+    fn serialize(&self, value: &serde_json::Value) -> Vec<u8> {
+        FormatSerializer(Format::MessagePack).serialize(value)
+    }
+
+    fn content_type(&self) -> &'static str {
+        Format::MessagePack.content_type()
+    }
+}
+
+pub fn for_format(format: Format) -> Box<dyn Serializer> {
+    match format {
+        Format::Json => Box::new(JsonSerializer),
+        Format::MessagePack => Box::new(MessagePackSerializer),
+        other => Box::new(FormatSerializer(other)),
+    }
+}
+
+static FORMAT: RwLock<Format> = RwLock::new(Format::Json);
+
+pub fn configure(format: Format) {
+    *FORMAT.write().unwrap() = format;
+}
+
+pub fn format() -> Format {
+    *FORMAT.read().unwrap()
+}
+
+/// Returns the event detail to send for `response` in the configured format.
+/// Compression only applies to JSON; binary formats are already compact.
+pub fn encode_detail(response: serde_json::Value) -> serde_json::Value {
+    let format = format();
+    if format == Format::Json {
+        return compression::encode_detail(response);
+    }
+
+    let serializer = for_format(format);
+    serde_json::json!({
+        "content_type": serializer.content_type(),
+        "data": base64::engine::general_purpose::STANDARD.encode(serializer.serialize(&response)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user_response() -> serde_json::Value {
+        serde_json::json!({
+            "success": true,
+            "data": {
+                "id": 7,
+                "name": "Ada: Countess of Lovelace",
+                "email": "ada@example.com",
+                "role": "Admin",
+                "external_id": null,
+                "tags": ["math", "engines"],
+            },
+        })
+    }
+
+    #[test]
+    fn user_response_round_trips_through_json_and_messagepack() {
+        let cases: [(Box<dyn Serializer>, Format); 2] = [
+            (Box::new(JsonSerializer), Format::Json),
+            (Box::new(MessagePackSerializer), Format::MessagePack),
+        ];
+
+        for (serializer, format) in cases {
+            let bytes = serializer.serialize(&user_response());
+            let decoded: serde_json::Value = serialization::deserialize(format, &bytes).unwrap();
+            assert_eq!(decoded, user_response(), "{}", format.as_str());
+            assert_eq!(serializer.content_type(), format.content_type());
+        }
+    }
+
+    #[test]
+    fn messagepack_is_binary_not_json() {
+        let bytes = MessagePackSerializer.serialize(&user_response());
+
+        assert!(serde_json::from_slice::<serde_json::Value>(&bytes).is_err());
+        assert_eq!(for_format(Format::MessagePack).content_type(), "application/msgpack");
+    }
+}
@@ -1,92 +1,77 @@
 // infrastructure/web/handlers/system_handlers.rs
 use log::{info, warn};
+use std::path::PathBuf;
+use std::sync::Arc;
 use webui_rs::webui;
 
-fn get_event_arg(event: &webui::Event, index: usize) -> Option<String> {
-    unsafe {
-        let size =
-            webui::bindgen::webui_interface_get_size_at(event.window, event.event_number, index);
-        if size == 0 {
-            return None;
-        }
-
-        let ptr =
-            webui::bindgen::webui_interface_get_string_at(event.window, event.event_number, index);
-        if ptr.is_null() {
-            return None;
-        }
-
-        Some(std::ffi::CStr::from_ptr(ptr).to_string_lossy().into_owned())
-    }
-}
+use super::response;
+use crate::core::health::system_health;
+use crate::core::runtime;
+use crate::di::ServiceProvider;
+use crate::core::event_bus::InMemoryEventBus;
+use crate::infrastructure::web::logging::HandlerMetrics;
+use crate::plugins::SharedPluginRegistry;
+use crate::core::platform::sysinfo::{self, SystemInfoSources};
 
-fn get_event_element(event: &webui::Event) -> Option<String> {
-    if event.element.is_null() {
-        return None;
-    }
-
-    unsafe {
-        Some(
-            std::ffi::CStr::from_ptr(event.element)
-                .to_string_lossy()
-                .into_owned(),
-        )
-    }
+/// The bus counters sent as `event_metrics_response`. Bindings run on WebUI
+/// threads, outside the runtime, so blocking on the shared handle is safe.
+fn event_metrics(event_bus: &InMemoryEventBus) -> serde_json::Value {
+    let metrics = runtime::handle().block_on(event_bus.get_metrics());
+    serde_json::json!({
+        "events_published": metrics.events_published,
+        "events_handled": metrics.events_handled,
+        "events_failed": metrics.events_failed,
+        "last_event_type": metrics.last_event_type,
+    })
 }
 
-pub fn setup_system_handlers(window: &mut webui::Window) {
-    window.bind("get_system_info", |event| {
-        let mut sysinfo = serde_json::Map::new();
-
-        sysinfo.insert(
-            "os".to_string(),
-            serde_json::json!({
-                "platform": std::env::consts::OS,
-                "arch": std::env::consts::ARCH,
-            }),
-        );
-
-        // Memory info
-        if let Ok(content) = std::fs::read_to_string("/proc/meminfo") {
-            let mut mem = serde_json::Map::new();
-            for line in content.lines() {
-                let parts: Vec<&str> = line.split(':').collect();
-                if parts.len() == 2 {
-                    let key = parts[0].trim();
-                    let value = parts[1].trim().split_whitespace().next();
-                    if let Some(v) = value {
-                        if let Ok(n) = v.parse::<u64>() {
-                            match key {
-                                "MemTotal" => {
-                                    mem.insert("total_mb".to_string(), serde_json::json!(n / 1024));
-                                }
-                                "MemFree" => {
-                                    mem.insert("free_mb".to_string(), serde_json::json!(n / 1024));
-                                }
-                                _ => {}
-                            }
-                        }
-                    }
-                }
-            }
-            sysinfo.insert("memory".to_string(), serde_json::Value::Object(mem));
+/// The main database's volume is reported under `disk`.
+pub fn setup_system_handlers(
+    window: &mut webui::Window,
+    provider: Arc<ServiceProvider>,
+    plugins: SharedPluginRegistry,
+) {
+    let database_path = PathBuf::from(&provider.config.database.path);
+    let sources = SystemInfoSources::default().with_database_path(database_path);
+    response::bind(window, "get_system_info", move |event| {
+        let info = sysinfo::collect(&sources);
+        for warning in &info.warnings {
+            warn!("System info source '{}' unavailable: {}", warning.source, warning.error);
         }
 
-        let response = serde_json::json!({
+        let detail = serde_json::json!({
             "success": true,
-            "data": serde_json::Value::Object(sysinfo)
+            "data": serde_json::Value::Object(info.data),
+            "warnings": info.warnings,
         });
 
-        let js = format!(
-            "window.dispatchEvent(new CustomEvent('sysinfo_response', {{ detail: {} }}))",
-            response
+        response::dispatch(event.window, "sysinfo_response", detail);
+    });
+
+    let health_provider = provider.clone();
+    response::bind(window, "get_health", move |event| {
+        let report = system_health(
+            health_provider.user_repository().as_deref(),
+            &health_provider.event_bus,
+            &plugins,
         );
-        webui::Window::from_id(event.window).run_js(&js);
+        response::respond::<String>(event.window, "health_response", Ok(report));
+    });
+
+    let event_bus = provider.event_bus.clone();
+    response::bind(window, "get_event_metrics", move |event| {
+        let data = event_metrics(&event_bus);
+        response::respond::<String>(event.window, "event_metrics_response", Ok(data));
+    });
+
+    response::bind(window, "get_handler_metrics", |event| {
+        let data = serde_json::json!({ "handlers": HandlerMetrics::global().snapshot() });
+        response::respond::<String>(event.window, "handler_metrics_response", Ok(data));
     });
 
-    window.bind("log_window_lifecycle", |event| {
-        let payload_raw = get_event_arg(&event, 0)
-            .or_else(|| get_event_element(&event))
+    response::bind(window, "log_window_lifecycle", |event| {
+        let payload_raw = response::get_event_arg(&event, 0)
+            .or_else(|| response::get_event_element(&event))
             .unwrap_or_default();
 
         match serde_json::from_str::<serde_json::Value>(&payload_raw) {
@@ -122,9 +107,9 @@ pub fn setup_system_handlers(window: &mut webui::Window) {
         }
     });
 
-    window.bind("ws_state_change", |event| {
-        let payload_raw = get_event_arg(&event, 0)
-            .or_else(|| get_event_element(&event))
+    response::bind(window, "ws_state_change", |event| {
+        let payload_raw = response::get_event_arg(&event, 0)
+            .or_else(|| response::get_event_element(&event))
             .unwrap_or_default();
 
         match serde_json::from_str::<serde_json::Value>(&payload_raw) {
@@ -164,9 +149,9 @@ pub fn setup_system_handlers(window: &mut webui::Window) {
         }
     });
 
-    window.bind("ws_error_report", |event| {
-        let payload_raw = get_event_arg(&event, 0)
-            .or_else(|| get_event_element(&event))
+    response::bind(window, "ws_error_report", |event| {
+        let payload_raw = response::get_event_arg(&event, 0)
+            .or_else(|| response::get_event_element(&event))
             .unwrap_or_default();
 
         match serde_json::from_str::<serde_json::Value>(&payload_raw) {
@@ -202,9 +187,49 @@ pub fn setup_system_handlers(window: &mut webui::Window) {
         }
     });
 
-    window.bind("ws_heartbeat", |event| {
-        let payload_raw = get_event_arg(&event, 0)
-            .or_else(|| get_event_element(&event))
+    response::bind(window, "frontend_log", |event| {
+        let payload_raw = response::get_event_arg(&event, 0)
+            .or_else(|| response::get_event_element(&event))
+            .unwrap_or_default();
+
+        match serde_json::from_str::<serde_json::Value>(&payload_raw) {
+            Ok(payload) => {
+                let level = match payload
+                    .get("level")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("log")
+                    .to_ascii_lowercase()
+                    .as_str()
+                {
+                    "error" => log::Level::Error,
+                    "warn" | "warning" => log::Level::Warn,
+                    "debug" => log::Level::Debug,
+                    "trace" => log::Level::Trace,
+                    _ => log::Level::Info,
+                };
+                let message = payload
+                    .get("message")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("");
+                let source = payload
+                    .get("source")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown");
+                let line = payload.get("line").and_then(|v| v.as_i64()).unwrap_or(0);
+                log::log!(level, "[frontend] {}:{} {}", source, line, message);
+            }
+            Err(err) => {
+                warn!(
+                    "Invalid frontend_log payload from frontend: payload='{}' error={}",
+                    payload_raw, err
+                );
+            }
+        }
+    });
+
+    response::bind(window, "ws_heartbeat", |event| {
+        let payload_raw = response::get_event_arg(&event, 0)
+            .or_else(|| response::get_event_element(&event))
             .unwrap_or_default();
 
         match serde_json::from_str::<serde_json::Value>(&payload_raw) {
@@ -244,3 +269,36 @@ pub fn setup_system_handlers(window: &mut webui::Window) {
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::domain::events::{UserCreatedEvent, UserDeletedEvent};
+    use crate::core::domain::user::UserId;
+    use crate::core::ports::event_bus::EventBus;
+    use crate::core::ports::logger::LogLevel;
+    use crate::core::event_bus::EventBusConfig;
+    use crate::core::logging::StructuredLogger;
+
+    #[test]
+    fn event_metrics_report_published_handled_and_failed_counts() {
+        let bus = InMemoryEventBus::new(
+            EventBusConfig::new().with_logging(false),
+            StructuredLogger::new(LogLevel::Error, "system_handlers_test"),
+        );
+        futures::executor::block_on(bus.subscribe("user.created", |_, _| {}));
+        futures::executor::block_on(bus.subscribe("user.deleted", |_, _| panic!("handler bug")));
+
+        for id in 1..=2 {
+            bus.publish(Box::new(UserCreatedEvent::new(UserId(id), "Ada".into(), "ada@example.com".into())))
+                .unwrap();
+        }
+        let _ = bus.publish(Box::new(UserDeletedEvent::new(UserId(1))));
+
+        let metrics = event_metrics(&bus);
+        assert_eq!(metrics["events_published"], 3);
+        assert_eq!(metrics["events_handled"], 2);
+        assert_eq!(metrics["events_failed"], 1);
+        assert_eq!(metrics["last_event_type"], "user.deleted");
+    }
+}
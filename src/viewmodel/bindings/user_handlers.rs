@@ -1,98 +1,342 @@
 // infrastructure/web/handlers/user_handlers.rs
+use std::sync::Arc;
 use webui_rs::webui;
 
-fn get_event_arg(event: &webui::Event, index: usize) -> Option<String> {
-    unsafe {
-        let size =
-            webui::bindgen::webui_interface_get_size_at(event.window, event.event_number, index);
-        if size == 0 {
-            return None;
-        }
+use base64::Engine;
+
+use super::response::{self, ResponseError};
+
+use crate::core::domain::errors::DomainError;
+use crate::core::domain::user::{Email, NewUser, UserId, UserRole};
+use crate::core::logging::correlation;
+use crate::core::mvvm::viewmodel::ViewModelError;
+use crate::core::ports::repository::UserRepository;
+use crate::core::services::user_service::UserService;
+use crate::features::user::computed;
+use crate::model::repositories::sqlite::user_projection::UserListProjection;
+use crate::viewmodel::commands::import_users::{ImportUsersCommand, ImportUsersHandler};
+use crate::viewmodel::commands::CommandHandler;
+use crate::viewmodel::dispatcher::CommandDispatcher;
+
+/// Why a user request failed: the frontend sent something unusable, the
+/// command was at its concurrency limit, or the request was well-formed but
+/// the operation itself failed.
+#[derive(Debug)]
+enum UserRequestError {
+    InvalidPayload(String),
+    Busy(String),
+    Failed(String),
+}
 
-        let ptr =
-            webui::bindgen::webui_interface_get_string_at(event.window, event.event_number, index);
-        if ptr.is_null() {
-            return None;
+impl std::fmt::Display for UserRequestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UserRequestError::InvalidPayload(message)
+            | UserRequestError::Busy(message)
+            | UserRequestError::Failed(message) => write!(f, "{}", message),
         }
+    }
+}
 
-        Some(std::ffi::CStr::from_ptr(ptr).to_string_lossy().into_owned())
+impl ResponseError for UserRequestError {
+    fn code(&self) -> &'static str {
+        match self {
+            UserRequestError::InvalidPayload(_) => "INVALID_PAYLOAD",
+            UserRequestError::Busy(_) => "BUSY",
+            UserRequestError::Failed(_) => "FAILED",
+        }
     }
 }
 
-fn get_event_element(event: &webui::Event) -> Option<String> {
-    if event.element.is_null() {
-        return None;
+impl From<ViewModelError> for UserRequestError {
+    fn from(error: ViewModelError) -> Self {
+        match error {
+            ViewModelError::Busy(message) => UserRequestError::Busy(message),
+            other => UserRequestError::Failed(other.to_string()),
+        }
     }
+}
+
+/// Runs a user request within the dispatcher's concurrency limit for `name`,
+/// like a dispatched command of that name.
+fn run_limited(
+    dispatcher: &CommandDispatcher,
+    name: &str,
+    request: impl FnOnce() -> Result<serde_json::Value, UserRequestError>,
+) -> Result<serde_json::Value, UserRequestError> {
+    dispatcher.run_limited(name, request)?
+}
 
-    unsafe {
-        Some(
-            std::ffi::CStr::from_ptr(event.element)
-                .to_string_lossy()
-                .into_owned(),
-        )
+/// Parses the first event argument as a JSON object.
+fn parse_payload(raw: &str) -> Result<serde_json::Value, UserRequestError> {
+    match serde_json::from_str::<serde_json::Value>(raw) {
+        Ok(payload) if payload.is_object() => Ok(payload),
+        Ok(_) => Err(UserRequestError::InvalidPayload(
+            "Expected a JSON object".to_string(),
+        )),
+        Err(e) => Err(UserRequestError::InvalidPayload(format!("Invalid JSON: {}", e))),
     }
 }
 
-pub fn setup_user_handlers(window: &mut webui::Window) {
-    // Get service from DI - this is a simplified approach
-    // In a more advanced setup, you'd use a registry or lazy initialization
+fn required_str<'a>(payload: &'a serde_json::Value, field: &str) -> Result<&'a str, UserRequestError> {
+    payload
+        .get(field)
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.trim().is_empty())
+        .ok_or_else(|| UserRequestError::InvalidPayload(format!("Missing '{}' field", field)))
+}
+
+/// Handles a `create_user` request: `{name, email, role?, external_id?}`.
+fn create_user(service: &dyn UserService, raw: &str) -> Result<serde_json::Value, UserRequestError> {
+    let payload = parse_payload(raw)?;
+    let email = required_str(&payload, "email")?;
+    let new_user = NewUser {
+        name: required_str(&payload, "name")?.to_string(),
+        email: Email::new(email).map_err(|e| UserRequestError::InvalidPayload(e.to_string()))?,
+        role: UserRole::from_str(payload["role"].as_str().unwrap_or("User")),
+        external_id: payload["external_id"].as_str().map(str::to_string),
+    };
+    let id = futures::executor::block_on(service.create_user(new_user.clone()))
+        .map_err(|e| UserRequestError::Failed(e.to_string()))?;
+    Ok(serde_json::json!({ "id": id.0, "name": new_user.name }))
+}
+
+/// Handles a `get_users` request from the projection, which user events keep
+/// current, adding the `include`d computed fields to each user.
+fn list_users(
+    user_list: &UserListProjection,
+    include: &[String],
+) -> Result<serde_json::Value, DomainError> {
+    user_list.list().map(|users| {
+        let now = chrono::Utc::now();
+        let data: Vec<serde_json::Value> = users
+            .iter()
+            .map(|user| {
+                let mut value = serde_json::json!(user);
+                let created_at = chrono::DateTime::parse_from_rfc3339(&user.created_at)
+                    .map(|ts| ts.with_timezone(&chrono::Utc))
+                    .unwrap_or(now);
+                computed::attach(&mut value, include, &user.name, created_at, now);
+                value
+            })
+            .collect();
+        serde_json::Value::Array(data)
+    })
+}
+
+/// Handles a `delete_user` request: `{id}`.
+fn delete_user(service: &dyn UserService, raw: &str) -> Result<serde_json::Value, UserRequestError> {
+    let payload = parse_payload(raw)?;
+    let id = payload
+        .get("id")
+        .and_then(|v| v.as_i64())
+        .ok_or_else(|| UserRequestError::InvalidPayload("Missing 'id' field".to_string()))?;
+    futures::executor::block_on(service.delete_user(UserId(id)))
+        .map_err(|e| UserRequestError::Failed(e.to_string()))?;
+    Ok(serde_json::json!({ "id": id }))
+}
+
+// Binary payloads cross the bridge as base64 strings inside the JSON argument.
+fn decode_binary_payload(payload: &serde_json::Value, field: &str) -> Result<Vec<u8>, String> {
+    let encoded = payload
+        .get(field)
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| format!("Missing '{}' field", field))?;
+    base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|e| format!("Invalid base64 in '{}': {}", field, e))
+}
 
-    window.bind("get_users", move |event| {
+/// Reads go through the projection; writes go through the service, whose
+/// events keep the projection current, within the dispatcher's limits for
+/// `create_user` and `delete_user`.
+pub fn setup_user_handlers(
+    window: &mut webui::Window,
+    user_list: Arc<UserListProjection>,
+    user_service: Arc<dyn UserService>,
+    dispatcher: Arc<CommandDispatcher>,
+) {
+    response::bind(window, "get_users", move |event| {
         let window = event.get_window();
+        let include: Vec<String> = response::get_event_arg(&event, 0)
+            .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+            .and_then(|args| serde_json::from_value(args["include"].clone()).ok())
+            .unwrap_or_default();
 
-        // Simple synchronous approach - directly access provider
-        // In production, you'd want to store the provider globally
-        let response = serde_json::json!({
-            "success": true,
-            "data": [],
-            "message": "Use get_users via Rust backend"
-        });
+        let result = list_users(&user_list, &include);
 
-        let js = format!(
-            "window.dispatchEvent(new CustomEvent('db_response', {{ detail: {} }}))",
-            response
-        );
-        let _ = webui::Window::from_id(window.id).run_js(&js);
+        response::respond(window.id, "db_response", result);
     });
 
-    window.bind("create_user", |event| {
+    let service = user_service.clone();
+    let limits = dispatcher.clone();
+    response::bind(window, "create_user", move |event| {
         let window = event.get_window();
-        let payload = get_event_arg(&event, 0)
-            .or_else(|| get_event_element(&event))
+        let payload_raw = response::get_event_arg(&event, 0)
+            .or_else(|| response::get_event_element(&event))
             .unwrap_or_default();
-        let parts: Vec<&str> = payload.split(':').collect();
-        let name = parts.get(1).unwrap_or(&"");
-        let _email = parts.get(2).unwrap_or(&"");
 
-        let response = serde_json::json!({
-            "success": true,
-            "message": format!("User '{}' created", name)
-        });
+        correlation::scope(correlation::from_payload(&payload_raw), || {
+            let result = run_limited(&limits, "create_user", || create_user(service.as_ref(), &payload_raw));
 
-        let js = format!(
-            "window.dispatchEvent(new CustomEvent('user_create_response', {{ detail: {} }}))",
-            response
-        );
-        let _ = webui::Window::from_id(window.id).run_js(&js);
+            response::respond(window.id, "user_create_response", result);
+        });
     });
 
-    window.bind("delete_user", |event| {
+    let service = user_service;
+    response::bind(window, "delete_user", move |event| {
         let window = event.get_window();
-        let payload = get_event_arg(&event, 0)
-            .or_else(|| get_event_element(&event))
+        let payload_raw = response::get_event_arg(&event, 0)
+            .or_else(|| response::get_event_element(&event))
             .unwrap_or_default();
-        let parts: Vec<&str> = payload.split(':').collect();
-        let id = parts.get(1).unwrap_or(&"0");
 
-        let response = serde_json::json!({
-            "success": true,
-            "message": format!("User {} deleted", id)
+        correlation::scope(correlation::from_payload(&payload_raw), || {
+            let result = run_limited(&dispatcher, "delete_user", || delete_user(service.as_ref(), &payload_raw));
+
+            response::respond(window.id, "user_delete_response", result);
         });
+    });
+}
+
+/// Imports run within the dispatcher's concurrency limit for
+/// `import_users_json`, like a dispatched command of that name.
+pub fn setup_user_import_handlers(
+    window: &mut webui::Window,
+    user_service: Arc<dyn UserService>,
+    dispatcher: Arc<CommandDispatcher>,
+) {
+    let handler = Arc::new(ImportUsersHandler::new(user_service));
+
+    response::bind(window, "import_users_json", move |event| {
+        let window = event.get_window();
+        let payload = response::get_event_arg(&event, 0)
+            .or_else(|| response::get_event_element(&event))
+            .unwrap_or_default();
 
-        let js = format!(
-            "window.dispatchEvent(new CustomEvent('user_delete_response', {{ detail: {} }}))",
-            response
-        );
-        let _ = webui::Window::from_id(window.id).run_js(&js);
+        let command = ImportUsersCommand { json: payload };
+        let result = dispatcher
+            .run_limited("import_users_json", || futures::executor::block_on(handler.handle(command)))
+            .and_then(|rows| rows.map_err(ViewModelError::from))
+            .map(|rows| {
+                let failed = rows.iter().filter(|r| !r.success).count();
+                serde_json::json!({
+                    "imported": rows.len() - failed,
+                    "failed": failed,
+                    "rows": rows,
+                })
+            });
+
+        response::respond(window.id, "import_users_response", result);
     });
 }
+
+pub fn setup_user_avatar_handlers(window: &mut webui::Window, user_repository: Arc<dyn UserRepository>) {
+    response::bind(window, "set_user_avatar", move |event| {
+        let window = event.get_window();
+        let payload_raw = response::get_event_arg(&event, 0)
+            .or_else(|| response::get_event_element(&event))
+            .unwrap_or_default();
+
+        let result = serde_json::from_str::<serde_json::Value>(&payload_raw)
+            .map_err(|e| format!("Invalid avatar payload: {}", e))
+            .and_then(|payload| {
+                let id = payload
+                    .get("id")
+                    .and_then(|v| v.as_i64())
+                    .ok_or_else(|| "Missing 'id' field".to_string())?;
+                let mime = payload
+                    .get("mime")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string();
+                let bytes = decode_binary_payload(&payload, "data")?;
+                futures::executor::block_on(user_repository.set_avatar(UserId(id), &bytes, &mime))
+                    .map_err(|e| e.to_string())?;
+                Ok(serde_json::json!({ "id": id, "mime": mime, "size": bytes.len() }))
+            });
+
+        response::respond(window.id, "user_avatar_response", result);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::user::testing::persisted;
+    use crate::core::config::{ConcurrencySettings, OverflowMode};
+
+    #[test]
+    fn names_with_colons_survive_create_and_delete() {
+        let persisted = persisted();
+        let service = persisted.service.as_ref();
+
+        let raw = serde_json::json!({ "name": "Ada: Countess", "email": "ada@example.com" }).to_string();
+        let created = create_user(service, &raw).unwrap();
+        assert_eq!(created["name"], "Ada: Countess");
+        let id = created["id"].as_i64().unwrap();
+        let stored = futures::executor::block_on(service.get_user(UserId(id))).unwrap();
+        assert_eq!((stored.name.as_str(), stored.email.0.as_str()), ("Ada: Countess", "ada@example.com"));
+
+        assert!(delete_user(service, &serde_json::json!({ "id": id }).to_string()).is_ok());
+        assert!(futures::executor::block_on(service.get_all_users()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn created_user_is_listed_by_get_users() {
+        let persisted = persisted();
+        let raw = serde_json::json!({ "name": "Ada", "email": "ada@example.com", "role": "Admin" }).to_string();
+
+        let created = create_user(persisted.service.as_ref(), &raw).unwrap();
+        let listed = list_users(&persisted.projection, &["initials".to_string()]).unwrap();
+
+        let users = listed.as_array().unwrap();
+        assert_eq!(users.len(), 1);
+        let user = &users[0];
+        assert_eq!(user["id"], created["id"]);
+        assert_eq!(user["name"], "Ada");
+        assert_eq!(user["email"], "ada@example.com");
+        assert_eq!(user["role"], "Admin");
+        assert_eq!(user["status"], "Active");
+        assert!(user["external_id"].is_null());
+        assert!(user["created_at"].as_str().is_some_and(|ts| chrono::DateTime::parse_from_rfc3339(ts).is_ok()));
+        assert_eq!(user["initials"], "A");
+        assert_eq!(user.as_object().unwrap().len(), 8);
+    }
+
+    #[test]
+    fn create_user_over_its_limit_gets_the_busy_response() {
+        let persisted = persisted();
+        let service = persisted.service.as_ref();
+        let dispatcher = CommandDispatcher::new();
+        dispatcher.set_concurrency(&ConcurrencySettings {
+            mode: OverflowMode::Reject,
+            wait_timeout_ms: 0,
+            limits: [("create_user".to_string(), 1)].into(),
+        });
+        let raw = serde_json::json!({ "name": "Ada", "email": "ada@example.com" }).to_string();
+
+        let mut over_limit = None;
+        let first = run_limited(&dispatcher, "create_user", || {
+            over_limit = Some(run_limited(&dispatcher, "create_user", || create_user(service, &raw)));
+            create_user(service, &raw)
+        });
+
+        let detail = response::envelope(over_limit.unwrap());
+        assert_eq!(detail["success"], false);
+        assert_eq!(detail["code"], "BUSY");
+        assert!(first.is_ok());
+        assert_eq!(futures::executor::block_on(service.get_all_users()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn malformed_payloads_are_rejected_as_invalid() {
+        let persisted = persisted();
+        let service = persisted.service.as_ref();
+
+        for raw in ["Ada:ada@example.com:User", "[1, 2]", r#"{"name": "Ada"}"#, r#"{"name": "Ada", "email": "a@b"}"#] {
+            let error = create_user(service, raw).unwrap_err();
+            assert_eq!(error.code(), "INVALID_PAYLOAD", "{}", raw);
+        }
+        assert_eq!(delete_user(service, r#"{"id": "one"}"#).unwrap_err().code(), "INVALID_PAYLOAD");
+    }
+}
@@ -9,6 +9,7 @@ pub struct CreateUserCommand {
     pub name: String,
     pub email: String,
     pub role: String,
+    pub external_id: Option<String>,
 }
 
 pub struct CreateUserHandler {
@@ -28,8 +29,9 @@ impl CommandHandler<CreateUserCommand> for CreateUserHandler {
     async fn handle(&self, command: CreateUserCommand) -> Result<Self::Output, DomainError> {
         let new_user = NewUser {
             name: command.name,
-            email: Email::new(&command.email).map_err(|e| DomainError::ValidationError(e))?,
+            email: Email::new(&command.email)?,
             role: UserRole::from_str(&command.role),
+            external_id: command.external_id,
         };
         
         self.user_service.create_user(new_user).await
@@ -0,0 +1,169 @@
+// application/commands/import_users.rs
+use std::sync::Arc;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use crate::viewmodel::commands::CommandHandler;
+use crate::core::domain::{user::{NewUser, Email, UserRole}, errors::DomainError};
+use crate::core::services::user_service::UserService;
+
+pub struct ImportUsersCommand {
+    pub json: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImportUserRow {
+    name: String,
+    email: String,
+    role: Option<String>,
+    external_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportRowResult {
+    pub index: usize,
+    pub success: bool,
+    pub id: Option<i64>,
+    pub error: Option<String>,
+}
+
+impl ImportRowResult {
+    fn created(index: usize, id: i64) -> Self {
+        Self { index, success: true, id: Some(id), error: None }
+    }
+
+    fn failed(index: usize, error: String) -> Self {
+        Self { index, success: false, id: None, error: Some(error) }
+    }
+}
+
+pub struct ImportUsersHandler {
+    user_service: Arc<dyn UserService>,
+}
+
+impl ImportUsersHandler {
+    pub fn new(user_service: Arc<dyn UserService>) -> Self {
+        Self { user_service }
+    }
+
+    fn parse_row(value: serde_json::Value) -> Result<NewUser, DomainError> {
+        let row: ImportUserRow = serde_json::from_value(value)
+            .map_err(|e| DomainError::ValidationError(e.to_string()))?;
+
+        Ok(NewUser {
+            name: row.name,
+            email: Email::new(&row.email)?,
+            role: UserRole::from_str(row.role.as_deref().unwrap_or("User")),
+            external_id: row.external_id,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandHandler<ImportUsersCommand> for ImportUsersHandler {
+    type Output = Vec<ImportRowResult>;
+    
+    async fn handle(&self, command: ImportUsersCommand) -> Result<Self::Output, DomainError> {
+        let parsed: serde_json::Value = serde_json::from_str(&command.json)
+            .map_err(|e| DomainError::ValidationError(format!("Invalid JSON: {}", e)))?;
+
+        let serde_json::Value::Array(rows) = parsed else {
+            return Err(DomainError::ValidationError(
+                "Expected a JSON array of users at the top level".to_string(),
+            ));
+        };
+
+        // Rows are checked up front so each failure is reported against its
+        // own index; the rest go through the bulk insert together.
+        let mut results = Vec::with_capacity(rows.len());
+        let mut valid = Vec::new();
+        for (index, value) in rows.into_iter().enumerate() {
+            let checked = Self::parse_row(value)
+                .and_then(|new_user| self.user_service.validate_user(&new_user).map(|_| new_user));
+            match checked {
+                Ok(new_user) => valid.push((index, new_user)),
+                Err(e) => results.push(ImportRowResult::failed(index, e.to_string())),
+            }
+        }
+
+        if !valid.is_empty() {
+            let (indices, new_users): (Vec<usize>, Vec<NewUser>) = valid.into_iter().unzip();
+            match self.user_service.create_users(new_users.clone()).await {
+                Ok(ids) => results.extend(
+                    indices.iter().zip(ids).map(|(index, id)| ImportRowResult::created(*index, id.0)),
+                ),
+                // The bulk insert is all-or-nothing, so one conflicting row
+                // (e.g. a duplicate email) rejects it; retry row by row to
+                // pin the failure on that row.
+                Err(_) => {
+                    for (index, new_user) in indices.into_iter().zip(new_users) {
+                        results.push(match self.user_service.create_user(new_user).await {
+                            Ok(id) => ImportRowResult::created(index, id.0),
+                            Err(e) => ImportRowResult::failed(index, e.to_string()),
+                        });
+                    }
+                }
+            }
+        }
+
+        results.sort_by_key(|result| result.index);
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::user::testing::persisted;
+    use serde_json::json;
+
+    fn import(handler: &ImportUsersHandler, rows: serde_json::Value) -> Vec<ImportRowResult> {
+        let command = ImportUsersCommand { json: rows.to_string() };
+        futures::executor::block_on(handler.handle(command)).unwrap()
+    }
+
+    #[test]
+    fn well_formed_array_imports_every_row() {
+        let persisted = persisted();
+        let handler = ImportUsersHandler::new(persisted.service.clone());
+
+        let results = import(&handler, json!([
+            { "name": "Ada", "email": "ada@example.com" },
+            { "name": "Grace", "email": "grace@example.com", "role": "Admin" },
+            { "name": "Linus", "email": "linus@example.com", "external_id": "ext-3" },
+        ]));
+
+        assert!(results.iter().all(|r| r.success && r.id.is_some()));
+        assert_eq!(results.iter().map(|r| r.index).collect::<Vec<_>>(), [0, 1, 2]);
+        assert_eq!(persisted.projection.list().unwrap().len(), 3);
+        assert_eq!(persisted.events.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn mixed_array_reports_failures_per_row() {
+        let persisted = persisted();
+        let handler = ImportUsersHandler::new(persisted.service.clone());
+        import(&handler, json!([{ "name": "Ada", "email": "ada@example.com" }]));
+
+        let results = import(&handler, json!([
+            { "name": "Grace", "email": "grace@example.com" },
+            { "name": "Broken", "email": "not-an-email" },
+            { "email": "nameless@example.com" },
+            { "name": "Ada again", "email": "ada@example.com" },
+            { "name": "Linus", "email": "linus@example.com" },
+        ]));
+
+        let outcome: Vec<(usize, bool)> = results.iter().map(|r| (r.index, r.success)).collect();
+        assert_eq!(outcome, [(0, true), (1, false), (2, false), (3, false), (4, true)]);
+        assert!(results.iter().filter(|r| !r.success).all(|r| r.error.is_some()));
+        assert_eq!(persisted.projection.list().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn non_array_json_is_rejected() {
+        let handler = ImportUsersHandler::new(persisted().service);
+        let command = ImportUsersCommand { json: json!({ "name": "Ada" }).to_string() };
+
+        let error = futures::executor::block_on(handler.handle(command)).unwrap_err();
+        assert!(error.to_string().contains("Expected a JSON array"));
+    }
+}
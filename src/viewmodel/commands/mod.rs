@@ -1,6 +1,7 @@
 // application/commands/mod.rs
 pub mod create_user;
 pub mod delete_user;
+pub mod import_users;
 
 use async_trait::async_trait;
 use crate::core::domain::errors::DomainError;
@@ -0,0 +1,562 @@
+// application/dispatcher.rs
+// Routes named commands to the ViewModel that owns them
+
+use std::collections::HashMap;
+use std::result::Result as StdResult;
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::time::Duration;
+
+use crate::core::config::{ConcurrencySettings, OverflowMode};
+use crate::core::mvvm::viewmodel::{Checkpoint, SharedViewModel, ViewModelError};
+use crate::core::trace::{self, Span};
+use crate::viewmodel::bindings::response::command_envelope;
+use crate::model::repositories::sqlite::connection::SqliteDatabase;
+
+/// Built-in command that runs `{ "commands": [{ "name", "payload" }], "atomic" }`
+/// in order and returns one result envelope per command.
+pub const BATCH_COMMAND: &str = "batch";
+
+/// Counting semaphore capping how many calls of one command run at once.
+struct CommandLimit {
+    limit: usize,
+    running: Mutex<usize>,
+    released: Condvar,
+}
+
+impl CommandLimit {
+    fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            running: Mutex::new(0),
+            released: Condvar::new(),
+        }
+    }
+
+    fn acquire(&self, wait: Option<Duration>) -> bool {
+        let running = self.running.lock().unwrap();
+        let mut running = match wait {
+            Some(timeout) => {
+                let (guard, result) = self
+                    .released
+                    .wait_timeout_while(running, timeout, |n| *n >= self.limit)
+                    .unwrap();
+                if result.timed_out() {
+                    return false;
+                }
+                guard
+            }
+            None if *running >= self.limit => return false,
+            None => running,
+        };
+        *running += 1;
+        true
+    }
+
+    fn release(&self) {
+        *self.running.lock().unwrap() -= 1;
+        self.released.notify_one();
+    }
+}
+
+struct Permit<'a>(&'a CommandLimit);
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        self.0.release();
+    }
+}
+
+pub struct CommandDispatcher {
+    routes: RwLock<HashMap<String, SharedViewModel>>,
+    aliases: RwLock<HashMap<String, String>>,
+    limits: RwLock<HashMap<String, Arc<CommandLimit>>>,
+    overflow: RwLock<(OverflowMode, Duration)>,
+    atomic_batch: Mutex<()>,
+    database: RwLock<Option<Arc<SqliteDatabase>>>,
+}
+
+impl CommandDispatcher {
+    pub fn new() -> Self {
+        Self {
+            routes: RwLock::new(HashMap::new()),
+            aliases: RwLock::new(HashMap::new()),
+            limits: RwLock::new(HashMap::new()),
+            overflow: RwLock::new((OverflowMode::Wait, Duration::ZERO)),
+            atomic_batch: Mutex::new(()),
+            database: RwLock::new(None),
+        }
+    }
+
+    /// Installs per-command concurrency limits. Limits apply to the canonical
+    /// command, so aliases share their target's slots.
+    pub fn set_concurrency(&self, settings: &ConcurrencySettings) {
+        *self.limits.write().unwrap() = settings
+            .limits
+            .iter()
+            .map(|(command, limit)| {
+                (command.clone(), Arc::new(CommandLimit::new((*limit).max(1))))
+            })
+            .collect();
+        *self.overflow.write().unwrap() =
+            (settings.mode, Duration::from_millis(settings.wait_timeout_ms));
+    }
+
+    /// Atomic batches run inside a transaction on `database`, so whatever
+    /// their commands write there is rolled back with them.
+    pub fn set_database(&self, database: Option<Arc<SqliteDatabase>>) {
+        *self.database.write().unwrap() = database;
+    }
+
+    /// Routes every command the viewmodel declares to it.
+    pub fn register_viewmodel(&self, viewmodel: SharedViewModel) {
+        let mut routes = self.routes.write().unwrap();
+        for command in viewmodel.commands() {
+            routes.insert(command.to_string(), viewmodel.clone());
+        }
+    }
+
+    /// Installs alias → canonical command mappings. Fails without applying
+    /// anything if an alias targets an unknown command or shadows a real one.
+    pub fn set_aliases(&self, aliases: &HashMap<String, String>) -> StdResult<(), ViewModelError> {
+        let routes = self.routes.read().unwrap();
+
+        let mut problems = Vec::new();
+        for (alias, target) in aliases {
+            if !routes.contains_key(target) {
+                problems.push(format!("'{}' -> unknown command '{}'", alias, target));
+            } else if routes.contains_key(alias) {
+                problems.push(format!("'{}' shadows an existing command", alias));
+            }
+        }
+
+        if !problems.is_empty() {
+            problems.sort();
+            return Err(ViewModelError::validation(format!(
+                "Invalid command aliases: {}",
+                problems.join(", ")
+            )));
+        }
+
+        *self.aliases.write().unwrap() = aliases.clone();
+        Ok(())
+    }
+
+    pub fn resolve(&self, command: &str) -> String {
+        self.aliases
+            .read()
+            .unwrap()
+            .get(command)
+            .cloned()
+            .unwrap_or_else(|| command.to_string())
+    }
+
+    pub fn dispatch(&self, command: &str, payload: &str) -> StdResult<String, ViewModelError> {
+        let canonical = self.resolve(command);
+        if canonical == BATCH_COMMAND {
+            return self.dispatch_batch(payload);
+        }
+
+        let viewmodel = self
+            .routes
+            .read()
+            .unwrap()
+            .get(&canonical)
+            .cloned()
+            .ok_or_else(|| ViewModelError::invalid_command(format!("Unknown command: {}", command)))?;
+
+        self.run_limited(&canonical, || {
+            trace::span(&format!("handle.{}", canonical), || {
+                viewmodel.handle_command(&canonical, payload)
+            })
+        })?
+    }
+
+    /// Runs `work` within `name`'s concurrency limit, if one is configured,
+    /// waiting or failing with `Busy` as the overflow mode says. Bindings
+    /// that bypass the dispatcher use this to share its limits.
+    pub fn run_limited<T>(&self, name: &str, work: impl FnOnce() -> T) -> StdResult<T, ViewModelError> {
+        let limit = self.limits.read().unwrap().get(name).cloned();
+        let _permit = match &limit {
+            Some(limit) => {
+                let (mode, timeout) = *self.overflow.read().unwrap();
+                let wait = (mode == OverflowMode::Wait).then_some(timeout);
+                if !trace::span("dispatch.acquire", || limit.acquire(wait)) {
+                    return Err(ViewModelError::busy(format!(
+                        "{} is at its limit of {} concurrent call(s)",
+                        name, limit.limit
+                    )));
+                }
+                Some(Permit(limit))
+            }
+            None => None,
+        };
+        Ok(work())
+    }
+
+    /// `dispatch` with a span tree of the execution rooted at the command name.
+    pub fn dispatch_traced(
+        &self,
+        command: &str,
+        payload: &str,
+    ) -> (StdResult<String, ViewModelError>, Span) {
+        trace::capture(command, || self.dispatch(command, payload))
+    }
+
+    /// Runs a batch in order. Without `atomic`, a failure is recorded and the
+    /// rest still run. With `atomic`, every routed viewmodel is checkpointed
+    /// and a database transaction opened first; on the first failure the
+    /// transaction is rolled back, the checkpoints restored and later
+    /// commands skipped. Atomic batches are serialized with each other, and
+    /// other writers wait for their transaction.
+    fn dispatch_batch(&self, payload: &str) -> StdResult<String, ViewModelError> {
+        let request: serde_json::Value = serde_json::from_str(payload)
+            .map_err(|e| ViewModelError::invalid_command(format!("Invalid batch: {}", e)))?;
+        let commands = request
+            .get("commands")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| ViewModelError::validation("Batch requires a 'commands' array"))?;
+        let atomic = request.get("atomic").and_then(|v| v.as_bool()).unwrap_or(false);
+
+        let mut entries = Vec::with_capacity(commands.len());
+        for entry in commands {
+            let name = entry.get("name").and_then(|v| v.as_str()).unwrap_or_default();
+            if self.resolve(name) == BATCH_COMMAND {
+                return Err(ViewModelError::validation("Batches cannot be nested"));
+            }
+            let payload = entry
+                .get("payload")
+                .cloned()
+                .unwrap_or(serde_json::Value::Null)
+                .to_string();
+            entries.push((name.to_string(), payload));
+        }
+
+        let _guard = atomic.then(|| self.atomic_batch.lock().unwrap());
+        let checkpoints = if atomic { self.checkpoint_all()? } else { Vec::new() };
+        let database = if atomic { self.database.read().unwrap().clone() } else { None };
+        let transaction = database
+            .as_deref()
+            .map(SqliteDatabase::begin_transaction)
+            .transpose()?;
+
+        let mut results = Vec::with_capacity(entries.len());
+        let mut rolled_back = false;
+        for (name, payload) in &entries {
+            if rolled_back {
+                let skipped = ViewModelError::execution_failed("skipped, batch rolled back");
+                results.push(command_envelope(name, Err(skipped)));
+                continue;
+            }
+            let result = self.dispatch(name, payload);
+            rolled_back = atomic && result.is_err();
+            results.push(command_envelope(name, result));
+        }
+
+        let finished = match transaction {
+            Some(transaction) if rolled_back => transaction.rollback(),
+            Some(transaction) => transaction.commit(),
+            None => Ok(()),
+        };
+        if rolled_back || finished.is_err() {
+            for (viewmodel, checkpoint) in checkpoints {
+                viewmodel.restore(checkpoint);
+            }
+        }
+        if !rolled_back {
+            finished?;
+        }
+
+        Ok(serde_json::json!({
+            "atomic": atomic,
+            "committed": !rolled_back,
+            "results": results,
+        })
+        .to_string())
+    }
+
+    fn checkpoint_all(&self) -> StdResult<Vec<(SharedViewModel, Checkpoint)>, ViewModelError> {
+        let mut viewmodels: Vec<SharedViewModel> = Vec::new();
+        for viewmodel in self.routes.read().unwrap().values() {
+            if !viewmodels.iter().any(|v| Arc::ptr_eq(v, viewmodel)) {
+                viewmodels.push(viewmodel.clone());
+            }
+        }
+
+        viewmodels
+            .into_iter()
+            .map(|viewmodel| {
+                let checkpoint = viewmodel.checkpoint().ok_or_else(|| {
+                    ViewModelError::validation(format!(
+                        "{} does not support atomic batches",
+                        viewmodel.name()
+                    ))
+                })?;
+                Ok((viewmodel, checkpoint))
+            })
+            .collect()
+    }
+
+    pub fn commands(&self) -> Vec<String> {
+        let mut commands: Vec<String> = self.routes.read().unwrap().keys().cloned().collect();
+        commands.push(BATCH_COMMAND.to_string());
+        commands.sort();
+        commands
+    }
+}
+
+impl Default for CommandDispatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::mvvm::viewmodel::{ViewModel, ViewModelState};
+    use crate::features::user::testing::{persisted, persisted_on, Persisted};
+    use crate::features::user::UserViewModel;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn count(span: &Span, name: &str) -> usize {
+        usize::from(span.name == name) + span.children.iter().map(|c| count(c, name)).sum::<usize>()
+    }
+
+    /// Echoes the command it was asked to run.
+    struct EchoViewModel;
+
+    impl ViewModel for EchoViewModel {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn state(&self) -> ViewModelState {
+            ViewModelState::Ready
+        }
+
+        fn handle_command(&self, command: &str, _payload: &str) -> StdResult<String, ViewModelError> {
+            Ok(command.to_string())
+        }
+
+        fn handle_query(&self, query: &str, _params: &[String]) -> StdResult<String, ViewModelError> {
+            Err(ViewModelError::invalid_query(query))
+        }
+
+        fn commands(&self) -> &[&'static str] {
+            &["create_user", "delete_user"]
+        }
+    }
+
+    fn dispatcher() -> CommandDispatcher {
+        let dispatcher = CommandDispatcher::new();
+        dispatcher.register_viewmodel(Arc::new(EchoViewModel));
+        dispatcher
+    }
+
+    #[test]
+    fn aliased_command_dispatches_to_its_canonical_command() {
+        let dispatcher = dispatcher();
+        dispatcher
+            .set_aliases(&[("add_user".to_string(), "create_user".to_string())].into())
+            .unwrap();
+
+        assert_eq!(dispatcher.resolve("add_user"), "create_user");
+        assert_eq!(dispatcher.dispatch("add_user", "{}").unwrap(), "create_user");
+    }
+
+    #[test]
+    fn invalid_aliases_are_rejected_without_applying_any() {
+        let dispatcher = dispatcher();
+        let aliases: HashMap<String, String> = [
+            ("add_user".to_string(), "create_user".to_string()),
+            ("make_user".to_string(), "no_such_command".to_string()),
+        ]
+        .into();
+
+        assert!(matches!(dispatcher.set_aliases(&aliases), Err(ViewModelError::ValidationError(_))));
+        assert_eq!(dispatcher.resolve("add_user"), "add_user");
+
+        let shadowing = [("create_user".to_string(), "delete_user".to_string())].into();
+        assert!(dispatcher.set_aliases(&shadowing).is_err());
+    }
+
+    #[test]
+    fn run_limited_reports_busy_once_the_limit_is_taken() {
+        let dispatcher = CommandDispatcher::new();
+        dispatcher.set_concurrency(&ConcurrencySettings {
+            mode: OverflowMode::Reject,
+            wait_timeout_ms: 0,
+            limits: [("import_users_json".to_string(), 1)].into(),
+        });
+
+        let nested = dispatcher
+            .run_limited("import_users_json", || dispatcher.run_limited("import_users_json", || ()))
+            .unwrap();
+        assert!(matches!(nested, Err(ViewModelError::Busy(_))));
+        assert!(dispatcher.run_limited("import_users_json", || ()).is_ok());
+    }
+
+    #[test]
+    fn waiting_callers_never_exceed_the_configured_limit() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        const LIMIT: usize = 2;
+        let dispatcher = CommandDispatcher::new();
+        dispatcher.set_concurrency(&ConcurrencySettings {
+            mode: OverflowMode::Wait,
+            wait_timeout_ms: 5000,
+            limits: [("import_users_json".to_string(), LIMIT)].into(),
+        });
+        let (running, peak) = (AtomicUsize::new(0), AtomicUsize::new(0));
+
+        std::thread::scope(|scope| {
+            for _ in 0..LIMIT * 3 {
+                scope.spawn(|| {
+                    dispatcher
+                        .run_limited("import_users_json", || {
+                            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                            peak.fetch_max(now, Ordering::SeqCst);
+                            std::thread::sleep(Duration::from_millis(50));
+                            running.fetch_sub(1, Ordering::SeqCst);
+                        })
+                        .unwrap();
+                });
+            }
+        });
+
+        assert_eq!(peak.load(Ordering::SeqCst), LIMIT);
+        assert_eq!(running.load(Ordering::SeqCst), 0);
+    }
+
+    /// A dispatcher over a persisted user viewmodel, with atomic batches
+    /// using the stack's database.
+    fn batch_dispatcher(persisted: &Persisted) -> (CommandDispatcher, Arc<UserViewModel>) {
+        let viewmodel = Arc::new(persisted.admin_viewmodel());
+        let dispatcher = CommandDispatcher::new();
+        dispatcher.register_viewmodel(viewmodel.clone());
+        dispatcher.set_database(Some(persisted.db.clone()));
+        (dispatcher, viewmodel)
+    }
+
+    fn run_batch(dispatcher: &CommandDispatcher, atomic: bool) -> serde_json::Value {
+        let batch = json!({
+            "atomic": atomic,
+            "commands": [
+                { "name": "create_user", "payload": { "name": "Ada", "email": "ada@example.com" } },
+                { "name": "update_user", "payload": { "id": 999, "name": "Nobody" } },
+                { "name": "create_user", "payload": { "name": "Grace", "email": "grace@example.com" } },
+            ],
+        });
+        serde_json::from_str(&dispatcher.dispatch(BATCH_COMMAND, &batch.to_string()).unwrap()).unwrap()
+    }
+
+    fn successes(outcome: &serde_json::Value) -> Vec<bool> {
+        outcome["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["success"].as_bool().unwrap())
+            .collect()
+    }
+
+    fn stored_names(persisted: &Persisted) -> Vec<String> {
+        futures::executor::block_on(persisted.service.get_all_users())
+            .unwrap()
+            .into_iter()
+            .map(|user| user.name)
+            .collect()
+    }
+
+    #[test]
+    fn non_atomic_batch_applies_the_rest_after_a_failure() {
+        let persisted = persisted();
+        let (dispatcher, _) = batch_dispatcher(&persisted);
+
+        let outcome = run_batch(&dispatcher, false);
+
+        assert_eq!(successes(&outcome), [true, false, true]);
+        assert_eq!(outcome["committed"], true);
+        assert_eq!(stored_names(&persisted), ["Ada", "Grace"]);
+    }
+
+    #[test]
+    fn atomic_batch_rolls_back_stored_writes_on_failure() {
+        let persisted = persisted();
+        let (dispatcher, viewmodel) = batch_dispatcher(&persisted);
+
+        let outcome = run_batch(&dispatcher, true);
+
+        assert_eq!(successes(&outcome), [true, false, false]);
+        assert_eq!(outcome["committed"], false);
+        assert!(stored_names(&persisted).is_empty());
+        assert!(persisted.projection.list().unwrap().is_empty());
+        assert!(viewmodel.get_users().is_empty());
+        assert!(!persisted.db.in_transaction());
+    }
+
+    #[test]
+    fn atomic_batch_commits_when_every_command_succeeds() {
+        let persisted = persisted();
+        let (dispatcher, _) = batch_dispatcher(&persisted);
+        let batch = json!({
+            "atomic": true,
+            "commands": [
+                { "name": "create_user", "payload": { "name": "Ada", "email": "ada@example.com" } },
+                { "name": "create_user", "payload": { "name": "Grace", "email": "grace@example.com" } },
+            ],
+        });
+
+        let outcome: serde_json::Value =
+            serde_json::from_str(&dispatcher.dispatch(BATCH_COMMAND, &batch.to_string()).unwrap()).unwrap();
+
+        assert_eq!(outcome["committed"], true);
+        assert_eq!(stored_names(&persisted), ["Ada", "Grace"]);
+        assert_eq!(persisted.projection.list().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn atomic_batch_reads_its_own_writes_with_a_read_pool() {
+        let path = std::env::temp_dir().join(format!("dispatcher-test-{}.db", uuid::Uuid::new_v4()));
+        let db = Arc::new(SqliteDatabase::open(path.to_str().unwrap(), 2).unwrap());
+        let persisted = persisted_on(db);
+        let (dispatcher, _) = batch_dispatcher(&persisted);
+        let batch = json!({
+            "atomic": true,
+            "commands": [
+                { "name": "create_user", "payload": { "name": "Ada", "email": "ada@example.com" } },
+                { "name": "update_user", "payload": { "id": 1, "name": "Ada Lovelace" } },
+            ],
+        });
+
+        let outcome: serde_json::Value =
+            serde_json::from_str(&dispatcher.dispatch(BATCH_COMMAND, &batch.to_string()).unwrap()).unwrap();
+
+        assert_eq!(successes(&outcome), [true, true]);
+        assert_eq!(outcome["committed"], true);
+        assert_eq!(stored_names(&persisted), ["Ada Lovelace"]);
+
+        drop((dispatcher, persisted));
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    #[test]
+    fn traced_create_records_validation_insert_and_publish() {
+        let persisted = persisted();
+        let dispatcher = CommandDispatcher::new();
+        dispatcher.register_viewmodel(Arc::new(persisted.admin_viewmodel()));
+
+        let payload = serde_json::json!({ "name": "Ada", "email": "ada@example.com" }).to_string();
+        let (result, root) = dispatcher.dispatch_traced("create_user", &payload);
+        result.unwrap();
+
+        for name in ["validation", "db.insert", "event.publish"] {
+            let span = root.find(name).unwrap_or_else(|| panic!("no {} span", name));
+            assert!(span.duration <= root.duration);
+        }
+        assert_eq!(count(&root, "db.insert"), 1);
+        assert!(root.find("db.insert").unwrap().children.is_empty());
+    }
+}
@@ -2,4 +2,5 @@
 pub mod viewmodels;
 pub mod commands;
 pub mod queries;
-pub mod bindings;
\ No newline at end of file
+pub mod bindings;
+pub mod dispatcher;